@@ -4,7 +4,7 @@ use crate::error::SniperError;
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_sdk::pubkey::Pubkey;
 
-#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 pub struct BondingCurveAccount {
     pub discriminator: u64,
     pub virtual_token_reserves: u64,
@@ -17,6 +17,31 @@ pub struct BondingCurveAccount {
 }
 
 impl BondingCurveAccount {
+    /// Bytes of the fields known here; live accounts may be longer
+    pub const LEN: usize = 8 * 6 + 1 + 32;
+
+    /// Byte offset of `creator` in account data
+    pub const CREATOR_OFFSET: usize = 8 * 6 + 1;
+
+    /// Decode the known fields from the front of account data, ignoring any fields Pump
+    /// has appended since
+    pub fn from_account_data(data: &[u8]) -> Result<Self, SniperError> {
+        if data.len() < Self::LEN {
+            return Err(SniperError::SerializationError(format!(
+                "Bonding curve account is {} bytes, expected at least {}",
+                data.len(),
+                Self::LEN
+            )));
+        }
+        Self::deserialize(&mut &data[..]).map_err(|e| {
+            SniperError::SerializationError(format!(
+                "Failed to deserialize bonding curve from {} bytes: {}",
+                data.len(),
+                e
+            ))
+        })
+    }
+
     pub fn get_market_cap_sol(&self) -> u64 {
         if self.virtual_token_reserves == 0 {
             return 0;
@@ -48,6 +73,70 @@ impl BondingCurveAccount {
         })
     }
 
+    /// Tokens out for `sol_amount` once the protocol and creator fees, charged on top of
+    /// the curve input, are taken out of it
+    pub fn get_buy_price_with_fees(
+        &self,
+        sol_amount: u64,
+        fee_basis_points: u64,
+        creator_fee_basis_points: u64,
+    ) -> Result<u64, SniperError> {
+        let total_fee_bps =
+            10_000u128 + fee_basis_points as u128 + creator_fee_basis_points as u128;
+        let effective_sol = (sol_amount as u128 * 10_000 / total_fee_bps) as u64;
+        self.get_buy_price(effective_sol)
+    }
+
+    /// SOL needed to receive at least `token_amount` tokens (inverse of `get_buy_price`)
+    pub fn get_buy_cost_for_tokens(&self, token_amount: u64) -> Result<u64, SniperError> {
+        if self.complete {
+            return Err(SniperError::BondingCurveComplete);
+        }
+
+        if token_amount == 0 {
+            return Ok(0);
+        }
+
+        if token_amount > self.real_token_reserves || token_amount >= self.virtual_token_reserves {
+            return Err(SniperError::InsufficientLiquidity(format!(
+                "requested {} tokens but only {} remain on the curve",
+                token_amount, self.real_token_reserves
+            )));
+        }
+
+        // smallest input where n / (vs + x) + 1 <= vt - tokens
+        let n: u128 = (self.virtual_sol_reserves as u128) * (self.virtual_token_reserves as u128);
+        let remaining: u128 = (self.virtual_token_reserves as u128) - (token_amount as u128);
+        let required: u128 = n / remaining + 1;
+        let sol_amount = required.saturating_sub(self.virtual_sol_reserves as u128);
+
+        u64::try_from(sol_amount).map_err(|_| {
+            SniperError::InsufficientLiquidity(format!(
+                "cost of {} tokens overflows u64",
+                token_amount
+            ))
+        })
+    }
+
+    /// SOL, fees included, needed to receive at least `token_amount` tokens (inverse of
+    /// `get_buy_price_with_fees`)
+    pub fn get_buy_cost_for_tokens_with_fees(
+        &self,
+        token_amount: u64,
+        fee_basis_points: u64,
+        creator_fee_basis_points: u64,
+    ) -> Result<u64, SniperError> {
+        let cost = self.get_buy_cost_for_tokens(token_amount)? as u128;
+        let total_fee_bps =
+            10_000u128 + fee_basis_points as u128 + creator_fee_basis_points as u128;
+        u64::try_from((cost * total_fee_bps).div_ceil(10_000)).map_err(|_| {
+            SniperError::InsufficientLiquidity(format!(
+                "cost of {} tokens overflows u64",
+                token_amount
+            ))
+        })
+    }
+
     pub fn get_sell_price(
         &self,
         token_amount: u64,
@@ -68,6 +157,33 @@ impl BondingCurveAccount {
         Ok((n - fee) as u64)
     }
 
+    /// Market cap rise, in percent, at which selling `token_amount` after the sell fee
+    /// returns `cost_lamports`; negative when already above break-even. Along the constant
+    /// product curve a market cap multiple of m scales SOL reserves by sqrt(m) and token
+    /// reserves by 1/sqrt(m), so `get_sell_price` at that point is solved for sqrt(m)
+    pub fn break_even_move_percent(
+        &self,
+        token_amount: u64,
+        cost_lamports: u64,
+        fee_basis_points: u64,
+    ) -> Option<f64> {
+        if token_amount == 0 || self.virtual_sol_reserves == 0 || fee_basis_points >= 10_000 {
+            return None;
+        }
+
+        let tokens = token_amount as f64;
+        let cost = cost_lamports as f64;
+        let vs = self.virtual_sol_reserves as f64;
+        let vt = self.virtual_token_reserves as f64;
+        let net = 1.0 - fee_basis_points as f64 / 10_000.0;
+
+        // net * tokens * vs * s / (vt / s + tokens) = cost, a quadratic in s
+        let a = net * tokens * vs;
+        let s = (cost * tokens + (cost * cost * tokens * tokens + 4.0 * a * cost * vt).sqrt())
+            / (2.0 * a);
+        Some((s * s - 1.0) * 100.0)
+    }
+
     pub fn has_sufficient_liquidity(&self, sol_amount: u64) -> bool {
         !self.complete && sol_amount <= self.real_sol_reserves
     }
@@ -86,6 +202,31 @@ impl BondingCurveAccount {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_trailing_bytes_tolerated() {
+        let curve = create_test_bonding_curve();
+        let mut data = borsh::to_vec(&curve).unwrap();
+        assert_eq!(data.len(), BondingCurveAccount::LEN);
+
+        // a field appended by a program upgrade
+        data.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 0, 7]);
+        assert_eq!(
+            BondingCurveAccount::from_account_data(&data).unwrap(),
+            curve
+        );
+
+        let truncated = &data[..BondingCurveAccount::LEN - 1];
+        let err = BondingCurveAccount::from_account_data(truncated).unwrap_err();
+        assert!(matches!(err, SniperError::SerializationError(_)));
+        assert!(
+            err.to_string()
+                .contains("Bonding curve account is 80 bytes, expected at least 81"),
+            "{}",
+            err
+        );
+        assert!(BondingCurveAccount::from_account_data(&[]).is_err());
+    }
+
     fn create_test_bonding_curve() -> BondingCurveAccount {
         BondingCurveAccount {
             discriminator: 1,
@@ -99,6 +240,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_buy_price_with_fees() {
+        let curve = create_test_bonding_curve();
+        let sol_amount = 1_000_000_000;
+        let without_fees = curve.get_buy_price(sol_amount).unwrap();
+
+        assert_eq!(
+            curve.get_buy_price_with_fees(sol_amount, 0, 0).unwrap(),
+            without_fees
+        );
+
+        // 1% protocol + 0.05% creator fee come off the curve input
+        let with_fees = curve.get_buy_price_with_fees(sol_amount, 100, 5).unwrap();
+        assert!(with_fees < without_fees);
+        assert_eq!(with_fees, curve.get_buy_price(989_609_104).unwrap());
+        assert!(with_fees < curve.get_buy_price_with_fees(sol_amount, 100, 0).unwrap());
+    }
+
     #[test]
     fn test_market_cap_calculation() {
         let curve = create_test_bonding_curve();
@@ -113,6 +272,48 @@ mod tests {
         assert!(tokens <= curve.real_token_reserves);
     }
 
+    #[test]
+    fn test_buy_cost_round_trip() {
+        let curve = create_test_bonding_curve();
+
+        for tokens in [1, 1_000, 10_000_000, 250_000_000, 799_999_999] {
+            let cost = curve.get_buy_cost_for_tokens(tokens).unwrap();
+            assert!(curve.get_buy_price(cost).unwrap() >= tokens);
+            // one lamport less must not be enough
+            assert!(curve.get_buy_price(cost - 1).unwrap() < tokens);
+        }
+    }
+
+    #[test]
+    fn test_buy_cost_with_fees_round_trip() {
+        let curve = create_test_bonding_curve();
+
+        for tokens in [1_000, 10_000_000, 250_000_000] {
+            let cost = curve
+                .get_buy_cost_for_tokens_with_fees(tokens, 100, 5)
+                .unwrap();
+            assert!(cost > curve.get_buy_cost_for_tokens(tokens).unwrap());
+            assert!(curve.get_buy_price_with_fees(cost, 100, 5).unwrap() >= tokens);
+            assert!(curve.get_buy_price_with_fees(cost - 1, 100, 5).unwrap() < tokens);
+        }
+        assert_eq!(
+            curve
+                .get_buy_cost_for_tokens_with_fees(1_000, 0, 0)
+                .unwrap(),
+            curve.get_buy_cost_for_tokens(1_000).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_buy_cost_exceeds_reserves() {
+        let curve = create_test_bonding_curve();
+        assert_eq!(curve.get_buy_cost_for_tokens(0).unwrap(), 0);
+        assert!(matches!(
+            curve.get_buy_cost_for_tokens(curve.real_token_reserves + 1),
+            Err(SniperError::InsufficientLiquidity(_))
+        ));
+    }
+
     #[test]
     fn test_curve_progress() {
         let curve = create_test_bonding_curve();
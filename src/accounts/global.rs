@@ -1,5 +1,7 @@
 //! Global config account for Pump program
 
+use crate::constants::{accounts, DEFAULT_FEE_BASIS_POINTS};
+use crate::error::SniperError;
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_sdk::pubkey::Pubkey;
 
@@ -38,7 +40,76 @@ pub struct GlobalAccount {
     pub set_creator_authority: Pubkey,
 }
 
+/// Trading fees the bonding curve charges on each buy and sell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurveFees {
+    pub fee_basis_points: u64,
+    pub creator_fee_basis_points: u64,
+}
+
+impl CurveFees {
+    /// Protocol and creator fee together
+    pub fn total_basis_points(&self) -> u64 {
+        self.fee_basis_points + self.creator_fee_basis_points
+    }
+}
+
+impl Default for CurveFees {
+    /// Protocol fee only, for quotes before the global account has been read
+    fn default() -> Self {
+        Self {
+            fee_basis_points: DEFAULT_FEE_BASIS_POINTS,
+            creator_fee_basis_points: 0,
+        }
+    }
+}
+
 impl GlobalAccount {
+    /// Bytes of the fields known here; live accounts may be longer
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 8 * 5 + 32 + 1 + 8 + 8 + 32 * 7 + 32;
+
+    /// Decode the known fields from the front of account data, ignoring any fields Pump
+    /// has appended since
+    pub fn from_account_data(data: &[u8]) -> Result<Self, SniperError> {
+        if data.len() < Self::LEN {
+            return Err(SniperError::SerializationError(format!(
+                "Global account is {} bytes, expected at least {}",
+                data.len(),
+                Self::LEN
+            )));
+        }
+        Self::deserialize(&mut &data[..]).map_err(|e| {
+            SniperError::SerializationError(format!(
+                "Failed to deserialize global account from {} bytes: {}",
+                data.len(),
+                e
+            ))
+        })
+    }
+
+    pub fn fees(&self) -> CurveFees {
+        CurveFees {
+            fee_basis_points: self.fee_basis_points,
+            creator_fee_basis_points: self.creator_fee_basis_points,
+        }
+    }
+
+    /// Whether `recipient` is the primary fee recipient or one of the listed alternates
+    pub fn is_known_fee_recipient(&self, recipient: &Pubkey) -> bool {
+        *recipient == accounts::fee_recipient() || self.fee_recipients.contains(recipient)
+    }
+
+    /// Populated entry of `fee_recipients` for round-robin `turn`, None if the array is
+    /// all default keys
+    pub fn rotated_fee_recipient(&self, turn: usize) -> Option<Pubkey> {
+        let populated: Vec<&Pubkey> = self
+            .fee_recipients
+            .iter()
+            .filter(|recipient| **recipient != Pubkey::default())
+            .collect();
+        (!populated.is_empty()).then(|| *populated[turn % populated.len()])
+    }
+
     /// Calculate fee amount
     pub fn calculate_fee(&self, trade_value: u64) -> u64 {
         (trade_value as u128 * self.fee_basis_points as u128 / 10000) as u64
@@ -74,3 +145,30 @@ impl GlobalAccount {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::test_global;
+
+    #[test]
+    fn test_length_checked() {
+        let global = test_global();
+        let mut data = borsh::to_vec(&global).unwrap();
+        assert_eq!(data.len(), GlobalAccount::LEN);
+
+        data.extend_from_slice(&[0; 16]);
+        let decoded = GlobalAccount::from_account_data(&data).unwrap();
+        assert_eq!(decoded.fee_recipient, global.fee_recipient);
+
+        let err = GlobalAccount::from_account_data(&data[..100]).unwrap_err();
+        assert!(matches!(err, SniperError::SerializationError(_)));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Serialization error: Global account is 100 bytes, expected at least {}",
+                GlobalAccount::LEN
+            )
+        );
+    }
+}
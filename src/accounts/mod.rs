@@ -2,8 +2,10 @@
 
 pub mod bonding_curve;
 pub mod global;
+pub mod pump_swap;
 pub mod token_info;
 
 pub use bonding_curve::*;
 pub use global::*;
+pub use pump_swap::*;
 pub use token_info::*;
@@ -0,0 +1,52 @@
+//! PumpSwap AMM accounts
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_sdk::pubkey::Pubkey;
+
+/// PumpSwap pool account; reserves live in its two token accounts
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct PumpSwapPool {
+    pub discriminator: u64,
+    pub pool_bump: u8,
+    pub index: u16,
+    pub creator: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub pool_base_token_account: Pubkey,
+    pub pool_quote_token_account: Pubkey,
+    pub lp_supply: u64,
+    /// Receives the creator fee; carried over from the bonding curve
+    pub coin_creator: Pubkey,
+}
+
+/// PumpSwap global config account
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct PumpSwapGlobalConfig {
+    pub discriminator: u64,
+    pub admin: Pubkey,
+    pub lp_fee_basis_points: u64,
+    pub protocol_fee_basis_points: u64,
+    pub disable_flags: u8,
+    pub protocol_fee_recipients: [Pubkey; 8],
+    pub coin_creator_fee_basis_points: u64,
+}
+
+impl PumpSwapGlobalConfig {
+    /// Total fee charged on the quote side of a swap
+    pub fn total_fee_basis_points(&self) -> u64 {
+        self.lp_fee_basis_points
+            + self.protocol_fee_basis_points
+            + self.coin_creator_fee_basis_points
+    }
+}
+
+/// Base tokens out for `quote_in` after fees, constant product
+pub fn amm_buy_quote(quote_in: u64, base_reserve: u64, quote_reserve: u64, fee_bps: u64) -> u64 {
+    let effective_in = quote_in as u128 * 10_000 / (10_000 + fee_bps as u128);
+    let denominator = quote_reserve as u128 + effective_in;
+    if denominator == 0 {
+        return 0;
+    }
+    (base_reserve as u128 * effective_in / denominator) as u64
+}
@@ -19,8 +19,14 @@ pub struct TokenInfo {
     pub bonding_curve: Pubkey,
     /// Creation transaction signature
     pub creation_signature: String,
-    /// Creation timestamp
+    /// Unix seconds when the create transaction was parsed. The stream delivers creates
+    /// within a slot or so of landing, so this stands in for the on-chain creation time
     pub created_at: u64,
+    /// Slot the create transaction landed in, 0 if unknown
+    pub creation_slot: u64,
+    /// Creator's balance of the mint after the create transaction (its bundled dev buy),
+    /// None when the transaction had no balance metadata
+    pub creator_initial_tokens: Option<u64>,
 }
 
 impl TokenInfo {
@@ -46,16 +52,27 @@ impl TokenInfo {
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_secs(),
+            creation_slot: 0,
+            creator_initial_tokens: None,
         }
     }
 
-    /// Get age of token in seconds
+    /// Seconds since `created_at`
     pub fn age_seconds(&self) -> u64 {
         std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs()
-            - self.created_at
+            .saturating_sub(self.created_at)
+    }
+
+    /// Metadata URI is a non-empty http(s) URL
+    pub fn has_http_uri(&self) -> bool {
+        let uri = self.uri.trim().to_ascii_lowercase();
+        let rest = uri
+            .strip_prefix("https://")
+            .or_else(|| uri.strip_prefix("http://"));
+        matches!(rest, Some(host) if !host.is_empty())
     }
 
     /// Format for display
@@ -63,3 +80,26 @@ impl TokenInfo {
         format!("{} ({})", self.name, self.symbol)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_utils::test_token;
+
+    #[test]
+    fn test_http_uri_filter() {
+        let mut token = test_token();
+        let cases = [
+            ("", false),
+            ("   ", false),
+            ("data:application/json;base64,e30=", false),
+            ("ipfs://QmHash", false),
+            ("https://", false),
+            ("https://ipfs.io/ipfs/QmHash", true),
+            ("HTTP://example.com/meta.json", true),
+        ];
+        for (uri, expected) in cases {
+            token.uri = uri.to_string();
+            assert_eq!(token.has_http_uri(), expected, "{uri:?}");
+        }
+    }
+}
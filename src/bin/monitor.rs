@@ -4,9 +4,13 @@ use anyhow::Result;
 use pump_sniper::{
     accounts::TokenInfo,
     common::{Config, MarketData, SniperEvent, StreamClient},
-    utils::{PriceFetcher, TransactionExecutor},
+    utils::{PriceFetcher, RateLimiter, TransactionExecutor},
 };
 use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tracing::{error, info, warn};
@@ -15,6 +19,8 @@ struct TokenTracker {
     token_info: TokenInfo,
     initial_market_cap_usd: f64,
     current_market_cap_usd: f64,
+    /// Spot USD price of one token, 0 until curve data is seen
+    price_per_token_usd: f64,
     first_seen: Instant,
     last_updated: Instant,
 }
@@ -26,6 +32,7 @@ impl TokenTracker {
             token_info,
             initial_market_cap_usd: market_cap_usd,
             current_market_cap_usd: market_cap_usd,
+            price_per_token_usd: 0.0,
             first_seen: now,
             last_updated: now,
         }
@@ -49,24 +56,120 @@ impl TokenTracker {
     }
 }
 
+const CSV_HEADER: &str =
+    "timestamp,mint,symbol,initial_mc_usd,current_mc_usd,change_pct,age_s,curve_progress";
+
+/// Market cap samples appended to `MONITOR_CSV_PATH`
+struct CsvExport {
+    writer: BufWriter<File>,
+}
+
+impl CsvExport {
+    /// Open `path` for appending, writing the header if the file is new or empty
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let is_empty = file.metadata()?.len() == 0;
+        let mut writer = BufWriter::new(file);
+        if is_empty {
+            writeln!(writer, "{}", CSV_HEADER)?;
+        }
+        Ok(Self { writer })
+    }
+
+    fn write_sample(
+        &mut self,
+        mint: &str,
+        tracker: &TokenTracker,
+        curve_progress: f64,
+    ) -> io::Result<()> {
+        writeln!(
+            self.writer,
+            "{},{},{},{:.2},{:.2},{:.2},{},{:.2}",
+            chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            mint,
+            csv_field(&tracker.token_info.symbol),
+            tracker.initial_market_cap_usd,
+            tracker.current_market_cap_usd,
+            tracker.market_cap_change_percent(),
+            tracker.age_seconds(),
+            curve_progress
+        )
+    }
+}
+
+/// Run `write` against the export, dropping the export if it fails so the monitor
+/// carries on without it
+fn export_or_disable(
+    csv: &mut Option<CsvExport>,
+    write: impl FnOnce(&mut CsvExport) -> io::Result<()>,
+) {
+    if let Some(export) = csv {
+        if let Err(e) = write(export) {
+            warn!("CSV export failed, disabling it: {}", e);
+            *csv = None;
+        }
+    }
+}
+
+/// Quote a CSV field holding a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
 struct MonitorBot {
     tracked_tokens: HashMap<String, TokenTracker>,
+    max_tokens: usize,
+    token_ttl: Duration,
     event_receiver: mpsc::UnboundedReceiver<SniperEvent>,
     transaction_executor: TransactionExecutor,
     price_fetcher: PriceFetcher,
+    csv: Option<CsvExport>,
+    rate_limiter: Arc<RateLimiter>,
     start_time: Instant,
 }
 
 impl MonitorBot {
     fn new(event_receiver: mpsc::UnboundedReceiver<SniperEvent>, config: Config) -> Self {
-        let transaction_executor = TransactionExecutor::new(config);
-        let price_fetcher = PriceFetcher::new();
+        let max_tokens = config.monitor_max_tokens;
+        let token_ttl = Duration::from_secs(config.monitor_token_ttl_secs);
+        let transaction_executor = TransactionExecutor::new(config.clone());
+        let price_fetcher = PriceFetcher::from_source_name(
+            &config.sol_price_source,
+            transaction_executor.rpc_client(),
+            config.coingecko_api_key.as_deref(),
+            Duration::from_millis(config.price_timeout_ms),
+            Duration::from_secs(config.price_cache_secs),
+        )
+        .unwrap_or_else(|e| {
+            warn!("{}, falling back to CoinGecko", e);
+            PriceFetcher::new()
+        });
+        let csv = config.monitor_csv_path.as_deref().and_then(|path| {
+            match CsvExport::open(Path::new(path)) {
+                Ok(export) => {
+                    info!("Writing market cap samples to {}", path);
+                    Some(export)
+                }
+                Err(e) => {
+                    warn!("Can't open monitor CSV {}, not exporting: {}", path, e);
+                    None
+                }
+            }
+        });
 
         Self {
             tracked_tokens: HashMap::new(),
+            max_tokens,
+            token_ttl,
             event_receiver,
             transaction_executor,
             price_fetcher,
+            csv,
+            rate_limiter: Arc::new(RateLimiter::new(config.monitor_rpc_rps)),
             start_time: Instant::now(),
         }
     }
@@ -98,6 +201,12 @@ impl MonitorBot {
 
             // Update all tracked tokens every 3 seconds
             if last_update_check.elapsed() >= Duration::from_secs(3) {
+                evict_tokens(
+                    &mut self.tracked_tokens,
+                    self.max_tokens,
+                    self.token_ttl,
+                    Instant::now(),
+                );
                 self.update_all_market_caps().await;
                 last_update_check = Instant::now();
             }
@@ -118,11 +227,12 @@ impl MonitorBot {
         println!("{:^120}", "PUMP.FUN TOKEN MONITOR");
         println!("{}", "=".repeat(120));
         println!(
-            "{:<45} {:<15} {:<12} {:<12} {:<12} {:<8} {:<10}",
+            "{:<45} {:<15} {:<12} {:<12} {:<14} {:<12} {:<8} {:<10}",
             "TOKEN (SYMBOL)",
             "MINT ADDRESS",
             "INITIAL MC",
             "CURRENT MC",
+            "PRICE (USD)",
             "CHANGE %",
             "AGE (s)",
             "STATUS"
@@ -130,6 +240,14 @@ impl MonitorBot {
         println!("{}", "-".repeat(120));
     }
 
+    /// Token price at the cached SOL price, 0 if it is unavailable
+    async fn token_price_usd(&mut self, market_data: MarketData) -> f64 {
+        match self.price_fetcher.get_sol_price_usd().await {
+            Ok(sol_price) => market_data.price_per_token_usd(sol_price),
+            Err(_) => 0.0,
+        }
+    }
+
     async fn handle_new_token(&mut self, token_info: TokenInfo) {
         info!(
             "New token detected: {} ({})",
@@ -140,6 +258,7 @@ impl MonitorBot {
         tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
 
         // Try to get actual bonding curve data first (current state)
+        self.rate_limiter.acquire().await;
         match self
             .transaction_executor
             .fetch_bonding_curve_data(&token_info.bonding_curve)
@@ -155,9 +274,21 @@ impl MonitorBot {
                 {
                     Ok(market_cap_usd) => {
                         // Add to tracking
-                        let tracker = TokenTracker::new(token_info.clone(), market_cap_usd);
+                        let mut tracker = TokenTracker::new(token_info.clone(), market_cap_usd);
+                        tracker.price_per_token_usd = self
+                            .token_price_usd(MarketData::new(
+                                token_info.clone(),
+                                bonding_curve_data,
+                            ))
+                            .await;
                         self.tracked_tokens
                             .insert(token_info.mint.to_string(), tracker);
+                        evict_tokens(
+                            &mut self.tracked_tokens,
+                            self.max_tokens,
+                            self.token_ttl,
+                            Instant::now(),
+                        );
 
                         info!(
                             "{} added to tracking - Initial MC: ${:.2}",
@@ -174,6 +305,7 @@ impl MonitorBot {
             }
             Err(e) => {
                 // Fallback: use global account initial values and add to tracking
+                self.rate_limiter.acquire().await;
                 match self.transaction_executor.fetch_global_account().await {
                     Ok(global_account) => {
                         let market_cap_sol = global_account.get_initial_market_cap_sol();
@@ -187,6 +319,12 @@ impl MonitorBot {
                                 let tracker = TokenTracker::new(token_info.clone(), market_cap_usd);
                                 self.tracked_tokens
                                     .insert(token_info.mint.to_string(), tracker);
+                                evict_tokens(
+                                    &mut self.tracked_tokens,
+                                    self.max_tokens,
+                                    self.token_ttl,
+                                    Instant::now(),
+                                );
 
                                 info!(
                                     "{} added to tracking (fallback) - Initial MC: ${:.2}",
@@ -223,6 +361,9 @@ impl MonitorBot {
                 Ok(market_cap_usd) => {
                     let old_market_cap = tracker.current_market_cap_usd;
                     tracker.update_market_cap(market_cap_usd);
+                    if let Ok(sol_price) = self.price_fetcher.get_sol_price_usd().await {
+                        tracker.price_per_token_usd = market_data.price_per_token_usd(sol_price);
+                    }
 
                     // Only print if there's a significant change (>1% or >$50)
                     let change_percent =
@@ -238,7 +379,7 @@ impl MonitorBot {
                             };
 
                         println!(
-                            "{:<45} {:<15} {:<12.2} {:<12.2} {:<12} {:<8} {:<10}",
+                            "{:<45} {:<15} {:<12.2} {:<12.2} {:<14.10} {:<12} {:<8} {:<10}",
                             format!(
                                 "{} ({})",
                                 truncate_string(&tracker.token_info.name, 25),
@@ -247,6 +388,7 @@ impl MonitorBot {
                             truncate_string(&mint_str, 15),
                             tracker.initial_market_cap_usd,
                             tracker.current_market_cap_usd,
+                            tracker.price_per_token_usd,
                             change_str,
                             tracker.age_seconds(),
                             "📈 UPDATE"
@@ -262,12 +404,13 @@ impl MonitorBot {
 
     /// Update market caps for all tracked tokens
     async fn update_all_market_caps(&mut self) {
-        for (_mint, tracker) in self.tracked_tokens.iter_mut() {
+        for (mint, tracker) in self.tracked_tokens.iter_mut() {
             // Skip if updated recently (within 1 second)
             if tracker.last_updated.elapsed() < Duration::from_secs(1) {
                 continue;
             }
 
+            self.rate_limiter.acquire().await;
             match self
                 .transaction_executor
                 .fetch_bonding_curve_data(&tracker.token_info.bonding_curve)
@@ -275,6 +418,7 @@ impl MonitorBot {
             {
                 Ok(bonding_curve_data) => {
                     let market_cap_sol = bonding_curve_data.get_market_cap_sol();
+                    let curve_progress = bonding_curve_data.get_curve_progress();
 
                     match self
                         .price_fetcher
@@ -284,6 +428,14 @@ impl MonitorBot {
                         Ok(market_cap_usd) => {
                             let old_market_cap = tracker.current_market_cap_usd;
                             tracker.update_market_cap(market_cap_usd);
+                            export_or_disable(&mut self.csv, |csv| {
+                                csv.write_sample(mint, tracker, curve_progress)
+                            });
+                            if let Ok(sol_price) = self.price_fetcher.get_sol_price_usd().await {
+                                tracker.price_per_token_usd =
+                                    MarketData::new(tracker.token_info.clone(), bonding_curve_data)
+                                        .price_per_token_usd(sol_price);
+                            }
 
                             // Log significant changes (>5% or >$100)
                             let change_percent =
@@ -325,10 +477,9 @@ impl MonitorBot {
                     }
                 }
             }
-
-            // Small delay between requests to avoid rate limits
-            tokio::time::sleep(Duration::from_millis(50)).await;
         }
+
+        export_or_disable(&mut self.csv, |csv| csv.writer.flush());
     }
 
     /// Refresh the display with current token data
@@ -371,7 +522,7 @@ impl MonitorBot {
             };
 
             println!(
-                "{:<45} {:<15} {:<12.2} {:<12.2} {:<12} {:<8} {:<10}",
+                "{:<45} {:<15} {:<12.2} {:<12.2} {:<14.10} {:<12} {:<8} {:<10}",
                 format!(
                     "{} ({})",
                     truncate_string(&tracker.token_info.name, 25),
@@ -380,6 +531,7 @@ impl MonitorBot {
                 truncate_string(mint, 15),
                 tracker.initial_market_cap_usd,
                 tracker.current_market_cap_usd,
+                tracker.price_per_token_usd,
                 change_str,
                 tracker.age_seconds(),
                 status
@@ -405,6 +557,29 @@ impl MonitorBot {
     }
 }
 
+/// Drop tokens with no update within `ttl`, then keep only the `max_tokens` highest by market cap
+fn evict_tokens(
+    tokens: &mut HashMap<String, TokenTracker>,
+    max_tokens: usize,
+    ttl: Duration,
+    now: Instant,
+) {
+    tokens.retain(|_, tracker| now.saturating_duration_since(tracker.last_updated) < ttl);
+
+    if tokens.len() <= max_tokens {
+        return;
+    }
+
+    let mut by_cap: Vec<(String, f64)> = tokens
+        .iter()
+        .map(|(mint, tracker)| (mint.clone(), tracker.current_market_cap_usd))
+        .collect();
+    by_cap.sort_by(|a, b| b.1.total_cmp(&a.1));
+    for (mint, _) in by_cap.into_iter().skip(max_tokens) {
+        tokens.remove(&mint);
+    }
+}
+
 fn truncate_string(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
         s.to_string()
@@ -432,6 +607,10 @@ async fn main() -> Result<()> {
         return Err(e.into());
     }
 
+    if let Err(e) = config.check_baseline() {
+        warn!("Config baseline check failed: {}", e);
+    }
+
     info!("Configuration loaded:");
     info!("  gRPC Endpoint: {}", config.grpc_endpoint);
     info!("  RPC Endpoint: {}", config.rpc_endpoint);
@@ -461,3 +640,92 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn tracker(market_cap_usd: f64) -> (String, TokenTracker) {
+        let mint = Pubkey::new_unique();
+        let token_info = TokenInfo::new(
+            mint,
+            "Test Token".to_string(),
+            "TEST".to_string(),
+            Pubkey::new_unique(),
+            String::new(),
+            Pubkey::new_unique(),
+            "sig".to_string(),
+        );
+        (
+            mint.to_string(),
+            TokenTracker::new(token_info, market_cap_usd),
+        )
+    }
+
+    #[test]
+    fn test_eviction_keeps_highest_caps() {
+        let mut tokens: HashMap<_, _> = [5_000.0, 9_000.0, 1_000.0, 7_000.0, 3_000.0]
+            .into_iter()
+            .map(tracker)
+            .collect();
+
+        evict_tokens(&mut tokens, 3, Duration::from_secs(300), Instant::now());
+
+        let mut caps: Vec<f64> = tokens.values().map(|t| t.current_market_cap_usd).collect();
+        caps.sort_by(|a, b| b.total_cmp(a));
+        assert_eq!(caps, [9_000.0, 7_000.0, 5_000.0]);
+    }
+
+    #[test]
+    fn test_csv_export_appends_samples() {
+        let path = std::env::temp_dir().join(format!("monitor-{}.csv", Pubkey::new_unique()));
+        let (mint, mut sample) = tracker(5_000.0);
+        sample.update_market_cap(6_000.0);
+        sample.token_info.symbol = "A,\"B\"".to_string();
+
+        let mut csv = CsvExport::open(&path).unwrap();
+        csv.write_sample(&mint, &sample, 12.5).unwrap();
+        csv.writer.flush().unwrap();
+        drop(csv);
+        // reopening appends without a second header
+        let mut csv = Some(CsvExport::open(&path).unwrap());
+        export_or_disable(&mut csv, |csv| csv.write_sample(&mint, &sample, 13.0));
+        export_or_disable(&mut csv, |csv| csv.writer.flush());
+        assert!(csv.is_some());
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER);
+        let fields: Vec<&str> = lines[1].splitn(3, ',').collect();
+        assert!(chrono::DateTime::parse_from_rfc3339(fields[0]).is_ok());
+        assert_eq!(fields[1], mint);
+        assert!(
+            fields[2].starts_with("\"A,\"\"B\"\"\",5000.00,6000.00,20.00,0,12.50"),
+            "{}",
+            lines[1]
+        );
+        assert!(lines[2].ends_with(",13.00"));
+
+        // a failing write drops the export instead of stopping the monitor
+        export_or_disable(&mut csv, |_| Err(io::Error::other("disk full")));
+        assert!(csv.is_none());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_eviction_drops_stale_tokens() {
+        let (stale_mint, stale) = tracker(50_000.0);
+        let (fresh_mint, fresh) = tracker(1_000.0);
+        let mut tokens = HashMap::from([(stale_mint.clone(), stale), (fresh_mint.clone(), fresh)]);
+        let later = Instant::now() + Duration::from_secs(10);
+        tokens.get_mut(&fresh_mint).unwrap().last_updated = later;
+
+        evict_tokens(&mut tokens, 10, Duration::from_secs(5), later);
+
+        assert!(!tokens.contains_key(&stale_mint));
+        assert!(tokens.contains_key(&fresh_mint));
+    }
+}
@@ -1,10 +1,17 @@
 //! Sniper with wallet setup
 
 use anyhow::Result;
-use pump_sniper::{common::Config, Sniper};
+use pump_sniper::{
+    common::Config,
+    constants::{BASE_FEE_LAMPORTS, TOKEN_ACCOUNT_RENT_LAMPORTS},
+    utils::total_cost_estimate,
+    Sniper,
+};
+use solana_client::rpc_client::RpcClient;
 use solana_sdk::{signature::Keypair, signer::Signer};
 use std::env;
-use tracing::{error, info};
+use std::sync::Arc;
+use tracing::{error, info, warn};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -25,6 +32,10 @@ async fn main() -> Result<()> {
         return Err(e.into());
     }
 
+    if let Err(e) = config.check_baseline() {
+        warn!("Config baseline check failed: {}", e);
+    }
+
     info!("Configuration loaded:");
     info!("  gRPC Endpoint: {}", config.grpc_endpoint);
     info!("  RPC Endpoint: {}", config.rpc_endpoint);
@@ -39,32 +50,67 @@ async fn main() -> Result<()> {
     );
     info!("  Slippage: {}%", config.max_slippage_bps as f64 / 100.0);
 
-    // Load wallet from env
-    let wallet = load_wallet_from_env()?;
-    info!("Wallet loaded: {}", wallet.pubkey());
-
-    // Check wallet balance
-    let rpc_client = solana_client::rpc_client::RpcClient::new(&config.rpc_endpoint);
-    match rpc_client.get_balance(&wallet.pubkey()) {
-        Ok(balance) => {
-            info!("Wallet balance: {:.6} SOL", balance as f64 / 1e9);
-            if balance < config.buy_amount_sol + config.priority_fee_sol + 10_000_000 {
-                error!(
-                    "Insufficient balance for buying! Need at least {} SOL",
-                    (config.buy_amount_sol + config.priority_fee_sol + 10_000_000) as f64 / 1e9
+    let dry_run = env::var("DRY_RUN").is_ok();
+
+    // Load wallet from env; optional when dry running
+    let wallet = match load_wallet() {
+        Ok(wallet) => Some(wallet),
+        Err(e) if dry_run => {
+            info!(
+                "No wallet loaded ({}), dry run will use a throwaway payer",
+                e
+            );
+            None
+        }
+        Err(e) => return Err(e),
+    };
+
+    if let Some(wallet) = &wallet {
+        info!("Wallet loaded: {}", wallet.pubkey());
+    }
+
+    // one client for the balance check and the sniper
+    let rpc_client = Arc::new(RpcClient::new_with_commitment(
+        config.rpc_endpoint.clone(),
+        config.commitment_config(),
+    ));
+
+    // Check wallet balance; a dry run spends nothing
+    if let Some(wallet) = wallet.as_ref().filter(|_| !dry_run) {
+        match rpc_client.get_balance(&wallet.pubkey()) {
+            Ok(balance) => {
+                info!("Wallet balance: {:.6} SOL", balance as f64 / 1e9);
+                let required = total_cost_estimate(
+                    config.buy_amount_sol,
+                    config.max_slippage_bps,
+                    config.priority_fee_sol,
+                    BASE_FEE_LAMPORTS,
+                    TOKEN_ACCOUNT_RENT_LAMPORTS,
                 );
-                return Err(anyhow::anyhow!("Insufficient wallet balance"));
+                if balance < required {
+                    error!(
+                        "Insufficient balance for buying! Need at least {} SOL",
+                        required as f64 / 1e9
+                    );
+                    return Err(anyhow::anyhow!("Insufficient wallet balance"));
+                }
+            }
+            Err(e) => {
+                error!("Failed to check wallet balance: {}", e);
+                return Err(e.into());
             }
-        }
-        Err(e) => {
-            error!("Failed to check wallet balance: {}", e);
-            return Err(e.into());
         }
     }
 
     // Create and start sniper
-    let mut sniper = Sniper::new(config).await?;
-    sniper.set_wallet(wallet);
+    let mut sniper = Sniper::with_rpc_client(config, rpc_client).await?;
+    if let Some(wallet) = wallet {
+        sniper.set_wallet(wallet);
+    }
+
+    if dry_run {
+        sniper.enable_dry_run();
+    }
 
     // Enable test mode if TEST_MODE env is set
     if env::var("TEST_MODE").is_ok() {
@@ -84,6 +130,43 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Load the wallet from WALLET_KEYPAIR_PATH, falling back to WALLET_PRIVATE_KEY
+fn load_wallet() -> Result<Keypair> {
+    if let Ok(path) = env::var("WALLET_KEYPAIR_PATH") {
+        return load_wallet_from_file(&path);
+    }
+    if env::var("WALLET_PRIVATE_KEY").is_ok() {
+        return load_wallet_from_env();
+    }
+    Err(anyhow::anyhow!(
+        "No wallet configured: set WALLET_KEYPAIR_PATH or WALLET_PRIVATE_KEY"
+    ))
+}
+
+/// Load a Solana CLI keyfile (JSON array of 64 bytes)
+fn load_wallet_from_file(path: &str) -> Result<Keypair> {
+    let path = match (path.strip_prefix("~/"), env::var("HOME")) {
+        (Some(rest), Ok(home)) => std::path::Path::new(&home).join(rest),
+        _ => std::path::PathBuf::from(path),
+    };
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to read keypair file {}: {}", path.display(), e))?;
+
+    parse_keypair_json(&contents)
+        .map_err(|e| anyhow::anyhow!("Invalid keypair file {}: {}", path.display(), e))
+}
+
+fn parse_keypair_json(contents: &str) -> Result<Keypair> {
+    let bytes: Vec<u8> = serde_json::from_str(contents)
+        .map_err(|e| anyhow::anyhow!("expected a JSON array of bytes: {}", e))?;
+
+    if bytes.len() != 64 {
+        return Err(anyhow::anyhow!("expected 64 bytes, got {}", bytes.len()));
+    }
+
+    Ok(Keypair::from_bytes(&bytes)?)
+}
+
 /// Load wallet keypair from env
 fn load_wallet_from_env() -> Result<Keypair> {
     let private_key = env::var("WALLET_PRIVATE_KEY")
@@ -103,3 +186,25 @@ fn load_wallet_from_env() -> Result<Keypair> {
 
     Ok(Keypair::from_bytes(&decoded)?)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keypair_json() {
+        let keypair = Keypair::new();
+        let json = serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap();
+        assert_eq!(
+            parse_keypair_json(&json).unwrap().pubkey(),
+            keypair.pubkey()
+        );
+
+        let short = serde_json::to_string(&[1u8; 32].to_vec()).unwrap();
+        let err = parse_keypair_json(&short).unwrap_err();
+        assert!(err.to_string().contains("expected 64 bytes, got 32"));
+
+        assert!(parse_keypair_json("not json").is_err());
+        assert!(parse_keypair_json("[1, 2, 300]").is_err());
+    }
+}
@@ -0,0 +1,107 @@
+//! Market cap threshold that adapts to realized win rate
+
+use super::Config;
+use std::collections::VecDeque;
+
+/// Raises the threshold while the rolling win rate is below target and lowers it while
+/// above, one step per realized outcome, within bounds
+#[derive(Debug, Clone)]
+pub struct AdaptiveThreshold {
+    threshold_usd: f64,
+    min_usd: f64,
+    max_usd: f64,
+    step_usd: f64,
+    target_win_rate: f64,
+    window: usize,
+    /// Most recent outcomes, true for a win
+    outcomes: VecDeque<bool>,
+}
+
+impl AdaptiveThreshold {
+    /// Controller when `adaptive_threshold` is set, starting from the configured threshold
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if !config.adaptive_threshold {
+            return None;
+        }
+
+        Some(Self {
+            threshold_usd: config.market_cap_threshold_usd.clamp(
+                config.adaptive_min_threshold_usd,
+                config.adaptive_max_threshold_usd,
+            ),
+            min_usd: config.adaptive_min_threshold_usd,
+            max_usd: config.adaptive_max_threshold_usd,
+            step_usd: config.adaptive_step_usd,
+            target_win_rate: config.adaptive_target_win_rate,
+            window: config.adaptive_window,
+            outcomes: VecDeque::with_capacity(config.adaptive_window),
+        })
+    }
+
+    pub fn threshold_usd(&self) -> f64 {
+        self.threshold_usd
+    }
+
+    /// Share of wins over the window, None before any outcome
+    pub fn win_rate(&self) -> Option<f64> {
+        if self.outcomes.is_empty() {
+            return None;
+        }
+        let wins = self.outcomes.iter().filter(|won| **won).count();
+        Some(wins as f64 / self.outcomes.len() as f64)
+    }
+
+    /// Record a realized outcome and return the adjusted threshold
+    pub fn record(&mut self, won: bool) -> f64 {
+        if self.outcomes.len() == self.window {
+            self.outcomes.pop_front();
+        }
+        self.outcomes.push_back(won);
+
+        let win_rate = self.win_rate().unwrap_or(self.target_win_rate);
+        if win_rate < self.target_win_rate {
+            self.threshold_usd = (self.threshold_usd + self.step_usd).min(self.max_usd);
+        } else if win_rate > self.target_win_rate {
+            self.threshold_usd = (self.threshold_usd - self.step_usd).max(self.min_usd);
+        }
+        self.threshold_usd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_follows_win_rate_within_bounds() {
+        let config = Config {
+            market_cap_threshold_usd: 8_000.0,
+            adaptive_threshold: true,
+            adaptive_min_threshold_usd: 6_000.0,
+            adaptive_max_threshold_usd: 11_000.0,
+            adaptive_step_usd: 1_000.0,
+            adaptive_target_win_rate: 0.5,
+            adaptive_window: 4,
+            ..Config::default()
+        };
+        assert!(AdaptiveThreshold::from_config(&Config::default()).is_none());
+        let mut adaptive = AdaptiveThreshold::from_config(&config).unwrap();
+
+        // losing streak tightens up to the ceiling
+        let raised: Vec<f64> = (0..5).map(|_| adaptive.record(false)).collect();
+        assert_eq!(raised, [9_000.0, 10_000.0, 11_000.0, 11_000.0, 11_000.0]);
+
+        // a win brings the window to 1/4, still below target
+        assert_eq!(adaptive.record(true), 11_000.0);
+        // 2/4 is on target
+        assert_eq!(adaptive.record(true), 11_000.0);
+        assert_eq!(adaptive.win_rate(), Some(0.5));
+
+        // winning streak loosens down to the floor
+        let lowered: Vec<f64> = (0..7).map(|_| adaptive.record(true)).collect();
+        assert_eq!(
+            lowered,
+            [10_000.0, 9_000.0, 8_000.0, 7_000.0, 6_000.0, 6_000.0, 6_000.0]
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! Local admin command listener
+
+use std::net::SocketAddr;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// Operator command sent over the admin socket, one per line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminCommand {
+    /// Report counters
+    Stats,
+    /// Stop buying; positions keep being managed
+    Pause,
+    /// Resume buying
+    Resume,
+    /// Sell every open position
+    Liquidate,
+    /// Stop the bot
+    Stop,
+}
+
+impl AdminCommand {
+    pub fn parse(line: &str) -> Option<Self> {
+        match line.trim().to_ascii_lowercase().as_str() {
+            "stats" => Some(Self::Stats),
+            "pause" => Some(Self::Pause),
+            "resume" => Some(Self::Resume),
+            "liquidate" => Some(Self::Liquidate),
+            "stop" => Some(Self::Stop),
+            _ => None,
+        }
+    }
+}
+
+/// A command with the channel its plain-text response goes back on
+#[derive(Debug)]
+pub struct AdminRequest {
+    pub command: AdminCommand,
+    pub reply: oneshot::Sender<String>,
+}
+
+/// Listen for admin connections on `address`: a loopback `host:port` for TCP, otherwise a
+/// Unix socket path. Commands are unauthenticated, so TCP refuses other interfaces and the
+/// socket is owner-only. Returns the bound address and the accept task
+pub async fn spawn_admin_listener(
+    address: &str,
+    requests: mpsc::UnboundedSender<AdminRequest>,
+) -> std::io::Result<(String, JoinHandle<()>)> {
+    if let Ok(addr) = address.parse::<SocketAddr>() {
+        if !addr.ip().is_loopback() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!("Admin listener must bind a loopback address, not {}", addr),
+            ));
+        }
+        let listener = TcpListener::bind(addr).await?;
+        let local = listener.local_addr()?.to_string();
+        let task = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        tokio::spawn(serve_connection(stream, requests.clone()));
+                    }
+                    Err(e) => error!("Admin accept failed: {}", e),
+                }
+            }
+        });
+        info!("Admin listener on {}", local);
+        return Ok((local, task));
+    }
+
+    spawn_unix_listener(address, requests)
+}
+
+#[cfg(unix)]
+fn spawn_unix_listener(
+    path: &str,
+    requests: mpsc::UnboundedSender<AdminRequest>,
+) -> std::io::Result<(String, JoinHandle<()>)> {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    // a stale socket from a previous run would fail the bind; anything else is left alone
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("Admin socket path {} exists and is not a socket", path),
+            ));
+        }
+        std::fs::remove_file(path)?;
+    }
+    let listener = tokio::net::UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    let task = tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(serve_connection(stream, requests.clone()));
+                }
+                Err(e) => error!("Admin accept failed: {}", e),
+            }
+        }
+    });
+    info!("Admin listener on {}", path);
+    Ok((path.to_string(), task))
+}
+
+#[cfg(not(unix))]
+fn spawn_unix_listener(
+    path: &str,
+    _requests: mpsc::UnboundedSender<AdminRequest>,
+) -> std::io::Result<(String, JoinHandle<()>)> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!("Unix admin socket {} needs a Unix platform", path),
+    ))
+}
+
+/// Answer commands line by line until the client hangs up
+async fn serve_connection<S>(stream: S, requests: mpsc::UnboundedSender<AdminRequest>)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match AdminCommand::parse(&line) {
+            Some(command) => {
+                let (reply, response) = oneshot::channel();
+                if requests.send(AdminRequest { command, reply }).is_err() {
+                    "error: sniper is not running".to_string()
+                } else {
+                    response
+                        .await
+                        .unwrap_or_else(|_| "error: no response".to_string())
+                }
+            }
+            None => format!(
+                "error: unknown command {:?} (stats, pause, resume, liquidate, stop)",
+                line.trim()
+            ),
+        };
+
+        if let Err(e) = writer.write_all(format!("{}\n", response).as_bytes()).await {
+            warn!("Admin reply failed: {}", e);
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tcp_listener_refuses_non_loopback() {
+        let (requests, _) = mpsc::unbounded_channel();
+        let error = spawn_admin_listener("0.0.0.0:0", requests.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::PermissionDenied);
+
+        let (_, task) = spawn_admin_listener("127.0.0.1:0", requests).await.unwrap();
+        task.abort();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_socket_owner_only_and_never_replaces_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("pump-sniper-admin-{}", std::process::id()));
+        let path_str = path.to_string_lossy().into_owned();
+        let (requests, _) = mpsc::unbounded_channel();
+
+        std::fs::write(&path, "keep").unwrap();
+        let error = spawn_admin_listener(&path_str, requests.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "keep");
+        std::fs::remove_file(&path).unwrap();
+
+        // a stale socket is replaced
+        for _ in 0..2 {
+            let (_, task) = spawn_admin_listener(&path_str, requests.clone())
+                .await
+                .unwrap();
+            task.abort();
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
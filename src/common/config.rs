@@ -1,23 +1,283 @@
 //! Config
 
+use super::{NameFilter, ScoringWeights};
 use crate::error::SniperError;
+use crate::utils::{DEFAULT_IPFS_GATEWAY, METADATA_FIELDS};
+use serde::{Deserialize, Serialize};
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::Path;
+use tracing::{info, warn};
 
-#[derive(Debug, Clone)]
+/// Per-transaction compute unit cap enforced by the runtime
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Ceiling on the derived compute unit price in micro-lamports per CU
+pub const MAX_COMPUTE_UNIT_PRICE_MICRO_LAMPORTS: u64 = 100_000_000;
+
+/// Fields whose values are hidden when logging config changes and fingerprinted in the
+/// baseline snapshot; URLs often embed API keys
+const REDACTED_FIELDS: &[&str] = &[
+    "grpc_endpoint",
+    "rpc_endpoint",
+    "rpc_endpoints",
+    "webhook_url",
+    "coingecko_api_key",
+    "jito_block_engine_url",
+];
+
+/// Fields read once at startup (connections, wallet signing, listeners and files), which
+/// a strategy switch could not apply
+const STARTUP_ONLY_FIELDS: &[&str] = &[
+    "grpc_endpoint",
+    "rpc_endpoint",
+    "rpc_endpoints",
+    "commitment",
+    "stream_filter",
+    "parser_workers",
+    "nonce_account",
+    "nonce_authority",
+    "jito_block_engine_url",
+    "statsd_addr",
+    "webhook_url",
+    "capture_path",
+    "replay_path",
+    "replay_speed",
+    "sol_price_source",
+    "coingecko_api_key",
+    "creator_reputation_path",
+    "admin_socket",
+    "metrics_port",
+    "metrics_bind",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     /// gRPC endpoint for streaming
     pub grpc_endpoint: String,
     /// RPC endpoint for transactions
     pub rpc_endpoint: String,
+    /// Fallback RPC endpoints, tried after `rpc_endpoint` on errors
+    pub rpc_endpoints: Vec<String>,
+    /// Commitment for the stream subscription, RPC reads and send preflight. Processed is
+    /// fastest but can see state from forks that get dropped; confirmed and finalized trade
+    /// latency for safety. Unset streams and preflights at processed and reads at confirmed
+    pub commitment: Option<CommitmentLevel>,
     /// Market cap threshold in USD
     pub market_cap_threshold_usd: f64,
+    /// Market cap threshold in SOL lamports; takes precedence over the USD threshold and
+    /// scoring, and needs no SOL price
+    pub market_cap_threshold_sol: Option<u64>,
+    /// Skip tokens whose curve holds less real SOL than this many lamports, whatever the
+    /// market cap
+    pub min_real_sol_reserves: Option<u64>,
+    /// Move the market cap threshold by realized win rate within the adaptive bounds
+    pub adaptive_threshold: bool,
+    /// Lowest the adaptive threshold goes, in USD
+    pub adaptive_min_threshold_usd: f64,
+    /// Highest the adaptive threshold goes, in USD
+    pub adaptive_max_threshold_usd: f64,
+    /// USD the adaptive threshold moves per realized outcome
+    pub adaptive_step_usd: f64,
+    /// Win rate (0-1) the adaptive threshold steers toward
+    pub adaptive_target_win_rate: f64,
+    /// Realized outcomes in the rolling win rate
+    pub adaptive_window: usize,
     /// Maximum slippage tolerance (basis points)
     pub max_slippage_bps: u64,
     /// Buy amount in SOL lamports
     pub buy_amount_sol: u64,
-    /// Priority fee in SOL lamports (0.005 SOL = 5_000_000 lamports)
+    /// Buy with this percent (0-100] of the wallet balance instead of `buy_amount_sol`,
+    /// keeping back enough for fees and rent
+    pub buy_amount_pct: Option<f64>,
+    /// Buy this many USD worth at the cached SOL price instead of `buy_amount_sol`;
+    /// `buy_amount_pct` takes precedence
+    pub buy_amount_usd: Option<f64>,
+    /// Target token count to buy; when set, the SOL amount is solved from the curve
+    pub buy_token_amount: Option<u64>,
+    /// Most lamports a `buy_token_amount` buy may cost, `buy_amount_sol` when unset
+    pub buy_token_max_cost_sol: Option<u64>,
+    /// Total priority fee per transaction in lamports (0.005 SOL = 5_000_000 lamports),
+    /// spread over `compute_unit_limit` as a per-CU price
     pub priority_fee_sol: u64,
+    /// Price compute units from recent prioritization fees instead of `priority_fee_sol`
+    pub dynamic_priority_fee: bool,
+    /// Percentile (0-100) of recent fees paid in dynamic mode
+    pub priority_fee_percentile: u8,
+    /// Lowest dynamic compute unit price in micro-lamports
+    pub min_priority_fee: u64,
+    /// Highest dynamic compute unit price in micro-lamports
+    pub max_priority_fee: u64,
     /// Compute unit limit for buy transactions
     pub compute_unit_limit: u32,
+    /// Sell a position once its gain reaches this many basis points
+    pub take_profit_bps: Option<u64>,
+    /// Hold off take-profit until the position's net proceeds after fees beat its cost by
+    /// this many USD at the current SOL price
+    pub min_net_profit_usd: Option<f64>,
+    /// Share one RPC request between concurrent fetches of the same bonding curve
+    pub coalesce_curve_fetches: bool,
+    /// Sell a position once its loss reaches this many basis points
+    pub stop_loss_bps: Option<u64>,
+    /// Move ATA creation into a preceding transaction when the buy exceeds the size limit
+    pub split_oversized_buy: bool,
+    /// Max slots the stream may trail the RPC before warning
+    pub max_acceptable_slot_lag: Option<u64>,
+    /// Skip buys while the stream lag exceeds `max_acceptable_slot_lag`
+    pub pause_buys_on_lag: bool,
+    /// Candidate thresholds in USD; when non-empty the bot records hits instead of buying
+    pub shadow_thresholds_usd: Vec<f64>,
+    /// Skip creates whose metadata URI is empty or not http(s)
+    pub require_http_uri: bool,
+    /// Fetch the metadata JSON before buying and skip tokens where it fails or lacks
+    /// `metadata_required_fields`
+    pub require_metadata: bool,
+    /// Metadata fields that must be non-blank when `require_metadata` is set
+    pub metadata_required_fields: Vec<String>,
+    /// Gateway `ipfs://` metadata URIs are fetched through
+    pub ipfs_gateway: String,
+    /// First gRPC reconnect delay in milliseconds
+    pub reconnect_base_backoff_ms: u64,
+    /// Cap on the gRPC reconnect delay in seconds
+    pub reconnect_max_backoff_secs: u64,
+    /// Most tokens the monitor keeps on screen
+    pub monitor_max_tokens: usize,
+    /// Monitor drops tokens with no update for this many seconds
+    pub monitor_token_ttl_secs: u64,
+    /// Monitor appends each market cap sample to this CSV file
+    pub monitor_csv_path: Option<String>,
+    /// Most account fetches the monitor starts per second. Each fetch makes up to one
+    /// request per `fetch_retry_delays_ms` entry, so this is not a cap on RPC requests
+    pub monitor_rpc_rps: f64,
+    /// Delay before each account fetch attempt in milliseconds
+    pub fetch_retry_delays_ms: Vec<u64>,
+    /// Skip tokens whose mint still has a mint authority
+    pub require_no_mint_authority: bool,
+    /// Skip tokens whose mint has a freeze authority
+    pub require_no_freeze_authority: bool,
+    /// Seconds between idle summary logs, 0 to disable
+    pub heartbeat_interval_seconds: u64,
+    /// Seconds between `StatsUpdate` events, 0 to disable
+    pub stats_interval_seconds: u64,
+    /// StatsD `host:port` stats updates are also sent to; needs the `statsd` feature
+    pub statsd_addr: Option<String>,
+    /// Discord/Slack-style webhook critical events and connection changes are posted to
+    pub webhook_url: Option<String>,
+    /// Append raw gRPC updates to this file for replay
+    pub capture_path: Option<String>,
+    /// Feed a capture file through the sniper instead of connecting to gRPC
+    pub replay_path: Option<String>,
+    /// Replay pace relative to the capture's slot timing, 0 for as fast as possible
+    pub replay_speed: f64,
+    /// SOL/USD price backend: `coingecko` or `jupiter`
+    pub sol_price_source: String,
+    /// CoinGecko Pro API key; prices come from the free endpoint without one
+    pub coingecko_api_key: Option<String>,
+    /// Timeout for SOL price API requests in milliseconds
+    pub price_timeout_ms: u64,
+    /// Seconds a fetched SOL price is reused
+    pub price_cache_secs: u64,
+    /// Most tokens tracked at once; the oldest are dropped with their per-mint state
+    pub max_tracked_tokens: usize,
+    /// Seconds a cached bonding curve lives without an update, 0 to keep until evicted
+    pub cache_ttl_secs: u64,
+    /// Most bonding curves cached at once; the least recently updated are dropped
+    pub max_cached_curves: usize,
+    /// Ignore curve updates identical to the cached state for that curve
+    pub dedup_curve_updates: bool,
+    /// Buy on a weighted signal score at or above this (0-1) instead of the cap threshold alone
+    pub buy_score_threshold: Option<f64>,
+    /// Weights of market cap, momentum and liquidity in the score
+    pub score_weights: ScoringWeights,
+    /// Buys (SOL-adding curve updates) at which momentum scores full marks
+    pub score_momentum_target_buys: u32,
+    /// Real SOL reserves in lamports at which liquidity scores full marks
+    pub score_liquidity_target_sol: u64,
+    /// Wait this long for a buy to confirm before reporting it failed, 0 to not wait
+    pub confirm_timeout_secs: u64,
+    /// Treat a confirmed buy as failed unless the token balance grew by the expected amount
+    /// less slippage
+    pub verify_buy_balance: bool,
+    /// Resends of a buy after a transient RPC or send failure, each with a fresh blockhash
+    pub max_buy_retries: u32,
+    /// Refuse a buy quoted below this many token base units, so a degenerate curve can't
+    /// take SOL for nothing
+    pub min_expected_tokens: u64,
+    /// Route buys of migrated tokens to the PumpSwap AMM instead of failing
+    pub trade_migrated_on_amm: bool,
+    /// Jito block engine buys are bundled to, RPC send when unset
+    pub jito_block_engine_url: Option<String>,
+    /// Tip paid to Jito with each bundled buy, in lamports
+    pub jito_tip_lamports: u64,
+    /// Durable nonce account buys are signed against instead of a recent blockhash
+    #[serde(with = "pubkey_opt")]
+    pub nonce_account: Option<Pubkey>,
+    /// Authority of `nonce_account`, the payer wallet when unset
+    #[serde(with = "pubkey_opt")]
+    pub nonce_authority: Option<Pubkey>,
+    /// Stop buying once buys plus priority fees would spend more than this many lamports
+    pub max_total_spend_sol: Option<u64>,
+    /// Skip buys that would take spend over the trailing 24h past this many lamports
+    pub daily_spend_cap_sol: Option<u64>,
+    /// Skip buys that would take the current value of open positions past this many lamports
+    pub max_portfolio_exposure_sol: Option<u64>,
+    /// Send buys on their own tasks, holding at most this many open or in-flight positions;
+    /// unset sends one buy at a time on the event loop
+    pub max_concurrent_positions: Option<usize>,
+    /// Tasks parsing create transactions off the stream task, 0 to parse inline
+    pub parser_workers: usize,
+    /// Skip buys within this many milliseconds of the last executed buy, 0 to disable
+    pub buy_cooldown_ms: u64,
+    /// Skip buys of tokens younger than this many seconds
+    pub min_token_age_secs: Option<u64>,
+    /// Skip buys of tokens older than this many seconds
+    pub max_token_age_secs: Option<u64>,
+    /// Skip buys until the stream is this many slots past the token's create transaction
+    pub min_slots_since_creation: Option<u64>,
+    /// Skip buys once a creator has had this many within `creator_buy_window_seconds`
+    pub max_buys_per_creator_per_window: Option<u32>,
+    /// Rolling window the per-creator buy limit counts over
+    pub creator_buy_window_seconds: u64,
+    /// Refuse to trade if the global account's fee recipient isn't a known pump recipient
+    pub validate_fee_recipient: bool,
+    /// JSON file creator reputation is loaded from and saved to
+    pub creator_reputation_path: Option<String>,
+    /// Skip buys from creators scoring below this (0-1, unknown creators score 0.5)
+    pub min_creator_reputation: Option<f64>,
+    /// Skip tokens whose creator holds more than this percent of supply after the create
+    pub max_dev_holding_pct: Option<f64>,
+    /// Skip buys whose `RugChecker` safety score (0-100) is below this
+    pub min_safety_score: Option<u8>,
+    /// Creators whose tokens are never tracked or bought
+    #[serde(with = "pubkey_set")]
+    pub creator_blacklist: HashSet<Pubkey>,
+    /// When non-empty, only tokens from these creators are tracked or bought
+    #[serde(with = "pubkey_set")]
+    pub creator_whitelist: HashSet<Pubkey>,
+    /// Narrows the gRPC subscription to a watchlist
+    pub stream_filter: StreamFilter,
+    /// Only track tokens whose name or symbol matches this regex
+    pub name_include_regex: Option<String>,
+    /// Never track tokens whose name or symbol matches this regex
+    pub name_exclude_regex: Option<String>,
+    /// Admin command listener: `host:port` for TCP, otherwise a Unix socket path
+    pub admin_socket: Option<String>,
+    /// Port for the `/health` and `/metrics` HTTP endpoints
+    pub metrics_port: Option<u16>,
+    /// Interface the metrics endpoints listen on, loopback unless exposed on purpose
+    pub metrics_bind: IpAddr,
+    /// JSON snapshot of the config that startup changes are reported against
+    #[serde(skip)]
+    pub config_baseline_file: Option<String>,
+    /// JSON file of named strategies, each a set of field overrides on this config
+    #[serde(skip)]
+    pub strategies_file: Option<String>,
+    /// Strategy from `strategies_file` to start with
+    #[serde(skip)]
+    pub active_strategy: Option<String>,
 }
 
 impl Default for Config {
@@ -25,19 +285,123 @@ impl Default for Config {
         Self {
             grpc_endpoint: "".to_string(),
             rpc_endpoint: "".to_string(),
+            rpc_endpoints: Vec::new(),
+            commitment: None,
             market_cap_threshold_usd: 8000.0,
+            market_cap_threshold_sol: None,
+            min_real_sol_reserves: None,
+            adaptive_threshold: false,
+            adaptive_min_threshold_usd: 5_000.0,
+            adaptive_max_threshold_usd: 30_000.0,
+            adaptive_step_usd: 1_000.0,
+            adaptive_target_win_rate: 0.5,
+            adaptive_window: 20,
             max_slippage_bps: 500,
             buy_amount_sol: 50_000_000,
+            buy_amount_pct: None,
+            buy_amount_usd: None,
+            buy_token_amount: None,
+            buy_token_max_cost_sol: None,
             priority_fee_sol: 5_000_000,
+            dynamic_priority_fee: false,
+            priority_fee_percentile: 75,
+            min_priority_fee: 1_000,
+            max_priority_fee: 1_000_000,
             compute_unit_limit: 200_000,
+            take_profit_bps: None,
+            min_net_profit_usd: None,
+            stop_loss_bps: None,
+            coalesce_curve_fetches: true,
+            split_oversized_buy: true,
+            max_acceptable_slot_lag: None,
+            pause_buys_on_lag: false,
+            shadow_thresholds_usd: Vec::new(),
+            require_http_uri: false,
+            require_metadata: false,
+            metadata_required_fields: Vec::new(),
+            ipfs_gateway: DEFAULT_IPFS_GATEWAY.to_string(),
+            reconnect_base_backoff_ms: 500,
+            reconnect_max_backoff_secs: 30,
+            monitor_max_tokens: 200,
+            monitor_token_ttl_secs: 600,
+            monitor_csv_path: None,
+            monitor_rpc_rps: 20.0,
+            fetch_retry_delays_ms: vec![0, 100, 200],
+            require_no_mint_authority: false,
+            require_no_freeze_authority: false,
+            heartbeat_interval_seconds: 60,
+            stats_interval_seconds: 60,
+            statsd_addr: None,
+            webhook_url: None,
+            capture_path: None,
+            replay_path: None,
+            replay_speed: 1.0,
+            sol_price_source: "coingecko".to_string(),
+            coingecko_api_key: None,
+            price_timeout_ms: 3_000,
+            price_cache_secs: 30,
+            max_tracked_tokens: 1_000,
+            cache_ttl_secs: 600,
+            max_cached_curves: 10_000,
+            dedup_curve_updates: true,
+            buy_score_threshold: None,
+            score_weights: ScoringWeights::default(),
+            score_momentum_target_buys: 20,
+            score_liquidity_target_sol: 5_000_000_000,
+            confirm_timeout_secs: 0,
+            verify_buy_balance: false,
+            max_buy_retries: 2,
+            min_expected_tokens: 1,
+            trade_migrated_on_amm: false,
+            jito_block_engine_url: None,
+            jito_tip_lamports: 10_000,
+            nonce_account: None,
+            nonce_authority: None,
+            max_total_spend_sol: None,
+            daily_spend_cap_sol: None,
+            max_portfolio_exposure_sol: None,
+            max_concurrent_positions: None,
+            parser_workers: 2,
+            buy_cooldown_ms: 0,
+            min_token_age_secs: None,
+            min_slots_since_creation: None,
+            max_token_age_secs: None,
+            max_buys_per_creator_per_window: None,
+            creator_buy_window_seconds: 3_600,
+            validate_fee_recipient: false,
+            creator_reputation_path: None,
+            min_creator_reputation: None,
+            max_dev_holding_pct: None,
+            min_safety_score: None,
+            creator_blacklist: HashSet::new(),
+            creator_whitelist: HashSet::new(),
+            stream_filter: StreamFilter::default(),
+            name_include_regex: None,
+            name_exclude_regex: None,
+            admin_socket: None,
+            metrics_port: None,
+            metrics_bind: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            config_baseline_file: None,
+            strategies_file: None,
+            active_strategy: None,
         }
     }
 }
 
+/// Layout of a TOML config file: every field under a `[sniper]` table
+#[derive(Debug, Serialize, Deserialize)]
+struct ConfigFile {
+    sniper: Config,
+}
+
 impl Config {
-    /// Load config
+    /// Load config from env vars, on top of the TOML file at `CONFIG_FILE` if set.
+    /// Validated when the sniper is built
     pub fn from_env() -> Result<Self, SniperError> {
-        let mut config = Self::default();
+        let mut config = match std::env::var("CONFIG_FILE") {
+            Ok(path) if !path.is_empty() => Self::read_toml(Path::new(&path))?,
+            _ => Self::default(),
+        };
 
         if let Ok(endpoint) = std::env::var("GRPC_ENDPOINT") {
             config.grpc_endpoint = endpoint;
@@ -53,6 +417,54 @@ impl Config {
             })?;
         }
 
+        if let Ok(threshold) = std::env::var("MARKET_CAP_THRESHOLD_SOL") {
+            config.market_cap_threshold_sol = Some(threshold.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid SOL market cap threshold".to_string())
+            })?);
+        }
+
+        if let Ok(min_reserves) = std::env::var("MIN_REAL_SOL_RESERVES") {
+            config.min_real_sol_reserves = Some(min_reserves.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid min real SOL reserves".to_string())
+            })?);
+        }
+
+        if let Ok(adaptive) = std::env::var("ADAPTIVE_THRESHOLD") {
+            config.adaptive_threshold = adaptive.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid adaptive threshold flag".to_string())
+            })?;
+        }
+
+        if let Ok(min) = std::env::var("ADAPTIVE_MIN_THRESHOLD_USD") {
+            config.adaptive_min_threshold_usd = min.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid adaptive min threshold".to_string())
+            })?;
+        }
+
+        if let Ok(max) = std::env::var("ADAPTIVE_MAX_THRESHOLD_USD") {
+            config.adaptive_max_threshold_usd = max.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid adaptive max threshold".to_string())
+            })?;
+        }
+
+        if let Ok(step) = std::env::var("ADAPTIVE_STEP_USD") {
+            config.adaptive_step_usd = step
+                .parse()
+                .map_err(|_| SniperError::InvalidConfig("Invalid adaptive step".to_string()))?;
+        }
+
+        if let Ok(target) = std::env::var("ADAPTIVE_TARGET_WIN_RATE") {
+            config.adaptive_target_win_rate = target.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid adaptive target win rate".to_string())
+            })?;
+        }
+
+        if let Ok(window) = std::env::var("ADAPTIVE_WINDOW") {
+            config.adaptive_window = window
+                .parse()
+                .map_err(|_| SniperError::InvalidConfig("Invalid adaptive window".to_string()))?;
+        }
+
         if let Ok(slippage) = std::env::var("MAX_SLIPPAGE_BPS") {
             config.max_slippage_bps = slippage
                 .parse()
@@ -65,29 +477,817 @@ impl Config {
                 .map_err(|_| SniperError::InvalidConfig("Invalid buy amount".to_string()))?;
         }
 
+        if let Ok(pct) = std::env::var("BUY_AMOUNT_PCT") {
+            config.buy_amount_pct = Some(pct.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid buy amount percent".to_string())
+            })?);
+        }
+
+        if let Ok(usd) = std::env::var("BUY_AMOUNT_USD") {
+            config.buy_amount_usd =
+                Some(usd.parse().map_err(|_| {
+                    SniperError::InvalidConfig("Invalid buy amount USD".to_string())
+                })?);
+        }
+
+        if let Ok(tokens) = std::env::var("BUY_TOKEN_AMOUNT") {
+            config.buy_token_amount =
+                Some(tokens.parse().map_err(|_| {
+                    SniperError::InvalidConfig("Invalid buy token amount".to_string())
+                })?);
+        }
+
+        if let Ok(max_cost) = std::env::var("BUY_TOKEN_MAX_COST_SOL") {
+            config.buy_token_max_cost_sol = Some(max_cost.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid buy token max cost".to_string())
+            })?);
+        }
+
         if let Ok(fee) = std::env::var("PRIORITY_FEE_SOL") {
             config.priority_fee_sol = fee
                 .parse()
                 .map_err(|_| SniperError::InvalidConfig("Invalid priority fee".to_string()))?;
         }
 
+        if let Ok(enabled) = std::env::var("DYNAMIC_PRIORITY_FEE") {
+            config.dynamic_priority_fee = enabled.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid dynamic priority fee flag".to_string())
+            })?;
+        }
+
+        if let Ok(percentile) = std::env::var("PRIORITY_FEE_PERCENTILE") {
+            config.priority_fee_percentile = percentile.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid priority fee percentile".to_string())
+            })?;
+        }
+
+        if let Ok(fee) = std::env::var("MIN_PRIORITY_FEE") {
+            config.min_priority_fee = fee
+                .parse()
+                .map_err(|_| SniperError::InvalidConfig("Invalid min priority fee".to_string()))?;
+        }
+
+        if let Ok(fee) = std::env::var("MAX_PRIORITY_FEE") {
+            config.max_priority_fee = fee
+                .parse()
+                .map_err(|_| SniperError::InvalidConfig("Invalid max priority fee".to_string()))?;
+        }
+
         if let Ok(limit) = std::env::var("COMPUTE_UNIT_LIMIT") {
             config.compute_unit_limit = limit.parse().map_err(|_| {
                 SniperError::InvalidConfig("Invalid compute unit limit".to_string())
             })?;
         }
 
+        if let Ok(take_profit) = std::env::var("TAKE_PROFIT_BPS") {
+            config.take_profit_bps = Some(
+                take_profit
+                    .parse()
+                    .map_err(|_| SniperError::InvalidConfig("Invalid take profit".to_string()))?,
+            );
+        }
+
+        if let Ok(floor) = std::env::var("MIN_NET_PROFIT_USD") {
+            config.min_net_profit_usd = Some(floor.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid min net profit USD".to_string())
+            })?);
+        }
+
+        if let Ok(stop_loss) = std::env::var("STOP_LOSS_BPS") {
+            config.stop_loss_bps = Some(
+                stop_loss
+                    .parse()
+                    .map_err(|_| SniperError::InvalidConfig("Invalid stop loss".to_string()))?,
+            );
+        }
+
+        if let Ok(coalesce) = std::env::var("COALESCE_CURVE_FETCHES") {
+            config.coalesce_curve_fetches = coalesce.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid coalesce curve fetches flag".to_string())
+            })?;
+        }
+
+        if let Ok(split) = std::env::var("SPLIT_OVERSIZED_BUY") {
+            config.split_oversized_buy = split.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid split oversized buy flag".to_string())
+            })?;
+        }
+
+        if let Ok(lag) = std::env::var("MAX_ACCEPTABLE_SLOT_LAG") {
+            config.max_acceptable_slot_lag = Some(
+                lag.parse()
+                    .map_err(|_| SniperError::InvalidConfig("Invalid slot lag".to_string()))?,
+            );
+        }
+
+        if let Ok(pause) = std::env::var("PAUSE_BUYS_ON_LAG") {
+            config.pause_buys_on_lag = pause.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid pause buys on lag flag".to_string())
+            })?;
+        }
+
+        if let Ok(thresholds) = std::env::var("SHADOW_THRESHOLDS_USD") {
+            config.shadow_thresholds_usd = thresholds
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(|t| t.parse())
+                .collect::<Result<_, _>>()
+                .map_err(|_| SniperError::InvalidConfig("Invalid shadow thresholds".to_string()))?;
+        }
+
+        if let Ok(require) = std::env::var("REQUIRE_HTTP_URI") {
+            config.require_http_uri = require.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid require http uri flag".to_string())
+            })?;
+        }
+
+        if let Ok(require) = std::env::var("REQUIRE_METADATA") {
+            config.require_metadata = require.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid require metadata flag".to_string())
+            })?;
+        }
+
+        if let Ok(fields) = std::env::var("METADATA_REQUIRED_FIELDS") {
+            config.metadata_required_fields = fields
+                .split(',')
+                .map(|f| f.trim().to_ascii_lowercase())
+                .filter(|f| !f.is_empty())
+                .collect();
+        }
+
+        if let Ok(gateway) = std::env::var("IPFS_GATEWAY") {
+            config.ipfs_gateway = gateway;
+        }
+
+        if let Ok(base) = std::env::var("RECONNECT_BASE_BACKOFF_MS") {
+            config.reconnect_base_backoff_ms = base.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid reconnect base backoff".to_string())
+            })?;
+        }
+
+        if let Ok(max) = std::env::var("RECONNECT_MAX_BACKOFF_SECS") {
+            config.reconnect_max_backoff_secs = max.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid reconnect max backoff".to_string())
+            })?;
+        }
+
+        if let Ok(max) = std::env::var("MONITOR_MAX_TOKENS") {
+            config.monitor_max_tokens = max.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid monitor max tokens".to_string())
+            })?;
+        }
+
+        if let Ok(ttl) = std::env::var("MONITOR_TOKEN_TTL_SECS") {
+            config.monitor_token_ttl_secs = ttl
+                .parse()
+                .map_err(|_| SniperError::InvalidConfig("Invalid monitor token TTL".to_string()))?;
+        }
+
+        if let Ok(path) = std::env::var("MONITOR_CSV_PATH") {
+            config.monitor_csv_path = Some(path).filter(|p| !p.is_empty());
+        }
+
+        if let Ok(rps) = std::env::var("MONITOR_RPC_RPS") {
+            config.monitor_rpc_rps = rps
+                .parse()
+                .map_err(|_| SniperError::InvalidConfig("Invalid monitor RPC rate".to_string()))?;
+        }
+
+        if let Ok(delays) = std::env::var("FETCH_RETRY_DELAYS_MS") {
+            config.fetch_retry_delays_ms = delays
+                .split(',')
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .map(|d| d.parse())
+                .collect::<Result<_, _>>()
+                .map_err(|_| {
+                    SniperError::InvalidConfig("Invalid fetch retry delays".to_string())
+                })?;
+        }
+
+        if let Ok(require) = std::env::var("REQUIRE_NO_MINT_AUTHORITY") {
+            config.require_no_mint_authority = require.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid require no mint authority flag".to_string())
+            })?;
+        }
+
+        if let Ok(require) = std::env::var("REQUIRE_NO_FREEZE_AUTHORITY") {
+            config.require_no_freeze_authority = require.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid require no freeze authority flag".to_string())
+            })?;
+        }
+
+        if let Ok(interval) = std::env::var("HEARTBEAT_INTERVAL_SECONDS") {
+            config.heartbeat_interval_seconds = interval.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid heartbeat interval".to_string())
+            })?;
+        }
+
+        if let Ok(interval) = std::env::var("STATS_INTERVAL_SECONDS") {
+            config.stats_interval_seconds = interval
+                .parse()
+                .map_err(|_| SniperError::InvalidConfig("Invalid stats interval".to_string()))?;
+        }
+
+        if let Ok(address) = std::env::var("STATSD_ADDR") {
+            config.statsd_addr = Some(address).filter(|a| !a.is_empty());
+        }
+
+        if let Ok(url) = std::env::var("WEBHOOK_URL") {
+            config.webhook_url = Some(url).filter(|u| !u.is_empty());
+        }
+
+        if let Ok(path) = std::env::var("CAPTURE_PATH") {
+            config.capture_path = Some(path).filter(|p| !p.is_empty());
+        }
+
+        if let Ok(path) = std::env::var("REPLAY_PATH") {
+            config.replay_path = Some(path).filter(|p| !p.is_empty());
+        }
+
+        if let Ok(speed) = std::env::var("REPLAY_SPEED") {
+            config.replay_speed = speed
+                .parse()
+                .map_err(|_| SniperError::InvalidConfig("Invalid replay speed".to_string()))?;
+        }
+
+        if let Ok(source) = std::env::var("SOL_PRICE_SOURCE") {
+            config.sol_price_source = source.trim().to_lowercase();
+        }
+
+        if let Ok(key) = std::env::var("COINGECKO_API_KEY") {
+            config.coingecko_api_key = Some(key.trim().to_string()).filter(|k| !k.is_empty());
+        }
+
+        if let Ok(timeout) = std::env::var("PRICE_TIMEOUT_MS") {
+            config.price_timeout_ms = timeout
+                .parse()
+                .map_err(|_| SniperError::InvalidConfig("Invalid price timeout".to_string()))?;
+        }
+
+        if let Ok(secs) = std::env::var("PRICE_CACHE_SECS") {
+            config.price_cache_secs = secs.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid price cache duration".to_string())
+            })?;
+        }
+
+        if let Ok(max) = std::env::var("MAX_TRACKED_TOKENS") {
+            config.max_tracked_tokens = max.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid max tracked tokens".to_string())
+            })?;
+        }
+
+        if let Ok(ttl) = std::env::var("CACHE_TTL_SECS") {
+            config.cache_ttl_secs = ttl
+                .parse()
+                .map_err(|_| SniperError::InvalidConfig("Invalid cache TTL".to_string()))?;
+        }
+
+        if let Ok(max) = std::env::var("MAX_CACHED_CURVES") {
+            config.max_cached_curves = max
+                .parse()
+                .map_err(|_| SniperError::InvalidConfig("Invalid max cached curves".to_string()))?;
+        }
+
+        if let Ok(dedup) = std::env::var("DEDUP_CURVE_UPDATES") {
+            config.dedup_curve_updates = dedup.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid dedup curve updates flag".to_string())
+            })?;
+        }
+
+        if let Ok(endpoints) = std::env::var("RPC_ENDPOINTS") {
+            config.rpc_endpoints = endpoints
+                .split(',')
+                .map(str::trim)
+                .filter(|e| !e.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+
+        if let Ok(commitment) = std::env::var("COMMITMENT") {
+            config.commitment = Some(parse_commitment(&commitment).ok_or_else(|| {
+                SniperError::InvalidConfig("Invalid commitment level".to_string())
+            })?);
+        }
+
+        if let Ok(threshold) = std::env::var("BUY_SCORE_THRESHOLD") {
+            config.buy_score_threshold = Some(threshold.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid buy score threshold".to_string())
+            })?);
+        }
+
+        if let Ok(weights) = std::env::var("SCORE_WEIGHTS") {
+            let weights = weights
+                .split(',')
+                .map(|w| w.trim().parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .ok()
+                .filter(|w| w.len() == 3)
+                .ok_or_else(|| {
+                    SniperError::InvalidConfig(
+                        "Score weights must be three numbers: cap,momentum,liquidity".to_string(),
+                    )
+                })?;
+            config.score_weights = ScoringWeights {
+                market_cap: weights[0],
+                momentum: weights[1],
+                liquidity: weights[2],
+            };
+        }
+
+        if let Ok(target) = std::env::var("SCORE_MOMENTUM_TARGET_BUYS") {
+            config.score_momentum_target_buys = target.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid score momentum target".to_string())
+            })?;
+        }
+
+        if let Ok(target) = std::env::var("SCORE_LIQUIDITY_TARGET_SOL") {
+            config.score_liquidity_target_sol = target.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid score liquidity target".to_string())
+            })?;
+        }
+
+        if let Ok(timeout) = std::env::var("CONFIRM_TIMEOUT_SECS") {
+            config.confirm_timeout_secs = timeout
+                .parse()
+                .map_err(|_| SniperError::InvalidConfig("Invalid confirm timeout".to_string()))?;
+        }
+
+        if let Ok(retries) = std::env::var("MAX_BUY_RETRIES") {
+            config.max_buy_retries = retries
+                .parse()
+                .map_err(|_| SniperError::InvalidConfig("Invalid max buy retries".to_string()))?;
+        }
+
+        if let Ok(min_tokens) = std::env::var("MIN_EXPECTED_TOKENS") {
+            config.min_expected_tokens = min_tokens.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid min expected tokens".to_string())
+            })?;
+        }
+
+        if let Ok(verify) = std::env::var("VERIFY_BUY_BALANCE") {
+            config.verify_buy_balance = verify.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid verify buy balance flag".to_string())
+            })?;
+        }
+
+        if let Ok(enabled) = std::env::var("TRADE_MIGRATED_ON_AMM") {
+            config.trade_migrated_on_amm = enabled.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid trade migrated on AMM flag".to_string())
+            })?;
+        }
+
+        if let Ok(url) = std::env::var("JITO_BLOCK_ENGINE_URL") {
+            config.jito_block_engine_url = Some(url).filter(|u| !u.is_empty());
+        }
+
+        if let Ok(tip) = std::env::var("JITO_TIP_LAMPORTS") {
+            config.jito_tip_lamports = tip
+                .parse()
+                .map_err(|_| SniperError::InvalidConfig("Invalid Jito tip".to_string()))?;
+        }
+
+        if let Ok(account) = std::env::var("NONCE_ACCOUNT") {
+            config.nonce_account =
+                Some(account.parse().map_err(|_| {
+                    SniperError::InvalidConfig("Invalid nonce account".to_string())
+                })?);
+        }
+
+        if let Ok(authority) = std::env::var("NONCE_AUTHORITY") {
+            config.nonce_authority =
+                Some(authority.parse().map_err(|_| {
+                    SniperError::InvalidConfig("Invalid nonce authority".to_string())
+                })?);
+        }
+
+        if let Ok(max_spend) = std::env::var("MAX_TOTAL_SPEND_SOL") {
+            config.max_total_spend_sol =
+                Some(max_spend.parse().map_err(|_| {
+                    SniperError::InvalidConfig("Invalid max total spend".to_string())
+                })?);
+        }
+
+        if let Ok(daily_cap) = std::env::var("DAILY_SPEND_CAP_SOL") {
+            config.daily_spend_cap_sol =
+                Some(daily_cap.parse().map_err(|_| {
+                    SniperError::InvalidConfig("Invalid daily spend cap".to_string())
+                })?);
+        }
+
+        if let Ok(max_exposure) = std::env::var("MAX_PORTFOLIO_EXPOSURE_SOL") {
+            config.max_portfolio_exposure_sol = Some(max_exposure.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid max portfolio exposure".to_string())
+            })?);
+        }
+
+        if let Ok(max_positions) = std::env::var("MAX_CONCURRENT_POSITIONS") {
+            config.max_concurrent_positions = Some(max_positions.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid max concurrent positions".to_string())
+            })?);
+        }
+
+        if let Ok(enabled) = std::env::var("VALIDATE_FEE_RECIPIENT") {
+            config.validate_fee_recipient = enabled.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid validate fee recipient flag".to_string())
+            })?;
+        }
+
+        if let Ok(cooldown) = std::env::var("BUY_COOLDOWN_MS") {
+            config.buy_cooldown_ms = cooldown
+                .parse()
+                .map_err(|_| SniperError::InvalidConfig("Invalid buy cooldown".to_string()))?;
+        }
+
+        if let Ok(min_age) = std::env::var("MIN_TOKEN_AGE_SECS") {
+            config.min_token_age_secs =
+                Some(min_age.parse().map_err(|_| {
+                    SniperError::InvalidConfig("Invalid min token age".to_string())
+                })?);
+        }
+
+        if let Ok(max_age) = std::env::var("MAX_TOKEN_AGE_SECS") {
+            config.max_token_age_secs =
+                Some(max_age.parse().map_err(|_| {
+                    SniperError::InvalidConfig("Invalid max token age".to_string())
+                })?);
+        }
+
+        if let Ok(min_slots) = std::env::var("MIN_SLOTS_SINCE_CREATION") {
+            config.min_slots_since_creation = Some(min_slots.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid min slots since creation".to_string())
+            })?);
+        }
+
+        if let Ok(max_buys) = std::env::var("MAX_BUYS_PER_CREATOR_PER_WINDOW") {
+            config.max_buys_per_creator_per_window = Some(max_buys.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid max buys per creator".to_string())
+            })?);
+        }
+
+        if let Ok(window) = std::env::var("CREATOR_BUY_WINDOW_SECONDS") {
+            config.creator_buy_window_seconds = window.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid creator buy window".to_string())
+            })?;
+        }
+
+        if let Ok(workers) = std::env::var("PARSER_WORKERS") {
+            config.parser_workers = workers
+                .parse()
+                .map_err(|_| SniperError::InvalidConfig("Invalid parser workers".to_string()))?;
+        }
+
+        if let Ok(path) = std::env::var("CREATOR_REPUTATION_PATH") {
+            config.creator_reputation_path = Some(path).filter(|p| !p.is_empty());
+        }
+
+        if let Ok(min) = std::env::var("MIN_CREATOR_REPUTATION") {
+            config.min_creator_reputation = Some(min.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid min creator reputation".to_string())
+            })?);
+        }
+
+        if let Ok(min) = std::env::var("MIN_SAFETY_SCORE") {
+            config.min_safety_score =
+                Some(min.parse().map_err(|_| {
+                    SniperError::InvalidConfig("Invalid min safety score".to_string())
+                })?);
+        }
+
+        if let Ok(max_pct) = std::env::var("MAX_DEV_HOLDING_PCT") {
+            config.max_dev_holding_pct = Some(max_pct.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid max dev holding pct".to_string())
+            })?);
+        }
+
+        if let Ok(creators) = std::env::var("CREATOR_BLACKLIST") {
+            config.creator_blacklist = parse_pubkey_list(&creators)
+                .map_err(|_| SniperError::InvalidConfig("Invalid creator blacklist".to_string()))?;
+        }
+
+        if let Ok(creators) = std::env::var("CREATOR_WHITELIST") {
+            config.creator_whitelist = parse_pubkey_list(&creators)
+                .map_err(|_| SniperError::InvalidConfig("Invalid creator whitelist".to_string()))?;
+        }
+
+        if let Ok(mints) = std::env::var("STREAM_WATCH_MINTS") {
+            config.stream_filter.mints = parse_pubkey_list(&mints).map_err(|_| {
+                SniperError::InvalidConfig("Invalid stream watch mints".to_string())
+            })?;
+        }
+
+        if let Ok(creators) = std::env::var("STREAM_WATCH_CREATORS") {
+            config.stream_filter.creators = parse_pubkey_list(&creators).map_err(|_| {
+                SniperError::InvalidConfig("Invalid stream watch creators".to_string())
+            })?;
+        }
+
+        if let Ok(pattern) = std::env::var("NAME_INCLUDE_REGEX") {
+            config.name_include_regex = Some(pattern).filter(|p| !p.is_empty());
+        }
+
+        if let Ok(pattern) = std::env::var("NAME_EXCLUDE_REGEX") {
+            config.name_exclude_regex = Some(pattern).filter(|p| !p.is_empty());
+        }
+
+        if let Ok(address) = std::env::var("ADMIN_SOCKET") {
+            config.admin_socket = Some(address).filter(|a| !a.is_empty());
+        }
+
+        if let Ok(port) = std::env::var("METRICS_PORT") {
+            config.metrics_port = Some(
+                port.parse()
+                    .map_err(|_| SniperError::InvalidConfig("Invalid metrics port".to_string()))?,
+            );
+        }
+
+        if let Ok(address) = std::env::var("METRICS_BIND") {
+            config.metrics_bind = address.parse().map_err(|_| {
+                SniperError::InvalidConfig("Invalid metrics bind address".to_string())
+            })?;
+        }
+
+        if let Ok(path) = std::env::var("CONFIG_BASELINE_FILE") {
+            config.config_baseline_file = Some(path).filter(|p| !p.is_empty());
+        }
+
+        if let Ok(path) = std::env::var("STRATEGIES_FILE") {
+            config.strategies_file = Some(path).filter(|p| !p.is_empty());
+        }
+
+        if let Ok(name) = std::env::var("STRATEGY") {
+            config.active_strategy = Some(name).filter(|n| !n.is_empty());
+        }
+
+        Ok(config)
+    }
+
+    /// Validated config from the `[sniper]` table of a TOML file; missing fields keep
+    /// their defaults
+    pub fn from_toml_path(path: &Path) -> Result<Config, SniperError> {
+        let config = Self::read_toml(path)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn read_toml(path: &Path) -> Result<Config, SniperError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| SniperError::InvalidConfig(format!("Read {}: {}", path.display(), e)))?;
+        // the error names the offending key and its line
+        let file: ConfigFile = toml::from_str(&contents).map_err(|e| {
+            SniperError::InvalidConfig(format!("Invalid config file {}: {}", path.display(), e))
+        })?;
+        Ok(file.sniper)
+    }
+
+    /// This config with `overrides` (a JSON object of field values) applied on top.
+    /// `STARTUP_ONLY_FIELDS` are rejected, since switching to the result can't apply them
+    pub fn with_overrides(&self, overrides: &serde_json::Value) -> Result<Config, SniperError> {
+        let serde_json::Value::Object(overrides) = overrides else {
+            return Err(SniperError::InvalidConfig(
+                "Config overrides must be a JSON object".to_string(),
+            ));
+        };
+        let mut fields = match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(fields)) => fields,
+            _ => {
+                return Err(SniperError::InvalidConfig(
+                    "Config is not serializable".to_string(),
+                ))
+            }
+        };
+
+        for (field, value) in overrides {
+            if !fields.contains_key(field) {
+                return Err(SniperError::InvalidConfig(format!(
+                    "Unknown config field {:?}",
+                    field
+                )));
+            }
+            if STARTUP_ONLY_FIELDS.contains(&field.as_str()) {
+                return Err(SniperError::InvalidConfig(format!(
+                    "{:?} is only read at startup and can't be overridden",
+                    field
+                )));
+            }
+            fields.insert(field.clone(), value.clone());
+        }
+
+        let mut config: Config = serde_json::from_value(serde_json::Value::Object(fields))
+            .map_err(|e| SniperError::InvalidConfig(format!("Invalid config override: {}", e)))?;
+        config.config_baseline_file = self.config_baseline_file.clone();
+        config.strategies_file = self.strategies_file.clone();
+        config.active_strategy = self.active_strategy.clone();
         Ok(config)
     }
 
+    /// Validated configs for each strategy in `strategies_file`, empty if none is set
+    pub fn load_strategies(&self) -> Result<HashMap<String, Config>, SniperError> {
+        let Some(path) = self.strategies_file.as_deref() else {
+            return Ok(HashMap::new());
+        };
+
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| SniperError::InvalidConfig(format!("Read {}: {}", path, e)))?;
+        let overrides: HashMap<String, serde_json::Value> = serde_json::from_str(&contents)
+            .map_err(|e| {
+                SniperError::InvalidConfig(format!("Invalid strategies file {}: {}", path, e))
+            })?;
+
+        overrides
+            .into_iter()
+            .map(|(name, overrides)| {
+                let config = self
+                    .with_overrides(&overrides)
+                    .and_then(|config| config.validate().map(|_| config))
+                    .map_err(|e| SniperError::InvalidConfig(format!("Strategy {}: {}", name, e)))?;
+                Ok((name, config))
+            })
+            .collect()
+    }
+
+    /// Fields that differ from `other` as (field, other's value, this value), ordered by field
+    pub fn diff(&self, other: &Config) -> Vec<(String, String, String)> {
+        let (Ok(serde_json::Value::Object(new)), Ok(serde_json::Value::Object(old))) =
+            (serde_json::to_value(self), serde_json::to_value(other))
+        else {
+            return Vec::new();
+        };
+        diff_fields(new, &old)
+    }
+
+    /// Config as JSON with each `REDACTED_FIELDS` value replaced by a fingerprint, so the
+    /// baseline shows a secret changed without storing it
+    fn redacted_snapshot(&self) -> Result<serde_json::Map<String, serde_json::Value>, SniperError> {
+        let serde_json::Value::Object(mut fields) = serde_json::to_value(self)
+            .map_err(|e| SniperError::InvalidConfig(format!("Config snapshot: {}", e)))?
+        else {
+            return Err(SniperError::InvalidConfig(
+                "Config snapshot: not an object".to_string(),
+            ));
+        };
+        for field in REDACTED_FIELDS {
+            if let Some(value) = fields.get_mut(*field).filter(|value| !value.is_null()) {
+                let digest = solana_sdk::hash::hash(value.to_string().as_bytes()).to_string();
+                *value = serde_json::Value::String(format!("redacted:{}", &digest[..16]));
+            }
+        }
+        Ok(fields)
+    }
+
+    /// Log fields changed since the `config_baseline_file` snapshot, writing the snapshot on
+    /// first run. Secrets are only stored and compared as fingerprints
+    pub fn check_baseline(&self) -> Result<(), SniperError> {
+        let Some(path) = self.config_baseline_file.as_deref().map(Path::new) else {
+            return Ok(());
+        };
+
+        let current = self.redacted_snapshot()?;
+        if !path.exists() {
+            let snapshot = serde_json::to_string_pretty(&current)
+                .map_err(|e| SniperError::InvalidConfig(format!("Config snapshot: {}", e)))?;
+            std::fs::write(path, snapshot).map_err(|e| {
+                SniperError::InvalidConfig(format!("Write {}: {}", path.display(), e))
+            })?;
+            info!("Saved config baseline to {}", path.display());
+            return Ok(());
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| SniperError::InvalidConfig(format!("Read {}: {}", path.display(), e)))?;
+        let baseline: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&contents)
+            .map_err(|e| {
+                SniperError::InvalidConfig(format!(
+                    "Invalid config baseline {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+
+        let changes = diff_fields(current, &baseline);
+        if changes.is_empty() {
+            info!("Config matches baseline {}", path.display());
+        }
+        for (field, old, new) in changes {
+            if REDACTED_FIELDS.contains(&field.as_str()) {
+                warn!("Config changed since baseline: {} (redacted)", field);
+            } else {
+                warn!(
+                    "Config changed since baseline: {} {} -> {}",
+                    field, old, new
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Why a creator's tokens are excluded, or `None` if they pass the black/whitelists
+    pub fn creator_filter_reason(&self, creator: &Pubkey) -> Option<&'static str> {
+        if self.creator_blacklist.contains(creator) {
+            Some("blacklisted creator")
+        } else if !self.creator_whitelist.is_empty() && !self.creator_whitelist.contains(creator) {
+            Some("creator not whitelisted")
+        } else {
+            None
+        }
+    }
+
+    /// Commitment for RPC reads, confirmed unless set
+    pub fn commitment_config(&self) -> CommitmentConfig {
+        CommitmentConfig {
+            commitment: self.commitment.unwrap_or(CommitmentLevel::Confirmed),
+        }
+    }
+
+    /// Commitment for the stream subscription and send preflight, processed unless set
+    pub fn stream_commitment(&self) -> CommitmentLevel {
+        self.commitment.unwrap_or(CommitmentLevel::Processed)
+    }
+
+    /// Every configured RPC endpoint, `rpc_endpoint` first, without duplicates
+    pub fn rpc_endpoint_list(&self) -> Vec<String> {
+        let mut endpoints = Vec::new();
+        for endpoint in std::iter::once(&self.rpc_endpoint).chain(&self.rpc_endpoints) {
+            if !endpoint.is_empty() && !endpoints.contains(endpoint) {
+                endpoints.push(endpoint.clone());
+            }
+        }
+        if endpoints.is_empty() {
+            endpoints.push(self.rpc_endpoint.clone());
+        }
+        endpoints
+    }
+
     /// Validate configuration
     pub fn validate(&self) -> Result<(), SniperError> {
+        // a replay reads its capture instead of the stream
+        if self.replay_path.is_none() {
+            check_endpoint(
+                "gRPC endpoint",
+                &self.grpc_endpoint,
+                &["http", "https", "grpc"],
+            )?;
+        }
+        for endpoint in std::iter::once(&self.rpc_endpoint).chain(&self.rpc_endpoints) {
+            check_endpoint("RPC endpoint", endpoint, &["http", "https"])?;
+        }
+        if let Some(url) = &self.webhook_url {
+            check_endpoint("Webhook URL", url, &["http", "https"])?;
+        }
+
         if self.market_cap_threshold_usd <= 0.0 {
             return Err(SniperError::InvalidConfig(
                 "Market cap threshold must be positive".to_string(),
             ));
         }
 
+        if let Some(url) = &self.jito_block_engine_url {
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                return Err(SniperError::InvalidConfig(format!(
+                    "Jito block engine URL must be http(s): {}",
+                    url
+                )));
+            }
+            if self.jito_tip_lamports == 0 {
+                return Err(SniperError::InvalidConfig(
+                    "Jito tip must be greater than 0".to_string(),
+                ));
+            }
+        }
+
+        if self.nonce_authority.is_some() && self.nonce_account.is_none() {
+            return Err(SniperError::InvalidConfig(
+                "Nonce authority is set without a nonce account".to_string(),
+            ));
+        }
+        // concurrent buys would all sign against the same nonce, and only one can land
+        if self.nonce_account.is_some() && self.max_concurrent_positions.is_some_and(|n| n > 1) {
+            return Err(SniperError::InvalidConfig(
+                "Nonce account cannot be used with more than one concurrent position".to_string(),
+            ));
+        }
+
+        if self.market_cap_threshold_sol == Some(0) {
+            return Err(SniperError::InvalidConfig(
+                "SOL market cap threshold must be positive".to_string(),
+            ));
+        }
+        // the SOL threshold path skips the USD-based shadow, scoring and adaptive gates
+        if self.market_cap_threshold_sol.is_some() {
+            let usd_only = [
+                ("shadow thresholds", !self.shadow_thresholds_usd.is_empty()),
+                ("a buy score threshold", self.buy_score_threshold.is_some()),
+                ("the adaptive threshold", self.adaptive_threshold),
+            ];
+            if let Some((feature, _)) = usd_only.iter().find(|(_, enabled)| *enabled) {
+                return Err(SniperError::InvalidConfig(format!(
+                    "SOL market cap threshold can't be combined with {}",
+                    feature
+                )));
+            }
+        }
+
         if self.max_slippage_bps > 10000 {
             return Err(SniperError::InvalidConfig(
                 "Slippage cannot exceed 100%".to_string(),
@@ -100,6 +1300,254 @@ impl Config {
             ));
         }
 
+        if self
+            .buy_amount_pct
+            .is_some_and(|pct| !(pct > 0.0 && pct <= 100.0))
+        {
+            return Err(SniperError::InvalidConfig(
+                "Buy amount percent must be above 0 and at most 100".to_string(),
+            ));
+        }
+
+        if self
+            .buy_amount_usd
+            .is_some_and(|usd| !usd.is_finite() || usd <= 0.0)
+        {
+            return Err(SniperError::InvalidConfig(
+                "Buy amount USD must be positive".to_string(),
+            ));
+        }
+
+        if self.compute_unit_limit == 0 || self.compute_unit_limit > MAX_COMPUTE_UNIT_LIMIT {
+            return Err(SniperError::InvalidConfig(format!(
+                "Compute unit limit must be between 1 and {}, got {}",
+                MAX_COMPUTE_UNIT_LIMIT, self.compute_unit_limit
+            )));
+        }
+
+        // unit price is derived as priority_fee * 1e6 / compute_unit_limit
+        if self.priority_fee_sol.checked_mul(1_000_000).is_none() {
+            return Err(SniperError::InvalidConfig(
+                "Priority fee too large to convert to a compute unit price".to_string(),
+            ));
+        }
+
+        if self.priority_fee_percentile > 100 {
+            return Err(SniperError::InvalidConfig(
+                "Priority fee percentile must be between 0 and 100".to_string(),
+            ));
+        }
+
+        if self.min_priority_fee > self.max_priority_fee
+            || self.max_priority_fee > MAX_COMPUTE_UNIT_PRICE_MICRO_LAMPORTS
+        {
+            return Err(SniperError::InvalidConfig(format!(
+                "Priority fee bounds must satisfy min <= max <= {}",
+                MAX_COMPUTE_UNIT_PRICE_MICRO_LAMPORTS
+            )));
+        }
+
+        if self.shadow_thresholds_usd.iter().any(|t| *t <= 0.0) {
+            return Err(SniperError::InvalidConfig(
+                "Shadow thresholds must be positive".to_string(),
+            ));
+        }
+
+        if !matches!(
+            self.sol_price_source.as_str(),
+            "coingecko" | "jupiter" | "pyth"
+        ) {
+            return Err(SniperError::InvalidConfig(format!(
+                "Unknown SOL price source: {}",
+                self.sol_price_source
+            )));
+        }
+
+        NameFilter::from_config(self)?;
+
+        if self.min_expected_tokens == 0 {
+            return Err(SniperError::InvalidConfig(
+                "Min expected tokens must be at least 1".to_string(),
+            ));
+        }
+
+        if !(self.monitor_rpc_rps > 0.0 && self.monitor_rpc_rps.is_finite()) {
+            return Err(SniperError::InvalidConfig(
+                "Monitor RPC rate must be greater than zero".to_string(),
+            ));
+        }
+
+        if self.price_timeout_ms == 0 {
+            return Err(SniperError::InvalidConfig(
+                "Price timeout must be greater than zero".to_string(),
+            ));
+        }
+
+        if let Some(threshold) = self.buy_score_threshold {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(SniperError::InvalidConfig(
+                    "Buy score threshold must be between 0 and 1".to_string(),
+                ));
+            }
+            let ScoringWeights {
+                market_cap,
+                momentum,
+                liquidity,
+            } = self.score_weights;
+            if [market_cap, momentum, liquidity].iter().any(|w| *w < 0.0)
+                || market_cap + momentum + liquidity <= 0.0
+            {
+                return Err(SniperError::InvalidConfig(
+                    "Score weights must be non-negative and not all zero".to_string(),
+                ));
+            }
+        }
+
+        if self
+            .min_creator_reputation
+            .is_some_and(|min| !(0.0..=1.0).contains(&min))
+        {
+            return Err(SniperError::InvalidConfig(
+                "Min creator reputation must be between 0 and 1".to_string(),
+            ));
+        }
+
+        if self.min_safety_score.is_some_and(|min| min > 100) {
+            return Err(SniperError::InvalidConfig(
+                "Min safety score must be between 0 and 100".to_string(),
+            ));
+        }
+
+        if self.adaptive_threshold {
+            if !(0.0 < self.adaptive_min_threshold_usd
+                && self.adaptive_min_threshold_usd <= self.adaptive_max_threshold_usd)
+            {
+                return Err(SniperError::InvalidConfig(
+                    "Adaptive threshold bounds must be positive with min <= max".to_string(),
+                ));
+            }
+            if self.adaptive_step_usd <= 0.0 || self.adaptive_window == 0 {
+                return Err(SniperError::InvalidConfig(
+                    "Adaptive step and window must be greater than 0".to_string(),
+                ));
+            }
+            if !(0.0..=1.0).contains(&self.adaptive_target_win_rate) {
+                return Err(SniperError::InvalidConfig(
+                    "Adaptive target win rate must be between 0 and 1".to_string(),
+                ));
+            }
+        }
+
+        if self
+            .max_dev_holding_pct
+            .is_some_and(|pct| !(0.0..=100.0).contains(&pct))
+        {
+            return Err(SniperError::InvalidConfig(
+                "Max dev holding pct must be between 0 and 100".to_string(),
+            ));
+        }
+
+        if let (Some(min), Some(max)) = (self.min_token_age_secs, self.max_token_age_secs) {
+            if min > max {
+                return Err(SniperError::InvalidConfig(
+                    "Min token age must not exceed max token age".to_string(),
+                ));
+            }
+        }
+
+        if self.max_buys_per_creator_per_window.is_some() && self.creator_buy_window_seconds == 0 {
+            return Err(SniperError::InvalidConfig(
+                "Creator buy window must be greater than 0".to_string(),
+            ));
+        }
+
+        if let Some(field) = self
+            .metadata_required_fields
+            .iter()
+            .find(|f| !METADATA_FIELDS.contains(&f.as_str()))
+        {
+            return Err(SniperError::InvalidConfig(format!(
+                "Unknown metadata field {:?}, expected one of {}",
+                field,
+                METADATA_FIELDS.join(", ")
+            )));
+        }
+
+        if self.max_tracked_tokens == 0 {
+            return Err(SniperError::InvalidConfig(
+                "Max tracked tokens must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.max_concurrent_positions == Some(0) {
+            return Err(SniperError::InvalidConfig(
+                "Max concurrent positions must be at least 1".to_string(),
+            ));
+        }
+
+        if !self.replay_speed.is_finite() || self.replay_speed < 0.0 {
+            return Err(SniperError::InvalidConfig(
+                "Replay speed cannot be negative".to_string(),
+            ));
+        }
+
+        if self.max_cached_curves == 0 {
+            return Err(SniperError::InvalidConfig(
+                "Max cached curves must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.fetch_retry_delays_ms.is_empty() {
+            return Err(SniperError::InvalidConfig(
+                "At least one fetch attempt is required".to_string(),
+            ));
+        }
+
+        if self.reconnect_base_backoff_ms == 0 {
+            return Err(SniperError::InvalidConfig(
+                "Reconnect base backoff must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.take_profit_bps == Some(0) {
+            return Err(SniperError::InvalidConfig(
+                "Take profit cannot be zero".to_string(),
+            ));
+        }
+
+        if self
+            .min_net_profit_usd
+            .is_some_and(|floor| !floor.is_finite() || floor <= 0.0)
+        {
+            return Err(SniperError::InvalidConfig(
+                "Min net profit USD must be positive".to_string(),
+            ));
+        }
+
+        if matches!(self.stop_loss_bps, Some(0) | Some(10_001..)) {
+            return Err(SniperError::InvalidConfig(
+                "Stop loss must be between 1 and 10000 bps".to_string(),
+            ));
+        }
+
+        if self.buy_token_amount == Some(0) {
+            return Err(SniperError::InvalidConfig(
+                "Buy token amount cannot be zero".to_string(),
+            ));
+        }
+
+        if self.buy_token_amount.is_some() && self.buy_amount_pct.is_some() {
+            return Err(SniperError::InvalidConfig(
+                "Buy token amount and buy amount percent can't both be set".to_string(),
+            ));
+        }
+
+        if self.buy_token_max_cost_sol == Some(0) {
+            return Err(SniperError::InvalidConfig(
+                "Buy token max cost cannot be zero".to_string(),
+            ));
+        }
+
         if self.priority_fee_sol == 0 {
             return Err(SniperError::InvalidConfig(
                 "Priority fee cannot be zero".to_string(),
@@ -124,3 +1572,446 @@ impl Config {
         self.priority_fee_sol as f64 / 1e9
     }
 }
+
+/// Mints and creators the stream is limited to; both empty subscribes to everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamFilter {
+    #[serde(with = "pubkey_set")]
+    pub mints: HashSet<Pubkey>,
+    #[serde(with = "pubkey_set")]
+    pub creators: HashSet<Pubkey>,
+}
+
+impl StreamFilter {
+    pub fn is_empty(&self) -> bool {
+        self.mints.is_empty() && self.creators.is_empty()
+    }
+
+    /// Watched mints, sorted
+    pub fn sorted_mints(&self) -> Vec<Pubkey> {
+        let mut mints: Vec<Pubkey> = self.mints.iter().copied().collect();
+        mints.sort();
+        mints
+    }
+
+    /// Watched creators, sorted
+    pub fn sorted_creators(&self) -> Vec<Pubkey> {
+        let mut creators: Vec<Pubkey> = self.creators.iter().copied().collect();
+        creators.sort();
+        creators
+    }
+}
+
+/// Pubkey sets as sorted base58 strings
+mod pubkey_set {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+    use std::collections::HashSet;
+
+    pub fn serialize<S: Serializer>(
+        set: &HashSet<Pubkey>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut keys: Vec<String> = set.iter().map(Pubkey::to_string).collect();
+        keys.sort();
+        keys.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashSet<Pubkey>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|k| k.parse().map_err(serde::de::Error::custom))
+            .collect()
+    }
+}
+
+/// Optional pubkey as a base58 string
+mod pubkey_opt {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(
+        key: &Option<Pubkey>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        key.map(|key| key.to_string()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Pubkey>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|key| key.parse().map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+/// `processed`, `confirmed` or `finalized`, case insensitive
+fn parse_commitment(value: &str) -> Option<CommitmentLevel> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "processed" => Some(CommitmentLevel::Processed),
+        "confirmed" => Some(CommitmentLevel::Confirmed),
+        "finalized" => Some(CommitmentLevel::Finalized),
+        _ => None,
+    }
+}
+
+/// Reject an empty `endpoint` or one without a host under one of `schemes`. The URL is
+/// left out of the error since endpoints often embed API keys
+fn check_endpoint(name: &str, endpoint: &str, schemes: &[&str]) -> Result<(), SniperError> {
+    if endpoint.trim().is_empty() {
+        return Err(SniperError::InvalidConfig(format!("{} is not set", name)));
+    }
+
+    let host = endpoint
+        .split_once("://")
+        .filter(|(scheme, _)| schemes.contains(&scheme.to_ascii_lowercase().as_str()))
+        .map(|(_, rest)| rest.split(['/', '?']).next().unwrap_or_default());
+    match host {
+        Some(host) if !host.is_empty() && !host.contains(char::is_whitespace) => Ok(()),
+        _ => Err(SniperError::InvalidConfig(format!(
+            "{} must be a {} URL",
+            name,
+            schemes.join("/")
+        ))),
+    }
+}
+
+/// Comma-separated base58 pubkeys, blanks ignored
+fn parse_pubkey_list(value: &str) -> Result<HashSet<Pubkey>, solana_sdk::pubkey::ParsePubkeyError> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Fields of `new` that differ from `old` as (field, old value, new value), ordered by field
+fn diff_fields(
+    new: serde_json::Map<String, serde_json::Value>,
+    old: &serde_json::Map<String, serde_json::Value>,
+) -> Vec<(String, String, String)> {
+    new.into_iter()
+        .filter_map(|(field, new_value)| {
+            let old_value = old.get(&field).cloned().unwrap_or_default();
+            (old_value != new_value).then(|| (field, old_value.to_string(), new_value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::test_config;
+
+    #[test]
+    fn test_commitment_levels() {
+        for (value, expected) in [
+            ("processed", CommitmentConfig::processed()),
+            ("Confirmed", CommitmentConfig::confirmed()),
+            ("finalized", CommitmentConfig::finalized()),
+        ] {
+            let config = Config {
+                commitment: parse_commitment(value),
+                ..Config::default()
+            };
+            assert_eq!(config.commitment_config(), expected);
+            assert_eq!(config.stream_commitment(), expected.commitment);
+        }
+        assert_eq!(parse_commitment("max"), None);
+        // unset keeps fast streaming with confirmed reads
+        assert_eq!(
+            Config::default().commitment_config(),
+            CommitmentConfig::confirmed()
+        );
+        assert_eq!(
+            Config::default().stream_commitment(),
+            CommitmentLevel::Processed
+        );
+    }
+
+    #[test]
+    fn test_diff_reports_changed_fields() {
+        let baseline = Config::default();
+        assert!(baseline.diff(&baseline.clone()).is_empty());
+
+        let creator = Pubkey::new_unique();
+        let current = Config {
+            buy_amount_sol: 100_000_000,
+            take_profit_bps: Some(5_000),
+            creator_blacklist: HashSet::from([creator]),
+            ..Config::default()
+        };
+
+        assert_eq!(
+            current.diff(&baseline),
+            vec![
+                (
+                    "buy_amount_sol".to_string(),
+                    "50000000".to_string(),
+                    "100000000".to_string()
+                ),
+                (
+                    "creator_blacklist".to_string(),
+                    "[]".to_string(),
+                    format!("[\"{}\"]", creator)
+                ),
+                (
+                    "take_profit_bps".to_string(),
+                    "null".to_string(),
+                    "5000".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_baseline_stores_secret_fingerprints() {
+        let path = std::env::temp_dir().join(format!(
+            "pump-sniper-baseline-{}.json",
+            Pubkey::new_unique()
+        ));
+        let config = Config {
+            rpc_endpoint: "https://rpc.example.com/?api-key=secret-rpc".to_string(),
+            jito_block_engine_url: Some("https://jito.example.com/secret-jito".to_string()),
+            webhook_url: Some("https://hooks.example.com/secret-hook".to_string()),
+            config_baseline_file: Some(path.to_string_lossy().into_owned()),
+            ..Config::default()
+        };
+        config.check_baseline().unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("secret"));
+        assert!(saved.contains("redacted:"));
+        config.check_baseline().unwrap();
+
+        let rotated = Config {
+            webhook_url: Some("https://hooks.example.com/other".to_string()),
+            ..config.clone()
+        };
+        let baseline = config.redacted_snapshot().unwrap();
+        let changes = diff_fields(rotated.redacted_snapshot().unwrap(), &baseline);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].0, "webhook_url");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_overrides_reject_startup_only_fields() {
+        let config = Config::default();
+        let overridden = config
+            .with_overrides(&serde_json::json!({ "buy_amount_sol": 1_000 }))
+            .unwrap();
+        assert_eq!(overridden.buy_amount_sol, 1_000);
+
+        for field in ["rpc_endpoint", "commitment", "nonce_account"] {
+            let overrides = serde_json::json!({ field: serde_json::Value::Null });
+            assert!(matches!(
+                config.with_overrides(&overrides),
+                Err(SniperError::InvalidConfig(message)) if message.contains("only read at startup")
+            ));
+        }
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("pump-sniper-config-{}.toml", Pubkey::new_unique()));
+        let config = Config {
+            buy_amount_sol: 250_000_000,
+            market_cap_threshold_usd: 12_500.0,
+            take_profit_bps: Some(5_000),
+            commitment: Some(CommitmentLevel::Confirmed),
+            creator_blacklist: HashSet::from([Pubkey::new_unique()]),
+            rpc_endpoints: vec!["https://fallback.example".to_string()],
+            ..test_config()
+        };
+        let file = toml::Value::try_from(ConfigFile {
+            sniper: config.clone(),
+        })
+        .unwrap();
+        std::fs::write(&path, toml::to_string(&file).unwrap()).unwrap();
+
+        let loaded = Config::from_toml_path(&path).unwrap();
+        assert!(
+            config.diff(&loaded).is_empty(),
+            "{:?}",
+            config.diff(&loaded)
+        );
+
+        // fields left out keep their defaults
+        let endpoints = "grpc_endpoint = \"http://127.0.0.1:10000\"\nrpc_endpoint = \"http://127.0.0.1:8899\"\n";
+        std::fs::write(
+            &path,
+            format!("[sniper]\n{endpoints}buy_amount_sol = 1000\n"),
+        )
+        .unwrap();
+        let sparse = Config::from_toml_path(&path).unwrap();
+        assert_eq!(sparse.buy_amount_sol, 1_000);
+        assert_eq!(sparse.max_slippage_bps, Config::default().max_slippage_bps);
+
+        std::fs::write(
+            &path,
+            format!("[sniper]\n{endpoints}buy_amount_sol = \"lots\"\n"),
+        )
+        .unwrap();
+        let error = Config::from_toml_path(&path).unwrap_err().to_string();
+        assert!(error.contains("buy_amount_sol"), "{}", error);
+
+        // merged values are validated
+        std::fs::write(
+            &path,
+            format!("[sniper]\n{endpoints}max_cached_curves = 0\n"),
+        )
+        .unwrap();
+        assert!(Config::from_toml_path(&path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_endpoint_validation() {
+        assert!(test_config().validate().is_ok());
+
+        let error = Config::default().validate().unwrap_err().to_string();
+        assert!(error.contains("gRPC endpoint is not set"), "{}", error);
+        // a replay never connects to the stream
+        assert!(Config {
+            grpc_endpoint: String::new(),
+            replay_path: Some("session.bin".to_string()),
+            ..test_config()
+        }
+        .validate()
+        .is_ok());
+
+        for endpoint in [
+            "localhost:8899",
+            "ftp://rpc.example",
+            "http://",
+            "https://a b",
+        ] {
+            let config = Config {
+                rpc_endpoint: endpoint.to_string(),
+                ..test_config()
+            };
+            let error = config.validate().unwrap_err().to_string();
+            assert!(error.contains("RPC endpoint"), "{}", error);
+            // endpoints can carry API keys
+            assert!(!error.contains(endpoint), "{}", error);
+        }
+
+        let config = Config {
+            grpc_endpoint: "grpc://geyser.example:443".to_string(),
+            rpc_endpoint: "https://rpc.example/?api-key=secret".to_string(),
+            rpc_endpoints: vec!["".to_string()],
+            ..test_config()
+        };
+        assert!(config.validate().is_err());
+        assert!(Config {
+            rpc_endpoints: vec![],
+            ..config
+        }
+        .validate()
+        .is_ok());
+
+        let config = Config {
+            webhook_url: Some("discord.com/api/webhooks/1/token".to_string()),
+            ..test_config()
+        };
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("Webhook URL"), "{}", error);
+        assert!(!error.contains("token"), "{}", error);
+    }
+
+    #[test]
+    fn test_compute_unit_limit_bounds() {
+        let mut config = Config {
+            compute_unit_limit: 0,
+            ..test_config()
+        };
+        assert!(config.validate().is_err());
+
+        config.compute_unit_limit = MAX_COMPUTE_UNIT_LIMIT + 1;
+        assert!(config.validate().is_err());
+
+        config.compute_unit_limit = MAX_COMPUTE_UNIT_LIMIT;
+        assert!(config.validate().is_ok());
+
+        config.compute_unit_limit = 1;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_priority_fee_overflow() {
+        let config = Config {
+            priority_fee_sol: u64::MAX / 1_000,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_sol_threshold_excludes_usd_gates() {
+        let sol_threshold = Config {
+            market_cap_threshold_sol: Some(25_000_000_000),
+            ..test_config()
+        };
+        assert!(sol_threshold.validate().is_ok());
+
+        for config in [
+            Config {
+                shadow_thresholds_usd: vec![5_000.0],
+                ..sol_threshold.clone()
+            },
+            Config {
+                buy_score_threshold: Some(0.5),
+                ..sol_threshold.clone()
+            },
+            Config {
+                adaptive_threshold: true,
+                ..sol_threshold.clone()
+            },
+        ] {
+            assert!(matches!(
+                config.validate(),
+                Err(SniperError::InvalidConfig(message)) if message.contains("can't be combined")
+            ));
+        }
+    }
+
+    #[test]
+    fn test_token_amount_excludes_wallet_percent() {
+        let config = Config {
+            buy_token_amount: Some(1_000_000),
+            buy_amount_pct: Some(10.0),
+            ..test_config()
+        };
+        assert!(config.validate().is_err());
+        assert!(Config {
+            buy_amount_pct: None,
+            ..config
+        }
+        .validate()
+        .is_ok());
+    }
+
+    #[test]
+    fn test_nonce_excludes_concurrent_buys() {
+        let config = Config {
+            nonce_account: Some(Pubkey::new_unique()),
+            max_concurrent_positions: Some(2),
+            ..test_config()
+        };
+        assert!(config.validate().is_err());
+        assert!(Config {
+            max_concurrent_positions: Some(1),
+            ..config
+        }
+        .validate()
+        .is_ok());
+    }
+}
@@ -0,0 +1,166 @@
+//! Bonding curve cache with expiry
+
+use crate::accounts::BondingCurveAccount;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+struct CachedCurve {
+    data: BondingCurveAccount,
+    updated_at: Instant,
+}
+
+/// Latest streamed state per bonding curve. Entries expire `ttl` after their last
+/// update and the least recently updated are dropped beyond `max_entries`
+#[derive(Debug, Clone)]
+pub struct CurveCache {
+    entries: HashMap<Pubkey, CachedCurve>,
+    /// Entries ordered oldest update first, so pruning pops from the front
+    by_age: BTreeSet<(Instant, Pubkey)>,
+    ttl: Option<Duration>,
+    max_entries: usize,
+}
+
+impl CurveCache {
+    /// `ttl` of None keeps entries until evicted by the size cap
+    pub fn new(ttl: Option<Duration>, max_entries: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            by_age: BTreeSet::new(),
+            ttl,
+            max_entries,
+        }
+    }
+
+    pub fn get(&self, bonding_curve: &Pubkey) -> Option<&BondingCurveAccount> {
+        self.get_at(bonding_curve, Instant::now())
+    }
+
+    /// Entry for `bonding_curve` unless it has expired by `now`
+    pub fn get_at(&self, bonding_curve: &Pubkey, now: Instant) -> Option<&BondingCurveAccount> {
+        self.entries
+            .get(bonding_curve)
+            .filter(|entry| !self.is_expired(entry, now))
+            .map(|entry| &entry.data)
+    }
+
+    pub fn contains_key(&self, bonding_curve: &Pubkey) -> bool {
+        self.get(bonding_curve).is_some()
+    }
+
+    pub fn insert(&mut self, bonding_curve: Pubkey, data: BondingCurveAccount) {
+        self.insert_at(bonding_curve, data, Instant::now());
+    }
+
+    /// Store `data` as updated at `now`, evicting expired entries and then the least
+    /// recently updated past the size cap
+    pub fn insert_at(&mut self, bonding_curve: Pubkey, data: BondingCurveAccount, now: Instant) {
+        let previous = self.entries.insert(
+            bonding_curve,
+            CachedCurve {
+                data,
+                updated_at: now,
+            },
+        );
+        if let Some(previous) = previous {
+            self.by_age.remove(&(previous.updated_at, bonding_curve));
+        }
+        self.by_age.insert((now, bonding_curve));
+        self.prune(now);
+    }
+
+    pub fn remove(&mut self, bonding_curve: &Pubkey) -> Option<BondingCurveAccount> {
+        let entry = self.entries.remove(bonding_curve)?;
+        self.by_age.remove(&(entry.updated_at, *bonding_curve));
+        Some(entry.data)
+    }
+
+    /// Drop expired entries and trim to the size cap, oldest first; returns how many
+    /// were dropped
+    pub fn prune(&mut self, now: Instant) -> usize {
+        let mut dropped = 0;
+        while let Some(&(updated_at, bonding_curve)) = self.by_age.first() {
+            let expired = self
+                .ttl
+                .is_some_and(|ttl| now.saturating_duration_since(updated_at) >= ttl);
+            if !expired && self.entries.len() <= self.max_entries {
+                break;
+            }
+            self.by_age.pop_first();
+            self.entries.remove(&bonding_curve);
+            dropped += 1;
+        }
+        dropped
+    }
+
+    /// Entries held, including any expired but not yet pruned
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn is_expired(&self, entry: &CachedCurve, now: Instant) -> bool {
+        self.ttl
+            .is_some_and(|ttl| now.saturating_duration_since(entry.updated_at) >= ttl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::test_curve;
+
+    #[test]
+    fn test_entry_expires_after_ttl() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut cache = CurveCache::new(Some(Duration::from_secs(60)), 100);
+        let curve = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+
+        cache.insert_at(curve, test_curve(), start);
+        assert!(cache.get_at(&curve, at(59)).is_some());
+        assert!(cache.get_at(&curve, at(60)).is_none());
+
+        // the next insert sweeps it out
+        cache.insert_at(other, test_curve(), at(61));
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get_at(&other, at(61)).is_some());
+
+        // an update refreshes the timer
+        cache.insert_at(other, test_curve(), at(100));
+        assert!(cache.get_at(&other, at(150)).is_some());
+    }
+
+    #[test]
+    fn test_size_cap_drops_least_recently_updated() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut cache = CurveCache::new(None, 2);
+        let curves: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+
+        cache.insert_at(curves[0], test_curve(), at(0));
+        cache.insert_at(curves[1], test_curve(), at(1));
+        // refreshing the first makes the second the oldest
+        cache.insert_at(curves[0], test_curve(), at(2));
+        cache.insert_at(curves[2], test_curve(), at(3));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get_at(&curves[0], at(3)).is_some());
+        assert!(cache.get_at(&curves[1], at(3)).is_none());
+        assert!(cache.get_at(&curves[2], at(3)).is_some());
+        // no ttl, nothing expires
+        assert!(cache.get_at(&curves[2], at(1_000_000)).is_some());
+
+        // a removed entry frees its slot without evicting another
+        cache.remove(&curves[0]);
+        cache.insert_at(curves[1], test_curve(), at(4));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get_at(&curves[2], at(4)).is_some());
+        assert_eq!(cache.prune(at(5)), 0);
+    }
+}
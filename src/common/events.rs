@@ -2,7 +2,65 @@
 
 use crate::accounts::{BondingCurveAccount, TokenInfo};
 use crate::common::MarketData;
+use crate::utils::{BuySnapshot, SwapEvent};
 use solana_sdk::pubkey::Pubkey;
+use std::time::SystemTime;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// Snapshot of the sniper's counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SniperStats {
+    /// Tokens currently tracked
+    pub tracked: usize,
+    /// Tokens bought or with a buy in flight
+    pub bought: usize,
+    pub successful_buys: usize,
+    pub failed_buys: usize,
+    pub uptime_seconds: u64,
+    /// Worst position drawdown this session in basis points, open or closed
+    pub max_drawdown_bps: u64,
+}
+
+/// Which decision path fired a buy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuySource {
+    /// Streamed bonding curve update, no RPC
+    Instant,
+    /// Cached bonding curve at token creation
+    Cached,
+    /// RPC fetch fallback at token creation
+    Rpc,
+}
+
+impl BuySource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BuySource::Instant => "instant",
+            BuySource::Cached => "cached",
+            BuySource::Rpc => "rpc",
+        }
+    }
+}
+
+/// Why a position is being exited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SellReason {
+    TakeProfit,
+    StopLoss,
+    /// Operator-requested liquidation
+    Manual,
+}
+
+impl SellReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SellReason::TakeProfit => "take_profit",
+            SellReason::StopLoss => "stop_loss",
+            SellReason::Manual => "manual",
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum SniperEvent {
@@ -16,12 +74,25 @@ pub enum SniperEvent {
         token_info: TokenInfo,
         market_cap: u64,
         buy_amount: u64,
+        trigger_source: BuySource,
+        /// Spot price of one token at trigger time, 0 if the SOL price was unavailable
+        price_per_token_usd: f64,
     },
     BuyExecuted {
         token_info: TokenInfo,
         transaction_signature: String,
         amount_spent: u64,
         tokens_received: u64,
+        /// Shortfall against the trigger-time quote, if one was taken
+        realized_slippage_bps: Option<i64>,
+        /// Curve state and bounds the buy was built from
+        snapshot: Option<BuySnapshot>,
+    },
+    SellTriggered {
+        token_info: TokenInfo,
+        token_amount: u64,
+        expected_sol_output: u64,
+        reason: SellReason,
     },
     BuyFailed {
         token_info: TokenInfo,
@@ -32,12 +103,19 @@ pub enum SniperEvent {
         connected: bool,
         endpoint: String,
     },
-    StatsUpdate {
-        tokens_tracked: usize,
-        successful_buys: usize,
-        failed_buys: usize,
-        uptime_seconds: u64,
+    StatsUpdate(SniperStats),
+    /// A buy was skipped because it would exceed `max_total_spend_sol`
+    SpendLimitReached {
+        token_info: TokenInfo,
+        spent: u64,
+        limit: u64,
+    },
+    /// A tracked token's bonding curve completed, so it was dropped without buying
+    TokenMigrated {
+        token_info: TokenInfo,
     },
+    /// A buy or sell landed on some bonding curve
+    SwapDetected(SwapEvent),
 }
 
 impl SniperEvent {
@@ -48,9 +126,13 @@ impl SniperEvent {
             SniperEvent::MarketCapUpdated(_) => "market_cap_updated",
             SniperEvent::BuyTriggered { .. } => "buy_triggered",
             SniperEvent::BuyExecuted { .. } => "buy_executed",
+            SniperEvent::SellTriggered { .. } => "sell_triggered",
             SniperEvent::BuyFailed { .. } => "buy_failed",
             SniperEvent::ConnectionStatusChanged { .. } => "connection_status_changed",
-            SniperEvent::StatsUpdate { .. } => "stats_update",
+            SniperEvent::StatsUpdate(_) => "stats_update",
+            SniperEvent::SpendLimitReached { .. } => "spend_limit_reached",
+            SniperEvent::TokenMigrated { .. } => "token_migrated",
+            SniperEvent::SwapDetected(_) => "swap_detected",
         }
     }
 
@@ -59,7 +141,45 @@ impl SniperEvent {
             self,
             SniperEvent::BuyTriggered { .. }
                 | SniperEvent::BuyExecuted { .. }
+                | SniperEvent::SellTriggered { .. }
                 | SniperEvent::BuyFailed { .. }
+                | SniperEvent::SpendLimitReached { .. }
         )
     }
 }
+
+/// An event as the sniper handled it, stamped when it entered the event loop
+#[derive(Debug, Clone)]
+pub struct EventEnvelope {
+    /// Handling order, starting at 0 and never reused within a run
+    pub seq: u64,
+    pub received_at: SystemTime,
+    pub event: SniperEvent,
+}
+
+impl EventEnvelope {
+    pub fn event_type(&self) -> &'static str {
+        self.event.event_type()
+    }
+
+    pub fn is_critical(&self) -> bool {
+        self.event.is_critical()
+    }
+}
+
+/// Next event from a `Sniper::subscribe` receiver. Events this subscriber fell too far
+/// behind to see are skipped with a warning, leaving a gap in `seq`; None once the
+/// sniper is dropped
+pub async fn recv_event(
+    receiver: &mut broadcast::Receiver<EventEnvelope>,
+) -> Option<EventEnvelope> {
+    loop {
+        match receiver.recv().await {
+            Ok(event) => return Some(event),
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                warn!("Event subscriber lagged, {} events dropped", missed)
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
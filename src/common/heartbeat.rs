@@ -0,0 +1,50 @@
+//! Periodic liveness log
+
+use std::time::{Duration, Instant};
+
+/// Decides when the next heartbeat is due; the clock is passed in
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+    interval: Duration,
+    last_beat: Instant,
+}
+
+impl Heartbeat {
+    pub fn new(interval: Duration, now: Instant) -> Self {
+        Self {
+            interval,
+            last_beat: now,
+        }
+    }
+
+    /// True once per elapsed interval
+    pub fn poll(&mut self, now: Instant) -> bool {
+        if now.saturating_duration_since(self.last_beat) < self.interval {
+            return false;
+        }
+        self.last_beat = now;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fires_at_interval() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut heartbeat = Heartbeat::new(Duration::from_secs(30), start);
+
+        assert!(!heartbeat.poll(at(1)));
+        assert!(!heartbeat.poll(at(29)));
+        assert!(heartbeat.poll(at(30)));
+        assert!(!heartbeat.poll(at(31)));
+        assert!(!heartbeat.poll(at(59)));
+        assert!(heartbeat.poll(at(60)));
+        // a long stall beats once, not once per missed interval
+        assert!(heartbeat.poll(at(200)));
+        assert!(!heartbeat.poll(at(201)));
+    }
+}
@@ -1,6 +1,8 @@
 //! Market data structures and calcs
 
 use crate::accounts::{BondingCurveAccount, TokenInfo};
+use crate::constants::TOKEN_DECIMALS;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
 
 /// Market data for a token
 #[derive(Debug, Clone)]
@@ -23,11 +25,9 @@ impl MarketData {
     /// Create new market data from token info and bonding curve
     pub fn new(token_info: TokenInfo, bonding_curve_data: BondingCurveAccount) -> Self {
         let current_market_cap_sol = bonding_curve_data.get_market_cap_sol();
-        let price_per_token_sol = if bonding_curve_data.token_total_supply > 0 {
-            current_market_cap_sol / bonding_curve_data.token_total_supply
-        } else {
-            0
-        };
+        let price_per_token_sol = current_market_cap_sol
+            .checked_div(bonding_curve_data.token_total_supply)
+            .unwrap_or(0);
 
         Self {
             token_info,
@@ -52,6 +52,18 @@ impl MarketData {
         self.price_per_token_sol as f64 / 1e9
     }
 
+    /// Spot USD price of one whole token from the virtual reserves; 0 for an empty curve
+    pub fn price_per_token_usd(&self, sol_price: f64) -> f64 {
+        let curve = &self.bonding_curve_data;
+        if curve.virtual_token_reserves == 0 || curve.token_total_supply == 0 {
+            return 0.0;
+        }
+
+        let sol_reserves = curve.virtual_sol_reserves as f64 / LAMPORTS_PER_SOL as f64;
+        let token_reserves = curve.virtual_token_reserves as f64 / 10f64.powi(TOKEN_DECIMALS);
+        sol_reserves / token_reserves * sol_price
+    }
+
     /// Get bonding curve progress (0-100%)
     pub fn curve_progress(&self) -> f64 {
         self.bonding_curve_data.get_curve_progress()
@@ -80,14 +92,54 @@ impl MarketData {
     pub fn update(&mut self, bonding_curve_data: BondingCurveAccount) {
         self.bonding_curve_data = bonding_curve_data;
         self.current_market_cap_sol = self.bonding_curve_data.get_market_cap_sol();
-        self.price_per_token_sol = if self.bonding_curve_data.token_total_supply > 0 {
-            self.current_market_cap_sol / self.bonding_curve_data.token_total_supply
-        } else {
-            0
-        };
+        self.price_per_token_sol = self
+            .current_market_cap_sol
+            .checked_div(self.bonding_curve_data.token_total_supply)
+            .unwrap_or(0);
         self.last_updated = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::{test_curve, test_token};
+
+    #[test]
+    fn test_price_per_token_usd() {
+        // launch reserves: 30 SOL against 1.073B tokens
+        let curve = BondingCurveAccount {
+            virtual_sol_reserves: 30_000_000_000,
+            virtual_token_reserves: 1_073_000_000_000_000,
+            token_total_supply: 1_000_000_000_000_000,
+            ..test_curve()
+        };
+        let market_data = MarketData::new(test_token(), curve.clone());
+        let price = market_data.price_per_token_usd(150.0);
+        assert!((price - 30.0 / 1_073_000_000.0 * 150.0).abs() < 1e-15);
+        assert!((price - 4.1938e-6).abs() < 1e-9);
+
+        // price times supply is the market cap
+        let market_cap_usd = market_data.market_cap_sol_display() * 150.0;
+        assert!((price * 1e9 - market_cap_usd).abs() < 1e-3);
+
+        for empty in [
+            BondingCurveAccount {
+                virtual_token_reserves: 0,
+                ..curve.clone()
+            },
+            BondingCurveAccount {
+                token_total_supply: 0,
+                ..curve
+            },
+        ] {
+            assert_eq!(
+                MarketData::new(test_token(), empty).price_per_token_usd(150.0),
+                0.0
+            );
+        }
+    }
+}
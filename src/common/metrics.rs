@@ -0,0 +1,268 @@
+//! Health and Prometheus metrics over HTTP
+
+use crate::common::SniperStats;
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// Longest a request waits on the event loop before answering 503
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Longest a client gets to send its request line and headers
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Cap on the request line and headers together; larger requests are dropped
+const MAX_REQUEST_HEAD_BYTES: u64 = 8 * 1024;
+
+/// Counters and connection state read from the event loop for one HTTP request
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    pub stats: SniperStats,
+    pub stream_connected: bool,
+    pub stream_reconnects: u64,
+}
+
+impl MetricsSnapshot {
+    /// Prometheus text exposition format
+    pub fn render_prometheus(&self) -> String {
+        let metrics: [(&str, &str, &str, u64); 5] = [
+            (
+                "pump_sniper_tokens_tracked",
+                "gauge",
+                "Tokens currently tracked",
+                self.stats.tracked as u64,
+            ),
+            (
+                "pump_sniper_successful_buys_total",
+                "counter",
+                "Buys that landed",
+                self.stats.successful_buys as u64,
+            ),
+            (
+                "pump_sniper_failed_buys_total",
+                "counter",
+                "Buys that failed after retries",
+                self.stats.failed_buys as u64,
+            ),
+            (
+                "pump_sniper_stream_reconnects_total",
+                "counter",
+                "gRPC stream reconnections after the first connect",
+                self.stream_reconnects,
+            ),
+            (
+                "pump_sniper_stream_connected",
+                "gauge",
+                "1 while the gRPC stream is connected",
+                self.stream_connected as u64,
+            ),
+        ];
+
+        let mut body = String::new();
+        for (name, kind, help, value) in metrics {
+            let _ = writeln!(body, "# HELP {} {}", name, help);
+            let _ = writeln!(body, "# TYPE {} {}", name, kind);
+            let _ = writeln!(body, "{} {}", name, value);
+        }
+        body
+    }
+}
+
+/// Ask for a snapshot, answered by the sniper's event loop
+#[derive(Debug)]
+pub struct MetricsRequest {
+    pub reply: oneshot::Sender<MetricsSnapshot>,
+}
+
+/// Serve `/health` and `/metrics` at `address`, port 0 for any free port. `/health` reads
+/// `stream_connected` directly so it answers even while the event loop is busy.
+/// Returns the bound address and the accept task
+pub async fn spawn_metrics_server(
+    address: SocketAddr,
+    stream_connected: Arc<AtomicBool>,
+    requests: mpsc::UnboundedSender<MetricsRequest>,
+) -> std::io::Result<(SocketAddr, JoinHandle<()>)> {
+    let listener = TcpListener::bind(address).await?;
+    let local = listener.local_addr()?;
+    let task = tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(serve_connection(
+                        stream,
+                        stream_connected.clone(),
+                        requests.clone(),
+                    ));
+                }
+                Err(e) => error!("Metrics accept failed: {}", e),
+            }
+        }
+    });
+    info!("Metrics server on {}", local);
+    Ok((local, task))
+}
+
+/// Answer one request and close the connection
+async fn serve_connection(
+    stream: TcpStream,
+    stream_connected: Arc<AtomicBool>,
+    requests: mpsc::UnboundedSender<MetricsRequest>,
+) {
+    let (reader, mut writer) = stream.into_split();
+    let Ok(Some(request_line)) =
+        tokio::time::timeout(REQUEST_READ_TIMEOUT, read_request_head(reader)).await
+    else {
+        return;
+    };
+
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or_default(),
+    );
+    let path = path.split('?').next().unwrap_or_default();
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/health") if stream_connected.load(Ordering::Relaxed) => {
+            ("200 OK", "text/plain", "ok\n".to_string())
+        }
+        ("GET", "/health") => (
+            "503 Service Unavailable",
+            "text/plain",
+            "stream disconnected\n".to_string(),
+        ),
+        ("GET", "/metrics") => match snapshot(&requests).await {
+            Some(snapshot) => (
+                "200 OK",
+                "text/plain; version=0.0.4",
+                snapshot.render_prometheus(),
+            ),
+            None => (
+                "503 Service Unavailable",
+                "text/plain",
+                "sniper not responding\n".to_string(),
+            ),
+        },
+        ("GET", _) => ("404 Not Found", "text/plain", "not found\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed\n".to_string(),
+        ),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    if let Err(e) = writer.write_all(response.as_bytes()).await {
+        warn!("Metrics reply failed: {}", e);
+    }
+}
+
+/// The request line, once the headers after it are drained so the client sees a clean
+/// close. None if the client hangs up or the head runs past `MAX_REQUEST_HEAD_BYTES`
+async fn read_request_head(reader: impl AsyncRead + Unpin) -> Option<String> {
+    let mut lines = BufReader::new(reader.take(MAX_REQUEST_HEAD_BYTES)).lines();
+    let request_line = lines.next_line().await.ok()??;
+    while !lines.next_line().await.ok()??.is_empty() {}
+    Some(request_line)
+}
+
+/// Snapshot from the event loop, None if it's gone or busy past `SNAPSHOT_TIMEOUT`
+async fn snapshot(requests: &mpsc::UnboundedSender<MetricsRequest>) -> Option<MetricsSnapshot> {
+    let (reply, response) = oneshot::channel();
+    requests.send(MetricsRequest { reply }).ok()?;
+    tokio::time::timeout(SNAPSHOT_TIMEOUT, response)
+        .await
+        .ok()?
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prometheus_format() {
+        let snapshot = MetricsSnapshot {
+            stats: SniperStats {
+                tracked: 12,
+                successful_buys: 3,
+                failed_buys: 1,
+                ..SniperStats::default()
+            },
+            stream_connected: true,
+            stream_reconnects: 2,
+        };
+        let body = snapshot.render_prometheus();
+        assert!(body.contains(
+            "# TYPE pump_sniper_successful_buys_total counter\npump_sniper_successful_buys_total 3\n"
+        ));
+        for line in [
+            "pump_sniper_tokens_tracked 12",
+            "pump_sniper_failed_buys_total 1",
+            "pump_sniper_stream_reconnects_total 2",
+            "pump_sniper_stream_connected 1",
+        ] {
+            assert!(body.lines().any(|l| l == line), "{}\n{}", line, body);
+        }
+    }
+
+    /// Server whose snapshot requests nobody answers, as if the event loop were stuck
+    async fn spawn_test_server() -> (SocketAddr, mpsc::UnboundedReceiver<MetricsRequest>) {
+        let (requests, receiver) = mpsc::unbounded_channel();
+        let (addr, _) = spawn_metrics_server(
+            SocketAddr::from(([127, 0, 0, 1], 0)),
+            Arc::new(AtomicBool::new(true)),
+            requests,
+        )
+        .await
+        .unwrap();
+        (addr, receiver)
+    }
+
+    async fn send_raw(addr: SocketAddr, request: &[u8]) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let _ = stream.write_all(request).await;
+        let mut response = Vec::new();
+        let _ = stream.read_to_end(&mut response).await;
+        String::from_utf8_lossy(&response).into_owned()
+    }
+
+    #[tokio::test]
+    async fn test_health_skips_event_loop() {
+        let (addr, _requests) = spawn_test_server().await;
+        let response = send_raw(addr, b"GET /health HTTP/1.1\r\nHost: x\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.ends_with("ok\n"), "{}", response);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_request_dropped() {
+        let (addr, _requests) = spawn_test_server().await;
+        let request = format!(
+            "GET /health HTTP/1.1\r\nX-Pad: {}\r\n\r\n",
+            "a".repeat(MAX_REQUEST_HEAD_BYTES as usize)
+        );
+        assert_eq!(send_raw(addr, request.as_bytes()).await, "");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_slow_request_dropped() {
+        let (addr, _requests) = spawn_test_server().await;
+        // the headers never finish, so the read times out instead of holding the task
+        assert_eq!(send_raw(addr, b"GET /health HTTP/1.1\r\n").await, "");
+    }
+}
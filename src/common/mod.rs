@@ -1,11 +1,37 @@
 //! Common utils
 
+pub mod adaptive;
+pub mod admin;
 pub mod config;
+pub mod curve_cache;
 pub mod events;
+pub mod heartbeat;
 pub mod market_data;
+pub mod metrics;
+pub mod name_filter;
+pub mod position;
+pub mod reputation;
+pub mod shadow;
+#[cfg(feature = "statsd")]
+pub mod statsd;
+pub mod strategy;
 pub mod stream;
+pub mod volume;
 
+pub use adaptive::*;
+pub use admin::*;
 pub use config::*;
+pub use curve_cache::*;
 pub use events::*;
+pub use heartbeat::*;
 pub use market_data::*;
+pub use metrics::*;
+pub use name_filter::*;
+pub use position::*;
+pub use reputation::*;
+pub use shadow::*;
+#[cfg(feature = "statsd")]
+pub use statsd::*;
+pub use strategy::*;
 pub use stream::*;
+pub use volume::*;
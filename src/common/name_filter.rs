@@ -0,0 +1,123 @@
+//! Token name and symbol filters
+
+use crate::accounts::TokenInfo;
+use crate::common::Config;
+use crate::error::SniperError;
+use regex::Regex;
+
+/// `name_include_regex` and `name_exclude_regex`, compiled
+#[derive(Debug, Clone, Default)]
+pub struct NameFilter {
+    include: Option<Regex>,
+    exclude: Option<Regex>,
+}
+
+impl NameFilter {
+    pub fn from_config(config: &Config) -> Result<Self, SniperError> {
+        let compile = |pattern: &Option<String>, which: &str| {
+            pattern
+                .as_deref()
+                .map(|pattern| {
+                    Regex::new(pattern).map_err(|e| {
+                        SniperError::InvalidConfig(format!("Invalid name {} regex: {}", which, e))
+                    })
+                })
+                .transpose()
+        };
+        Ok(Self {
+            include: compile(&config.name_include_regex, "include")?,
+            exclude: compile(&config.name_exclude_regex, "exclude")?,
+        })
+    }
+
+    /// Why `token_info` is filtered out by its name or symbol, or `None` if it passes
+    pub fn skip_reason(&self, token_info: &TokenInfo) -> Option<String> {
+        let fields = [("name", &token_info.name), ("symbol", &token_info.symbol)];
+
+        if let Some(exclude) = &self.exclude {
+            if let Some((field, value)) = fields.iter().find(|(_, value)| exclude.is_match(value)) {
+                return Some(format!(
+                    "{} {:?} matches exclude pattern /{}/",
+                    field, value, exclude
+                ));
+            }
+        }
+        match &self.include {
+            Some(include) if !fields.iter().any(|(_, value)| include.is_match(value)) => Some(
+                format!("name and symbol miss include pattern /{}/", include),
+            ),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::{test_config, test_token};
+
+    fn token(name: &str, symbol: &str) -> TokenInfo {
+        TokenInfo {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            ..test_token()
+        }
+    }
+
+    fn filter(include: Option<&str>, exclude: Option<&str>) -> NameFilter {
+        NameFilter::from_config(&Config {
+            name_include_regex: include.map(String::from),
+            name_exclude_regex: exclude.map(String::from),
+            ..test_config()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_include_pattern() {
+        let filter = filter(Some("(?i)cat|dog"), None);
+        assert_eq!(filter.skip_reason(&token("Space Cat", "SCAT")), None);
+        // a symbol match is enough
+        assert_eq!(filter.skip_reason(&token("Woof", "DOGE")), None);
+
+        let reason = filter.skip_reason(&token("Frog", "FRG")).unwrap();
+        assert!(
+            reason.contains("include pattern /(?i)cat|dog/"),
+            "{}",
+            reason
+        );
+
+        assert_eq!(
+            NameFilter::default().skip_reason(&token("Frog", "FRG")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_exclude_pattern_wins() {
+        let filter = filter(Some("(?i)cat"), Some("(?i)rug|scam"));
+        let reason = filter.skip_reason(&token("Cat", "NORUG")).unwrap();
+        assert_eq!(
+            reason,
+            "symbol \"NORUG\" matches exclude pattern /(?i)rug|scam/"
+        );
+        assert!(filter.skip_reason(&token("Scam Cat", "CAT")).is_some());
+        assert_eq!(filter.skip_reason(&token("Cat", "CAT")), None);
+    }
+
+    #[test]
+    fn test_invalid_regex_rejected() {
+        let config = Config {
+            name_exclude_regex: Some("(unclosed".to_string()),
+            ..test_config()
+        };
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("Invalid name exclude regex"), "{}", error);
+
+        let config = Config {
+            name_include_regex: Some("[z-a]".to_string()),
+            ..test_config()
+        };
+        assert!(NameFilter::from_config(&config).is_err());
+    }
+}
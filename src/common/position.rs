@@ -0,0 +1,291 @@
+//! Open positions
+
+use crate::accounts::{BondingCurveAccount, TokenInfo};
+use crate::utils::BuySnapshot;
+use solana_sdk::pubkey::Pubkey;
+use std::time::{Instant, SystemTime};
+
+/// A token the bot holds after a successful buy
+#[derive(Debug, Clone)]
+pub struct Position {
+    /// Token bought
+    pub token_info: TokenInfo,
+    /// Tokens received, unknown until the buy lands
+    pub tokens_received: Option<u64>,
+    /// Tokens quoted from the cached curve when the buy fired
+    pub expected_tokens: Option<u64>,
+    /// Signature of the buy transaction
+    pub signature: String,
+    /// Curve state and bounds the buy was built from
+    pub buy_snapshot: Option<BuySnapshot>,
+    /// SOL spent on the buy in lamports
+    pub entry_cost_lamports: u64,
+    /// Market cap at entry in SOL lamports
+    pub entry_market_cap_sol: u64,
+    /// A sell has been fired and not yet resolved
+    pub sell_pending: bool,
+    /// Highest value seen on curve updates in lamports, 0 before the first
+    pub peak_value_lamports: u64,
+    /// Lowest value seen since the current peak in lamports
+    pub trough_since_peak_lamports: u64,
+    /// Largest peak-to-trough drop so far in basis points
+    pub max_drawdown_bps: u64,
+    /// When the buy was recorded
+    pub opened_at: SystemTime,
+    /// Highest real SOL seen on the curve in lamports, carried over from tracking
+    pub peak_real_sol_lamports: u64,
+    /// The launch outcome has been credited to the creator
+    pub launch_resolved: bool,
+    /// Last balance lookup for the fill while `tokens_received` is unknown
+    pub last_fill_check: Option<Instant>,
+}
+
+/// Snapshot of an open position valued against the latest cached curve
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionReport {
+    pub mint: Pubkey,
+    pub symbol: String,
+    /// Tokens received, None until the buy lands
+    pub tokens_held: Option<u64>,
+    pub entry_cost_lamports: u64,
+    pub entry_market_cap_sol: u64,
+    pub opened_at: SystemTime,
+    /// Sell value after fees, None while the fill or the curve is unknown
+    pub current_value_lamports: Option<u64>,
+    pub unrealized_pnl_lamports: Option<i64>,
+    pub unrealized_pnl_percent: Option<f64>,
+}
+
+impl PositionReport {
+    /// Unrealized PnL in SOL
+    pub fn unrealized_pnl_sol(&self) -> Option<f64> {
+        self.unrealized_pnl_lamports
+            .map(|lamports| lamports as f64 / 1e9)
+    }
+}
+
+impl Position {
+    pub fn new(token_info: TokenInfo, entry_cost_lamports: u64, entry_market_cap_sol: u64) -> Self {
+        Self {
+            token_info,
+            tokens_received: None,
+            expected_tokens: None,
+            signature: String::new(),
+            buy_snapshot: None,
+            entry_cost_lamports,
+            entry_market_cap_sol,
+            sell_pending: false,
+            peak_value_lamports: 0,
+            trough_since_peak_lamports: 0,
+            max_drawdown_bps: 0,
+            opened_at: SystemTime::now(),
+            peak_real_sol_lamports: 0,
+            launch_resolved: false,
+            last_fill_check: None,
+        }
+    }
+
+    /// Value the position against `curve`, the latest one known for its token
+    pub fn report(
+        &self,
+        curve: Option<&BondingCurveAccount>,
+        fee_basis_points: u64,
+    ) -> PositionReport {
+        let current_value_lamports =
+            curve.and_then(|curve| self.current_value(curve, fee_basis_points));
+        let unrealized_pnl_lamports = current_value_lamports
+            .map(|value| (value as i128 - self.entry_cost_lamports as i128) as i64);
+        let unrealized_pnl_percent = unrealized_pnl_lamports
+            .filter(|_| self.entry_cost_lamports > 0)
+            .map(|pnl| pnl as f64 / self.entry_cost_lamports as f64 * 100.0);
+        PositionReport {
+            mint: self.token_info.mint,
+            symbol: self.token_info.symbol.clone(),
+            tokens_held: self.tokens_received,
+            entry_cost_lamports: self.entry_cost_lamports,
+            entry_market_cap_sol: self.entry_market_cap_sol,
+            opened_at: self.opened_at,
+            current_value_lamports,
+            unrealized_pnl_lamports,
+            unrealized_pnl_percent,
+        }
+    }
+
+    /// Track peak and trough from a fresh valuation
+    pub fn record_value(&mut self, value_lamports: u64) {
+        if value_lamports > self.peak_value_lamports {
+            self.peak_value_lamports = value_lamports;
+            self.trough_since_peak_lamports = value_lamports;
+            return;
+        }
+        if value_lamports < self.trough_since_peak_lamports {
+            self.trough_since_peak_lamports = value_lamports;
+            let drawdown_bps = (self.peak_value_lamports - value_lamports) as u128 * 10_000
+                / self.peak_value_lamports as u128;
+            self.max_drawdown_bps = self.max_drawdown_bps.max(drawdown_bps as u64);
+        }
+    }
+
+    /// Largest peak-to-trough drop in value seen so far, in percent
+    pub fn max_drawdown_percent(&self) -> f64 {
+        self.max_drawdown_bps as f64 / 100.0
+    }
+
+    /// SOL received for selling the whole position against `curve`
+    pub fn current_value(&self, curve: &BondingCurveAccount, fee_basis_points: u64) -> Option<u64> {
+        let tokens = self.tokens_received?;
+        curve.get_sell_price(tokens, fee_basis_points).ok()
+    }
+
+    /// Value held in the position: its sell value against `curve`, or the entry cost while
+    /// the fill or the curve is unknown
+    pub fn exposure_lamports(
+        &self,
+        curve: Option<&BondingCurveAccount>,
+        fee_basis_points: u64,
+    ) -> u64 {
+        curve
+            .and_then(|curve| self.current_value(curve, fee_basis_points))
+            .unwrap_or(self.entry_cost_lamports)
+    }
+
+    /// Market cap rise in percent needed for a sell against `curve` to recover the entry
+    /// cost after fees, once the fill is known
+    pub fn break_even_move_percent(
+        &self,
+        curve: &BondingCurveAccount,
+        fee_basis_points: u64,
+    ) -> Option<f64> {
+        curve.break_even_move_percent(
+            self.tokens_received?,
+            self.entry_cost_lamports,
+            fee_basis_points,
+        )
+    }
+
+    /// Slippage between the quote and the fill, once both are known
+    pub fn realized_slippage_bps(&self) -> Option<i64> {
+        Some(realized_slippage_bps(
+            self.expected_tokens?,
+            self.tokens_received?,
+        ))
+    }
+
+    /// Gain (or loss, negative) in basis points for a given exit value
+    pub fn pnl_bps(&self, value_lamports: u64) -> i64 {
+        if self.entry_cost_lamports == 0 {
+            return 0;
+        }
+        (value_lamports as i128 - self.entry_cost_lamports as i128) as i64 * 10_000
+            / self.entry_cost_lamports as i64
+    }
+}
+
+/// Shortfall of `actual` against `expected` in basis points; negative when
+/// the fill beat the quote
+pub fn realized_slippage_bps(expected: u64, actual: u64) -> i64 {
+    if expected == 0 {
+        return 0;
+    }
+    ((expected as i128 - actual as i128) * 10_000 / expected as i128) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::{test_curve, test_token};
+
+    #[test]
+    fn test_value_unknown_until_tokens_received() {
+        let curve = test_curve();
+        let mut position = Position::new(test_token(), 1_000_000_000, 0);
+        assert_eq!(position.current_value(&curve, 100), None);
+
+        position.tokens_received = Some(curve.get_buy_price(1_000_000_000).unwrap());
+        let value = position.current_value(&curve, 0).unwrap();
+        // round trip against the same curve loses a little to rounding
+        assert!(value > 900_000_000 && value <= 1_000_000_000);
+    }
+
+    #[test]
+    fn test_break_even_move() {
+        let curve = BondingCurveAccount {
+            virtual_sol_reserves: 100_000_000_000,
+            virtual_token_reserves: 100_000_000_000,
+            ..test_curve()
+        };
+        let mut position = Position::new(test_token(), 10_000_000_000, 0);
+        assert_eq!(position.break_even_move_percent(&curve, 100), None);
+        position.tokens_received = Some(10_000_000_000);
+
+        // a = 10 * 100 = 1000 (in 1e9 units), s = (100 + sqrt(100^2 + 4 * 1000 * 10 * 100)) / 2000
+        //   = 1.0512492, so the cap has to rise s^2 - 1 = 10.51%
+        let no_fee = position.break_even_move_percent(&curve, 0).unwrap();
+        assert!((no_fee - 10.5125).abs() < 0.001, "{no_fee}");
+        // with a 1% sell fee a = 990, s = 1.0568110, an 11.69% rise
+        let with_fee = position.break_even_move_percent(&curve, 100).unwrap();
+        assert!((with_fee - 11.6850).abs() < 0.001, "{with_fee}");
+
+        // selling on the curve moved by that much recovers the cost
+        let s = (1.0 + with_fee / 100.0).sqrt();
+        let moved = BondingCurveAccount {
+            virtual_sol_reserves: (100_000_000_000f64 * s) as u64,
+            virtual_token_reserves: (100_000_000_000f64 / s) as u64,
+            ..curve.clone()
+        };
+        let proceeds = position.current_value(&moved, 100).unwrap();
+        assert!(proceeds.abs_diff(10_000_000_000) < 1_000, "{proceeds}");
+
+        // already in profit means a negative move
+        position.entry_cost_lamports = 1_000_000_000;
+        assert!(position.break_even_move_percent(&curve, 100).unwrap() < 0.0);
+    }
+
+    #[test]
+    fn test_pnl_bps() {
+        let position = Position::new(test_token(), 1_000_000_000, 0);
+        assert_eq!(position.pnl_bps(1_500_000_000), 5_000);
+        assert_eq!(position.pnl_bps(800_000_000), -2_000);
+        assert_eq!(position.pnl_bps(1_000_000_000), 0);
+    }
+
+    #[test]
+    fn test_max_drawdown_over_value_path() {
+        let mut position = Position::new(test_token(), 1_000_000_000, 0);
+        assert_eq!(position.max_drawdown_percent(), 0.0);
+
+        // 1.0 -> 1.2 peak -> 0.9 (-25%) -> 1.5 new peak -> 1.2 (-20%) -> 1.3
+        for value in [
+            1_000_000_000,
+            1_200_000_000,
+            1_050_000_000,
+            900_000_000,
+            1_500_000_000,
+            1_200_000_000,
+            1_300_000_000,
+        ] {
+            position.record_value(value);
+        }
+        assert_eq!(position.peak_value_lamports, 1_500_000_000);
+        assert_eq!(position.trough_since_peak_lamports, 1_200_000_000);
+        assert_eq!(position.max_drawdown_percent(), 25.0);
+
+        // a deeper fall after the new peak takes over
+        position.record_value(600_000_000);
+        assert_eq!(position.max_drawdown_percent(), 60.0);
+    }
+
+    #[test]
+    fn test_realized_slippage_from_expected_vs_actual() {
+        assert_eq!(realized_slippage_bps(1_000_000, 1_000_000), 0);
+        assert_eq!(realized_slippage_bps(1_000_000, 970_000), 300);
+        assert_eq!(realized_slippage_bps(1_000_000, 1_010_000), -100);
+        assert_eq!(realized_slippage_bps(0, 5), 0);
+
+        let mut position = Position::new(test_token(), 1_000_000_000, 0);
+        position.expected_tokens = Some(2_000_000);
+        assert_eq!(position.realized_slippage_bps(), None);
+        position.tokens_received = Some(1_900_000);
+        assert_eq!(position.realized_slippage_bps(), Some(500));
+    }
+}
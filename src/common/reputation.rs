@@ -0,0 +1,282 @@
+//! Persistent per-creator reputation
+
+use crate::{accounts::BondingCurveAccount, error::SniperError};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::warn;
+
+/// Real SOL a curve must have held before draining counts as a rug
+pub const RUG_MIN_PEAK_LAMPORTS: u64 = 500_000_000;
+/// Real SOL left in a curve considered drained
+pub const RUG_RESIDUAL_LAMPORTS: u64 = 10_000_000;
+/// Creators kept before the least recently seen are evicted
+pub const MAX_CREATORS: usize = 50_000;
+/// Share of `MAX_CREATORS` evicted at once, so eviction runs rarely
+const EVICTION_BATCH: usize = MAX_CREATORS / 10;
+
+/// How a launch ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchOutcome {
+    /// Curve completed and moved to the AMM
+    Migrated,
+    /// Curve drained back to ~0 after filling up
+    Rugged,
+}
+
+impl LaunchOutcome {
+    /// Outcome shown by a curve update, given the highest real SOL seen on it before
+    pub fn observe(curve: &BondingCurveAccount, peak_real_sol: u64) -> Option<Self> {
+        if curve.complete {
+            Some(Self::Migrated)
+        } else if peak_real_sol >= RUG_MIN_PEAK_LAMPORTS
+            && curve.real_sol_reserves <= RUG_RESIDUAL_LAMPORTS
+        {
+            Some(Self::Rugged)
+        } else {
+            None
+        }
+    }
+}
+
+/// Launch history of one creator
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CreatorRecord {
+    pub launches: u32,
+    pub migrated: u32,
+    pub rugged: u32,
+    /// Unix seconds of the last launch or outcome, used for eviction
+    #[serde(default)]
+    pub last_seen: u64,
+}
+
+impl CreatorRecord {
+    /// Share of resolved launches that migrated, smoothed so unknown creators score 0.5
+    pub fn score(&self) -> f64 {
+        (self.migrated as f64 + 1.0) / ((self.migrated + self.rugged) as f64 + 2.0)
+    }
+}
+
+/// Creator records keyed by address, saved as JSON when a path is set
+#[derive(Debug, Default)]
+pub struct CreatorReputation {
+    path: Option<PathBuf>,
+    creators: HashMap<String, CreatorRecord>,
+    /// Records changed since the last save
+    dirty: bool,
+}
+
+impl CreatorReputation {
+    /// Load from `path`; a missing or unreadable file starts empty
+    pub fn load(path: Option<&Path>) -> Result<Self, SniperError> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+
+        let creators = match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                warn!(
+                    "Invalid reputation file {}, starting empty: {}",
+                    path.display(),
+                    e
+                );
+                HashMap::new()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => {
+                return Err(SniperError::IoError(format!(
+                    "Failed to read reputation file {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            creators,
+            dirty: false,
+        })
+    }
+
+    pub fn record(&self, creator: &Pubkey) -> CreatorRecord {
+        self.creators
+            .get(&creator.to_string())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn score(&self, creator: &Pubkey) -> f64 {
+        self.record(creator).score()
+    }
+
+    pub fn record_launch(&mut self, creator: &Pubkey) {
+        self.touch(creator).launches += 1;
+    }
+
+    pub fn record_outcome(&mut self, creator: &Pubkey, outcome: LaunchOutcome) {
+        let record = self.touch(creator);
+        match outcome {
+            LaunchOutcome::Migrated => record.migrated += 1,
+            LaunchOutcome::Rugged => record.rugged += 1,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.creators.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.creators.is_empty()
+    }
+
+    /// Record for `creator`, stamped as seen now and marked for saving
+    fn touch(&mut self, creator: &Pubkey) -> &mut CreatorRecord {
+        let key = creator.to_string();
+        if !self.creators.contains_key(&key) && self.creators.len() >= MAX_CREATORS {
+            self.evict_least_recent();
+        }
+        self.dirty = true;
+        let record = self.creators.entry(key).or_default();
+        record.last_seen = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        record
+    }
+
+    /// Drop the `EVICTION_BATCH` least recently seen creators
+    fn evict_least_recent(&mut self) {
+        let mut last_seen: Vec<u64> = self.creators.values().map(|r| r.last_seen).collect();
+        let index = (EVICTION_BATCH - 1).min(last_seen.len() - 1);
+        let (older, cutoff, _) = last_seen.select_nth_unstable(index);
+        let cutoff = *cutoff;
+        // everything older goes, ties at the cutoff fill the rest of the batch
+        let mut ties = EVICTION_BATCH - older.iter().filter(|seen| **seen < cutoff).count();
+        self.creators.retain(|_, record| {
+            if record.last_seen < cutoff {
+                false
+            } else if record.last_seen == cutoff && ties > 0 {
+                ties -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Save if anything changed since the last save
+    pub fn flush(&mut self) -> Result<(), SniperError> {
+        if !self.dirty {
+            return Ok(());
+        }
+        self.save()?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Write every record to disk through a temp file and rename, so a crash mid-write
+    /// leaves the previous file intact; no-op without a path
+    pub fn save(&self) -> Result<(), SniperError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let json = serde_json::to_string(&self.creators)
+            .map_err(|e| SniperError::SerializationError(e.to_string()))?;
+        let mut temp = path.clone().into_os_string();
+        temp.push(".tmp");
+        fs::write(&temp, json)
+            .and_then(|_| fs::rename(&temp, path))
+            .map_err(|e| {
+                SniperError::IoError(format!(
+                    "Failed to write reputation file {}: {}",
+                    path.display(),
+                    e
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::test_curve;
+
+    #[test]
+    fn test_outcomes_scores_and_persistence() {
+        let path = std::env::temp_dir().join(format!(
+            "pump-sniper-reputation-{}.json",
+            Pubkey::new_unique()
+        ));
+        let creator = Pubkey::new_unique();
+
+        let mut reputation = CreatorReputation::load(Some(&path)).unwrap();
+        assert_eq!(reputation.score(&creator), 0.5);
+
+        let drained = BondingCurveAccount {
+            real_sol_reserves: 0,
+            ..test_curve()
+        };
+        let completed = BondingCurveAccount {
+            complete: true,
+            ..test_curve()
+        };
+        assert_eq!(LaunchOutcome::observe(&drained, 100_000_000), None);
+        assert_eq!(
+            LaunchOutcome::observe(&drained, RUG_MIN_PEAK_LAMPORTS),
+            Some(LaunchOutcome::Rugged)
+        );
+        assert_eq!(
+            LaunchOutcome::observe(&completed, 0),
+            Some(LaunchOutcome::Migrated)
+        );
+
+        for _ in 0..3 {
+            reputation.record_launch(&creator);
+        }
+        reputation.record_outcome(&creator, LaunchOutcome::Rugged);
+        reputation.record_outcome(&creator, LaunchOutcome::Rugged);
+        reputation.record_outcome(&creator, LaunchOutcome::Migrated);
+        assert_eq!(reputation.score(&creator), 0.4);
+        reputation.flush().unwrap();
+
+        let reloaded = CreatorReputation::load(Some(&path)).unwrap();
+        let record = reloaded.record(&creator);
+        assert_eq!((record.launches, record.migrated, record.rugged), (3, 1, 2));
+        assert!(record.last_seen > 0);
+        assert_eq!(reloaded.score(&creator), 0.4);
+
+        // a corrupt file starts empty instead of failing
+        fs::write(&path, "{ not json").unwrap();
+        let corrupt = CreatorReputation::load(Some(&path)).unwrap();
+        assert_eq!(corrupt.record(&creator), CreatorRecord::default());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_evicts_least_recently_seen_at_capacity() {
+        let mut reputation = CreatorReputation::default();
+        let stale = Pubkey::new_unique();
+        reputation.record_launch(&stale);
+        reputation
+            .creators
+            .get_mut(&stale.to_string())
+            .unwrap()
+            .last_seen = 0;
+        while reputation.len() < MAX_CREATORS {
+            reputation.record_launch(&Pubkey::new_unique());
+        }
+
+        let fresh = Pubkey::new_unique();
+        reputation.record_launch(&fresh);
+        assert_eq!(reputation.len(), MAX_CREATORS - EVICTION_BATCH + 1);
+        assert_eq!(reputation.record(&stale), CreatorRecord::default());
+        assert_eq!(reputation.record(&fresh).launches, 1);
+    }
+}
@@ -0,0 +1,126 @@
+//! Shadow evaluation of candidate thresholds
+
+use std::collections::HashMap;
+
+/// Per-threshold hit counts for calibrating the market cap threshold
+#[derive(Debug, Clone, Default)]
+pub struct ShadowReport {
+    /// Candidate thresholds in USD, ascending
+    thresholds: Vec<f64>,
+    /// Tokens that crossed each threshold
+    hits: Vec<usize>,
+    /// Number of thresholds each tracked mint has crossed so far
+    crossed: HashMap<String, usize>,
+    /// Distinct mints that crossed at least one threshold, including forgotten ones
+    tokens_seen: usize,
+}
+
+impl ShadowReport {
+    pub fn new(mut thresholds: Vec<f64>) -> Self {
+        thresholds.sort_by(|a, b| a.total_cmp(b));
+        thresholds.dedup();
+        let hits = vec![0; thresholds.len()];
+
+        Self {
+            thresholds,
+            hits,
+            crossed: HashMap::new(),
+            tokens_seen: 0,
+        }
+    }
+
+    /// Shadow mode is active when any candidate threshold is configured
+    pub fn is_enabled(&self) -> bool {
+        !self.thresholds.is_empty()
+    }
+
+    /// Record a market cap observation, returning thresholds newly crossed by this mint
+    pub fn record(&mut self, mint: &str, market_cap_usd: f64) -> Vec<f64> {
+        let already = self.crossed.get(mint).copied().unwrap_or(0);
+        let now = self
+            .thresholds
+            .iter()
+            .take_while(|&&threshold| market_cap_usd >= threshold)
+            .count();
+
+        if now <= already {
+            return Vec::new();
+        }
+
+        if already == 0 {
+            self.tokens_seen += 1;
+        }
+        for hit in &mut self.hits[already..now] {
+            *hit += 1;
+        }
+        self.crossed.insert(mint.to_string(), now);
+
+        self.thresholds[already..now].to_vec()
+    }
+
+    /// (threshold, hit count) pairs, ascending by threshold
+    pub fn hits(&self) -> Vec<(f64, usize)> {
+        self.thresholds
+            .iter()
+            .copied()
+            .zip(self.hits.iter().copied())
+            .collect()
+    }
+
+    /// Number of distinct tokens that crossed at least one threshold
+    pub fn tokens_seen(&self) -> usize {
+        self.tokens_seen
+    }
+
+    /// Drop per-mint state for a token no longer tracked; hit counts are kept
+    pub fn forget(&mut self, mint: &str) {
+        self.crossed.remove(mint);
+    }
+
+    /// Whether per-mint state is held for `mint`
+    pub fn is_tracking(&self, mint: &str) -> bool {
+        self.crossed.contains_key(mint)
+    }
+
+    /// One-line summary for logging
+    pub fn summary(&self) -> String {
+        self.hits()
+            .iter()
+            .map(|(threshold, count)| format!("${:.0}: {}", threshold, count))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_per_threshold_hit_counts() {
+        let mut report = ShadowReport::new(vec![15_000.0, 8_000.0, 10_000.0]);
+
+        report.record("a", 5_000.0);
+        report.record("b", 9_000.0);
+        report.record("c", 12_000.0);
+        report.record("d", 20_000.0);
+
+        assert_eq!(
+            report.hits(),
+            vec![(8_000.0, 3), (10_000.0, 2), (15_000.0, 1)]
+        );
+    }
+
+    #[test]
+    fn test_repeat_observations_count_once() {
+        let mut report = ShadowReport::new(vec![8_000.0, 10_000.0]);
+
+        assert_eq!(report.record("a", 9_000.0), vec![8_000.0]);
+        assert!(report.record("a", 9_500.0).is_empty());
+        assert_eq!(report.record("a", 11_000.0), vec![10_000.0]);
+        assert!(report.record("a", 7_000.0).is_empty());
+
+        assert_eq!(report.hits(), vec![(8_000.0, 1), (10_000.0, 1)]);
+        assert_eq!(report.tokens_seen(), 1);
+    }
+}
@@ -0,0 +1,128 @@
+//! StatsD metrics over UDP
+
+use super::SniperStats;
+use std::net::UdpSocket;
+use std::time::Duration;
+use tracing::warn;
+
+/// Prefix on every metric name
+const METRIC_PREFIX: &str = "pump_sniper";
+
+/// Sends sniper stats as StatsD gauges, counters and timings. Counters carry the change
+/// since the previous emit
+pub struct StatsdEmitter {
+    socket: UdpSocket,
+    last_successful_buys: usize,
+    last_failed_buys: usize,
+}
+
+impl StatsdEmitter {
+    /// Emitter sending to `address` (`host:port`)
+    pub fn new(address: &str) -> std::io::Result<Self> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.connect(address)?;
+        // metrics must never hold up the event loop
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            last_successful_buys: 0,
+            last_failed_buys: 0,
+        })
+    }
+
+    /// Metric lines for `stats`, advancing the counter baselines
+    pub fn stats_lines(&mut self, stats: &SniperStats) -> Vec<String> {
+        let buys = stats
+            .successful_buys
+            .saturating_sub(self.last_successful_buys);
+        let failures = stats.failed_buys.saturating_sub(self.last_failed_buys);
+        self.last_successful_buys = stats.successful_buys;
+        self.last_failed_buys = stats.failed_buys;
+
+        vec![
+            format!("{}.tokens_tracked:{}|g", METRIC_PREFIX, stats.tracked),
+            format!("{}.tokens_bought:{}|g", METRIC_PREFIX, stats.bought),
+            format!("{}.buys:{}|c", METRIC_PREFIX, buys),
+            format!("{}.buy_failures:{}|c", METRIC_PREFIX, failures),
+            format!(
+                "{}.uptime_seconds:{}|g",
+                METRIC_PREFIX, stats.uptime_seconds
+            ),
+            format!(
+                "{}.max_drawdown_bps:{}|g",
+                METRIC_PREFIX, stats.max_drawdown_bps
+            ),
+        ]
+    }
+
+    /// Send `stats` as one packet
+    pub fn emit_stats(&mut self, stats: &SniperStats) {
+        let lines = self.stats_lines(stats);
+        self.send(&lines.join("\n"));
+    }
+
+    /// Send a timing for `name` in milliseconds
+    pub fn timing(&self, name: &str, elapsed: Duration) {
+        self.send(&format!(
+            "{}.{}:{}|ms",
+            METRIC_PREFIX,
+            name,
+            elapsed.as_millis()
+        ));
+    }
+
+    fn send(&self, payload: &str) {
+        if let Err(e) = self.socket.send(payload.as_bytes()) {
+            warn!("StatsD send failed: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receive(socket: &UdpSocket) -> String {
+        let mut buf = [0u8; 1024];
+        let len = socket.recv(&mut buf).unwrap();
+        String::from_utf8(buf[..len].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_emits_metric_lines_over_udp() {
+        let collector = UdpSocket::bind("127.0.0.1:0").unwrap();
+        collector
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        let mut emitter = StatsdEmitter::new(&collector.local_addr().unwrap().to_string()).unwrap();
+
+        let mut stats = SniperStats {
+            tracked: 12,
+            bought: 3,
+            successful_buys: 2,
+            failed_buys: 1,
+            uptime_seconds: 60,
+            max_drawdown_bps: 1_840,
+        };
+        emitter.emit_stats(&stats);
+        assert_eq!(
+            receive(&collector),
+            "pump_sniper.tokens_tracked:12|g\n\
+             pump_sniper.tokens_bought:3|g\n\
+             pump_sniper.buys:2|c\n\
+             pump_sniper.buy_failures:1|c\n\
+             pump_sniper.uptime_seconds:60|g\n\
+             pump_sniper.max_drawdown_bps:1840|g"
+        );
+
+        // counters only carry what happened since the last emit
+        stats.successful_buys = 5;
+        emitter.emit_stats(&stats);
+        let packet = receive(&collector);
+        assert!(packet.contains("pump_sniper.buys:3|c"));
+        assert!(packet.contains("pump_sniper.buy_failures:0|c"));
+
+        emitter.timing("buy_ms", Duration::from_millis(420));
+        assert_eq!(receive(&collector), "pump_sniper.buy_ms:420|ms");
+    }
+}
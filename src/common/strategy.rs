@@ -0,0 +1,151 @@
+//! Buy decision strategies
+
+use super::Config;
+use serde::{Deserialize, Serialize};
+
+/// Signals observed for a token at decision time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokenSignals {
+    /// Market cap in USD
+    pub market_cap_usd: f64,
+    /// Curve updates that added SOL since tracking began
+    pub buy_count: u32,
+    /// Real SOL in the curve in lamports
+    pub real_sol_reserves: u64,
+}
+
+/// Relative weight of each signal in the score
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScoringWeights {
+    pub market_cap: f64,
+    pub momentum: f64,
+    pub liquidity: f64,
+}
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        Self {
+            market_cap: 1.0,
+            momentum: 1.0,
+            liquidity: 1.0,
+        }
+    }
+}
+
+/// Weighted score over the signals, each capped at its target, in `[0, 1]`
+#[derive(Debug, Clone)]
+pub struct ScoringStrategy {
+    weights: ScoringWeights,
+    threshold: f64,
+    market_cap_target_usd: f64,
+    momentum_target_buys: u32,
+    liquidity_target_lamports: u64,
+}
+
+impl ScoringStrategy {
+    /// Scoring strategy when `buy_score_threshold` is set, None for simple threshold mode
+    pub fn from_config(config: &Config) -> Option<Self> {
+        Some(Self {
+            weights: config.score_weights,
+            threshold: config.buy_score_threshold?,
+            market_cap_target_usd: config.market_cap_threshold_usd,
+            momentum_target_buys: config.score_momentum_target_buys,
+            liquidity_target_lamports: config.score_liquidity_target_sol,
+        })
+    }
+
+    pub fn score(&self, signals: &TokenSignals) -> f64 {
+        let ratio = |value: f64, target: f64| {
+            if target <= 0.0 {
+                1.0
+            } else {
+                (value / target).clamp(0.0, 1.0)
+            }
+        };
+
+        let weights = &self.weights;
+        let total = weights.market_cap + weights.momentum + weights.liquidity;
+        if total <= 0.0 {
+            return 0.0;
+        }
+
+        (weights.market_cap * ratio(signals.market_cap_usd, self.market_cap_target_usd)
+            + weights.momentum * ratio(signals.buy_count as f64, self.momentum_target_buys as f64)
+            + weights.liquidity
+                * ratio(
+                    signals.real_sol_reserves as f64,
+                    self.liquidity_target_lamports as f64,
+                ))
+            / total
+    }
+
+    pub fn should_buy(&self, signals: &TokenSignals) -> bool {
+        self.score(signals) >= self.threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strategy(threshold: f64) -> ScoringStrategy {
+        let config = Config {
+            market_cap_threshold_usd: 10_000.0,
+            buy_score_threshold: Some(threshold),
+            score_momentum_target_buys: 20,
+            score_liquidity_target_sol: 5_000_000_000,
+            ..Config::default()
+        };
+        ScoringStrategy::from_config(&config).unwrap()
+    }
+
+    #[test]
+    fn test_scores_and_trigger_boundary() {
+        let strategy = strategy(0.8);
+        let signals = |market_cap_usd, buy_count, real_sol_reserves| TokenSignals {
+            market_cap_usd,
+            buy_count,
+            real_sol_reserves,
+        };
+
+        // every signal at target
+        let hot = signals(12_000.0, 25, 6_000_000_000);
+        assert_eq!(strategy.score(&hot), 1.0);
+        assert!(strategy.should_buy(&hot));
+
+        // cap alone no longer triggers
+        let cap_only = signals(50_000.0, 0, 0);
+        assert!((strategy.score(&cap_only) - 1.0 / 3.0).abs() < 1e-9);
+        assert!(!strategy.should_buy(&cap_only));
+
+        // exactly on the boundary: (1.0 + 0.7 + 0.7) / 3 = 0.8
+        let boundary = signals(10_000.0, 14, 3_500_000_000);
+        assert!((strategy.score(&boundary) - 0.8).abs() < 1e-9);
+        assert!(strategy.should_buy(&signals(10_000.0, 14, 3_600_000_000)));
+        assert!(!strategy.should_buy(&signals(10_000.0, 14, 3_400_000_000)));
+
+        assert_eq!(strategy.score(&TokenSignals::default()), 0.0);
+    }
+
+    #[test]
+    fn test_weights_shift_the_score() {
+        let mut strategy = strategy(0.5);
+        strategy.weights = ScoringWeights {
+            market_cap: 0.0,
+            momentum: 3.0,
+            liquidity: 1.0,
+        };
+        let momentum_only = TokenSignals {
+            market_cap_usd: 0.0,
+            buy_count: 20,
+            real_sol_reserves: 0,
+        };
+        assert_eq!(strategy.score(&momentum_only), 0.75);
+        assert!(strategy.should_buy(&momentum_only));
+    }
+
+    #[test]
+    fn test_disabled_without_threshold() {
+        assert!(ScoringStrategy::from_config(&Config::default()).is_none());
+    }
+}
@@ -1,56 +1,259 @@
 //! gRPC streaming utilities
 
 use crate::{
+    accounts::BondingCurveAccount,
     common::{Config, SniperEvent},
     error::SniperError,
-    utils::parser,
+    utils::{
+        derive_bonding_curve_pda,
+        parser::{self, ParserPool},
+    },
 };
 use anyhow::Result;
-use futures::{sink::SinkExt, stream::StreamExt};
+use async_trait::async_trait;
+use futures::{
+    sink::{Sink, SinkExt},
+    stream::{Stream, StreamExt},
+};
+use prost::Message;
 use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
-use tonic::transport::ClientTlsConfig;
-use tracing::{error, info};
+use tonic::{transport::ClientTlsConfig, Status};
+use tracing::{error, info, warn};
 use yellowstone_grpc_client::GeyserGrpcClient;
 use yellowstone_grpc_proto::prelude::*;
 
+/// A connection that stays up this long resets the reconnect backoff
+const STABLE_CONNECTION: Duration = Duration::from_secs(60);
+
+/// Nominal slot time, for pacing replays
+const SLOT_DURATION: Duration = Duration::from_millis(400);
+
+/// Where the sniper's chain updates come from
+#[async_trait]
+pub trait StreamSource: Send {
+    /// Feed updates into the event channel until the source is exhausted or dropped
+    async fn start(&mut self) -> Result<(), SniperError>;
+
+    /// Highest slot seen so far (0 before the first update)
+    fn latest_slot(&self) -> u64;
+}
+
+/// Exponential reconnect delay, doubling from `base` up to `max`
+#[derive(Debug, Clone)]
+pub struct ReconnectBackoff {
+    base: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl ReconnectBackoff {
+    pub fn new(base: Duration, max: Duration) -> Self {
+        Self {
+            base,
+            max,
+            current: base.min(max),
+        }
+    }
+
+    /// Delay before the next attempt
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.current;
+        self.current = (self.current * 2).min(self.max);
+        delay
+    }
+
+    /// Start again from `base`
+    pub fn reset(&mut self) {
+        self.current = self.base.min(self.max);
+    }
+}
+
+/// Updates stored in a capture file, in order
+pub fn read_capture(path: &Path) -> Result<Vec<SubscribeUpdate>, SniperError> {
+    let data = std::fs::read(path).map_err(|e| {
+        SniperError::IoError(format!(
+            "Failed to read capture file {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    let mut buf = data.as_slice();
+    let mut updates = Vec::new();
+    while !buf.is_empty() {
+        let update = SubscribeUpdate::decode_length_delimited(&mut buf).map_err(|e| {
+            SniperError::SerializationError(format!(
+                "Corrupt capture after {} updates: {}",
+                updates.len(),
+                e
+            ))
+        })?;
+        updates.push(update);
+    }
+    Ok(updates)
+}
+
+/// Slot an update belongs to, for updates that carry one
+fn update_slot(update: &SubscribeUpdate) -> Option<u64> {
+    match update.update_oneof.as_ref()? {
+        subscribe_update::UpdateOneof::Transaction(transaction) => Some(transaction.slot),
+        subscribe_update::UpdateOneof::Account(account) => Some(account.slot),
+        subscribe_update::UpdateOneof::Slot(slot) => Some(slot.slot),
+        _ => None,
+    }
+}
+
+fn open_capture(path: &Path) -> Result<BufWriter<File>, SniperError> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| {
+            SniperError::IoError(format!(
+                "Failed to open capture file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+    info!("CAPTURING raw updates to {}", path.display());
+    Ok(BufWriter::new(file))
+}
+
 pub struct StreamClient {
     config: Config,
     event_sender: mpsc::UnboundedSender<SniperEvent>,
+    /// Highest slot seen on the stream
+    latest_slot: Arc<AtomicU64>,
+    /// Raw updates are appended here, length-prefixed, when capturing
+    capture: Option<BufWriter<File>>,
+    /// Create parsing workers; None parses on the stream task
+    parser: Option<ParserPool>,
 }
 
 impl StreamClient {
+    /// Spawns `parser_workers` tasks, so must be called inside a runtime
     pub fn new(config: Config, event_sender: mpsc::UnboundedSender<SniperEvent>) -> Self {
+        let parser = (config.parser_workers > 0)
+            .then(|| ParserPool::spawn(config.parser_workers, event_sender.clone()));
+
         Self {
             config,
             event_sender,
+            latest_slot: Arc::new(AtomicU64::new(0)),
+            capture: None,
+            parser,
+        }
+    }
+
+    /// Append every received update to `path` for later replay
+    pub fn with_capture(mut self, path: impl AsRef<Path>) -> Result<Self, SniperError> {
+        self.capture = Some(open_capture(path.as_ref())?);
+        Ok(self)
+    }
+
+    /// Feed a capture file through the update handlers, returning the update count
+    pub async fn replay(&self, path: impl AsRef<Path>) -> Result<usize, SniperError> {
+        let path = path.as_ref();
+        let updates = read_capture(path)?;
+        let count = updates.len();
+        for update in updates {
+            if let Err(e) = self.handle_update(update).await {
+                error!("Error handling replayed update: {}", e);
+            }
+        }
+
+        info!("REPLAYED {} updates from {}", count, path.display());
+        Ok(count)
+    }
+
+    fn capture_update(&mut self, update: &SubscribeUpdate) {
+        let Some(writer) = self.capture.as_mut() else {
+            return;
+        };
+        let result = writer
+            .write_all(&update.encode_length_delimited_to_vec())
+            .and_then(|_| writer.flush());
+        if let Err(e) = result {
+            error!("Capture write failed, disabling capture: {}", e);
+            self.capture = None;
         }
     }
 
+    /// Share the latest-slot counter with an external observer
+    pub fn with_slot_tracker(mut self, latest_slot: Arc<AtomicU64>) -> Self {
+        self.latest_slot = latest_slot;
+        self
+    }
+
+    /// Highest slot seen on the stream so far (0 before the first update)
+    pub fn latest_slot(&self) -> u64 {
+        self.latest_slot.load(Ordering::Relaxed)
+    }
+
+    /// Stream until the task is dropped, reconnecting with exponential backoff
     pub async fn start(&mut self) -> Result<(), SniperError> {
+        if let (Some(path), None) = (&self.config.capture_path, &self.capture) {
+            self.capture = Some(open_capture(Path::new(path))?);
+        }
+
+        let mut backoff = ReconnectBackoff::new(
+            Duration::from_millis(self.config.reconnect_base_backoff_ms),
+            Duration::from_secs(self.config.reconnect_max_backoff_secs),
+        );
+
+        loop {
+            let connected_at = Instant::now();
+            match self.run_session().await {
+                Ok(()) => warn!("gRPC stream closed by server"),
+                Err(SniperError::InvalidConfig(e)) => {
+                    return Err(SniperError::InvalidConfig(e));
+                }
+                Err(e) => error!("gRPC stream failed: {}", e),
+            }
+
+            let _ = self
+                .event_sender
+                .send(SniperEvent::ConnectionStatusChanged {
+                    connected: false,
+                    endpoint: self.config.grpc_endpoint.clone(),
+                });
+
+            if connected_at.elapsed() >= STABLE_CONNECTION {
+                backoff.reset();
+            }
+            let delay = backoff.next_delay();
+            info!("RECONNECTING in {:?}", delay);
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// One connect-subscribe-consume cycle
+    async fn run_session(&mut self) -> Result<(), SniperError> {
         info!("CONNECTING to gRPC endpoint: {}", self.config.grpc_endpoint);
 
         let mut client = GeyserGrpcClient::build_from_shared(self.config.grpc_endpoint.clone())
-            .map_err(|e| SniperError::GrpcConnectionFailed(e.to_string()))?
+            .map_err(|e| SniperError::InvalidConfig(e.to_string()))?
             .tls_config(ClientTlsConfig::new())
-            .map_err(|e| SniperError::GrpcConnectionFailed(e.to_string()))?
+            .map_err(|e| SniperError::InvalidConfig(e.to_string()))?
             .connect()
             .await
             .map_err(|e| SniperError::GrpcConnectionFailed(e.to_string()))?;
 
-        info!("CONNECTED to gRPC endpoint");
-
-        let _ = self
-            .event_sender
-            .send(SniperEvent::ConnectionStatusChanged {
-                connected: true,
-                endpoint: self.config.grpc_endpoint.clone(),
-            });
-
-        let (mut subscribe_tx, mut subscribe_rx) = client
+        let (subscribe_tx, subscribe_rx) = client
             .subscribe()
             .await
             .map_err(|e| SniperError::GrpcConnectionFailed(e.to_string()))?;
+        let mut subscribe_tx = Box::pin(subscribe_tx);
+        let mut subscribe_rx = Box::pin(subscribe_rx);
 
         let request = self.create_subscription_request();
 
@@ -59,29 +262,50 @@ impl StreamClient {
             .await
             .map_err(|e| SniperError::GrpcConnectionFailed(e.to_string()))?;
 
+        info!("CONNECTED to gRPC endpoint");
+
+        let _ = self
+            .event_sender
+            .send(SniperEvent::ConnectionStatusChanged {
+                connected: true,
+                endpoint: self.config.grpc_endpoint.clone(),
+            });
+
         info!("SUBSCRIPTION ACTIVE - monitoring Pump transactions...");
 
-        while let Some(update) = subscribe_rx.next().await {
-            match update {
-                Ok(update) => {
-                    if let Err(e) = self.handle_update(update).await {
-                        error!("Error handling update: {}", e);
-                    }
-                }
-                Err(e) => {
-                    error!("Stream error: {}", e);
+        self.consume_updates(&mut subscribe_rx, &mut subscribe_tx)
+            .await
+    }
 
-                    let _ = self
-                        .event_sender
-                        .send(SniperEvent::ConnectionStatusChanged {
-                            connected: false,
-                            endpoint: self.config.grpc_endpoint.clone(),
-                        });
+    /// Drain the update stream, answering pings through the subscribe sink
+    async fn consume_updates<St, Si>(
+        &mut self,
+        updates: &mut St,
+        subscribe_tx: &mut Si,
+    ) -> Result<(), SniperError>
+    where
+        St: Stream<Item = Result<SubscribeUpdate, Status>> + Unpin,
+        Si: Sink<SubscribeRequest> + Unpin,
+        Si::Error: std::fmt::Display,
+    {
+        while let Some(update) = updates.next().await {
+            let update = update.map_err(|e| SniperError::GrpcConnectionFailed(e.to_string()))?;
+            self.capture_update(&update);
 
-                    tokio::time::sleep(tokio::time::Duration::from_millis(5000)).await;
+            if let Some(subscribe_update::UpdateOneof::Ping(_)) = update.update_oneof {
+                // providers drop idle subscriptions that never pong
+                subscribe_tx
+                    .send(SubscribeRequest {
+                        ping: Some(SubscribeRequestPing { id: 1 }),
+                        ..Default::default()
+                    })
+                    .await
+                    .map_err(|e| SniperError::GrpcConnectionFailed(e.to_string()))?;
+                continue;
+            }
 
-                    return Err(SniperError::GrpcConnectionFailed(e.to_string()));
-                }
+            if let Err(e) = self.handle_update(update).await {
+                error!("Error handling update: {}", e);
             }
         }
 
@@ -91,21 +315,46 @@ impl StreamClient {
     fn create_subscription_request(&self) -> SubscribeRequest {
         use crate::constants::PUMPFUN_PROGRAM_ID;
 
+        let watch = &self.config.stream_filter;
+        // bonding curve updates; named filters are ORed, so a watchlist gets one for the
+        // watched mints' curves and one per creator
+        let mut accounts = HashMap::new();
+        if watch.is_empty() {
+            accounts.insert("bonding_curves".to_string(), curve_filter(vec![], None));
+        }
+        if !watch.mints.is_empty() {
+            let curves = watch
+                .sorted_mints()
+                .iter()
+                .filter_map(|mint| derive_bonding_curve_pda(mint).ok())
+                .map(|curve| curve.to_string())
+                .collect();
+            accounts.insert("watched_curves".to_string(), curve_filter(curves, None));
+        }
+        for creator in watch.sorted_creators() {
+            accounts.insert(
+                format!("creator_curves_{}", creator),
+                curve_filter(vec![], Some(&creator)),
+            );
+        }
+
+        // with a watchlist, Pump transactions touching any watched account
+        let (account_include, account_required) = if watch.is_empty() {
+            (vec![PUMPFUN_PROGRAM_ID.to_string()], vec![])
+        } else {
+            (
+                watch
+                    .sorted_mints()
+                    .into_iter()
+                    .chain(watch.sorted_creators())
+                    .map(|key| key.to_string())
+                    .collect(),
+                vec![PUMPFUN_PROGRAM_ID.to_string()],
+            )
+        };
+
         SubscribeRequest {
-            // bonding curve updates
-            accounts: [(
-                "bonding_curves".to_string(),
-                SubscribeRequestFilterAccounts {
-                    account: vec![],
-                    owner: vec![PUMPFUN_PROGRAM_ID.to_string()],
-                    filters: vec![SubscribeRequestFilterAccountsFilter {
-                        filter: Some(subscribe_request_filter_accounts_filter::Filter::Datasize(
-                            8 + 32 + 32 + 8 + 8 + 8 + 8 + 1, // BondingCurveAccount size
-                        )),
-                    }],
-                },
-            )]
-            .into(),
+            accounts,
             slots: HashMap::new(),
             transactions: [(
                 "pumpfun_transactions".to_string(),
@@ -113,9 +362,9 @@ impl StreamClient {
                     vote: Some(false),
                     failed: Some(false),
                     signature: None,
-                    account_include: vec![PUMPFUN_PROGRAM_ID.to_string()],
+                    account_include,
                     account_exclude: vec![],
-                    account_required: vec![],
+                    account_required,
                 },
             )]
             .into(),
@@ -125,19 +374,21 @@ impl StreamClient {
             entry: HashMap::new(),
             accounts_data_slice: vec![],
             ping: None,
-            commitment: Some(CommitmentLevel::Processed as i32),
+            commitment: Some(grpc_commitment(self.config.stream_commitment()) as i32),
         }
     }
 
     async fn handle_update(&self, update: SubscribeUpdate) -> Result<()> {
         match update.update_oneof {
             Some(subscribe_update::UpdateOneof::Transaction(transaction)) => {
+                self.latest_slot
+                    .fetch_max(transaction.slot, Ordering::Relaxed);
                 self.handle_transaction(transaction).await
             }
             Some(subscribe_update::UpdateOneof::Account(account)) => {
+                self.latest_slot.fetch_max(account.slot, Ordering::Relaxed);
                 self.handle_account_update(account).await
             }
-            Some(subscribe_update::UpdateOneof::Ping(_)) => Ok(()),
             _ => Ok(()),
         }
     }
@@ -149,18 +400,20 @@ impl StreamClient {
                     let signature = bs58::encode(&transaction_info.signature).into_string();
 
                     if parser::is_create_transaction(&transaction_info) {
-                        info!("TOKEN CREATION DETECTED: {}", signature);
-
-                        if let Some(token_info) =
-                            parser::parse_token_creation(&transaction_info, signature)
-                        {
-                            if let Err(e) = self
-                                .event_sender
-                                .send(SniperEvent::TokenCreated(token_info))
-                            {
-                                error!("Failed to send token creation event: {}", e);
+                        match &self.parser {
+                            Some(pool) => {
+                                pool.submit(transaction_info, signature, transaction.slot)
+                                    .await
                             }
+                            None => parser::parse_and_emit(
+                                &transaction_info,
+                                signature,
+                                transaction.slot,
+                                &self.event_sender,
+                            ),
                         }
+                    } else if let Some(swap) = parser::parse_swap(&transaction_info) {
+                        let _ = self.event_sender.send(SniperEvent::SwapDetected(swap));
                     }
                 }
             }
@@ -172,22 +425,456 @@ impl StreamClient {
         if let Some(account_info) = account_update.account {
             let account_key = bs58::encode(&account_info.pubkey).into_string();
             if let Ok(pubkey) = account_key.parse::<solana_sdk::pubkey::Pubkey>() {
-                if let Ok(bonding_curve_data) = solana_sdk::borsh1::try_from_slice_unchecked::<
-                    crate::accounts::BondingCurveAccount,
-                >(&account_info.data)
-                {
-                    if let Err(e) =
-                        self.event_sender
-                            .send(crate::common::SniperEvent::BondingCurveUpdated {
+                match crate::accounts::BondingCurveAccount::from_account_data(&account_info.data) {
+                    Ok(bonding_curve_data) => {
+                        if let Err(e) = self.event_sender.send(
+                            crate::common::SniperEvent::BondingCurveUpdated {
                                 bonding_curve: pubkey,
                                 data: bonding_curve_data,
-                            })
-                    {
-                        error!("Failed to send bonding curve update: {}", e);
+                            },
+                        ) {
+                            error!("Failed to send bonding curve update: {}", e);
+                        }
                     }
+                    // a layout change would otherwise leave the sniper blind
+                    Err(e) => warn!("Unreadable bonding curve update for {}: {}", pubkey, e),
                 }
             }
         }
         Ok(())
     }
 }
+
+#[async_trait]
+impl StreamSource for StreamClient {
+    async fn start(&mut self) -> Result<(), SniperError> {
+        StreamClient::start(self).await
+    }
+
+    fn latest_slot(&self) -> u64 {
+        StreamClient::latest_slot(self)
+    }
+}
+
+/// Plays a capture file back through the live update handlers, so a session produces
+/// the same events it did when recorded. Creates are parsed inline to keep their order,
+/// and a disconnect is reported once the capture runs out
+pub struct ReplayClient {
+    path: String,
+    speed: f64,
+    client: StreamClient,
+}
+
+impl ReplayClient {
+    /// Replay `path` at `speed` times the capture's slot pace, or without delays at 0
+    pub fn new(
+        config: Config,
+        path: impl Into<String>,
+        speed: f64,
+        event_sender: mpsc::UnboundedSender<SniperEvent>,
+    ) -> Self {
+        let config = Config {
+            parser_workers: 0,
+            ..config
+        };
+        Self {
+            path: path.into(),
+            speed,
+            client: StreamClient::new(config, event_sender),
+        }
+    }
+
+    /// Share the latest-slot counter with an external observer
+    pub fn with_slot_tracker(mut self, latest_slot: Arc<AtomicU64>) -> Self {
+        self.client = self.client.with_slot_tracker(latest_slot);
+        self
+    }
+
+    /// Wait between updates `slots` apart
+    fn delay(&self, slots: u64) -> Option<Duration> {
+        (self.speed > 0.0 && slots > 0).then(|| SLOT_DURATION.mul_f64(slots as f64 / self.speed))
+    }
+}
+
+#[async_trait]
+impl StreamSource for ReplayClient {
+    async fn start(&mut self) -> Result<(), SniperError> {
+        let updates = read_capture(Path::new(&self.path))?;
+        info!("REPLAYING {} updates from {}", updates.len(), self.path);
+        let _ = self
+            .client
+            .event_sender
+            .send(SniperEvent::ConnectionStatusChanged {
+                connected: true,
+                endpoint: format!("replay:{}", self.path),
+            });
+
+        let mut previous_slot = None;
+        let count = updates.len();
+        for update in updates {
+            if let Some(slot) = update_slot(&update) {
+                let gap = previous_slot.map_or(0, |previous| slot.saturating_sub(previous));
+                if let Some(delay) = self.delay(gap) {
+                    tokio::time::sleep(delay).await;
+                }
+                previous_slot = Some(slot);
+            }
+            if let Err(e) = self.client.handle_update(update).await {
+                error!("Error handling replayed update: {}", e);
+            }
+        }
+
+        info!("REPLAY finished: {} updates from {}", count, self.path);
+        // the end of the capture is the end of the stream, which stops the sniper
+        let _ = self
+            .client
+            .event_sender
+            .send(SniperEvent::ConnectionStatusChanged {
+                connected: false,
+                endpoint: format!("replay:{}", self.path),
+            });
+        Ok(())
+    }
+
+    fn latest_slot(&self) -> u64 {
+        self.client.latest_slot()
+    }
+}
+
+/// Pump-owned bonding curves, limited to `account` when non-empty and to curves created
+/// by `creator` when given. Curves are matched on the discriminator rather than size, so
+/// they still arrive when Pump appends fields
+fn curve_filter(
+    account: Vec<String>,
+    creator: Option<&solana_sdk::pubkey::Pubkey>,
+) -> SubscribeRequestFilterAccounts {
+    use crate::constants::{BONDING_CURVE_DISCRIMINATOR, PUMPFUN_PROGRAM_ID};
+
+    let memcmp = |offset: usize, bytes: Vec<u8>| SubscribeRequestFilterAccountsFilter {
+        filter: Some(subscribe_request_filter_accounts_filter::Filter::Memcmp(
+            SubscribeRequestFilterAccountsFilterMemcmp {
+                offset: offset as u64,
+                data: Some(subscribe_request_filter_accounts_filter_memcmp::Data::Bytes(bytes)),
+            },
+        )),
+    };
+    let mut filters = vec![memcmp(0, BONDING_CURVE_DISCRIMINATOR.to_vec())];
+    if let Some(creator) = creator {
+        filters.push(memcmp(
+            BondingCurveAccount::CREATOR_OFFSET,
+            creator.to_bytes().to_vec(),
+        ));
+    }
+    SubscribeRequestFilterAccounts {
+        account,
+        owner: vec![PUMPFUN_PROGRAM_ID.to_string()],
+        filters,
+    }
+}
+
+/// Geyser commitment matching a Solana commitment level
+fn grpc_commitment(level: solana_sdk::commitment_config::CommitmentLevel) -> CommitmentLevel {
+    use solana_sdk::commitment_config::CommitmentLevel as Level;
+    match level {
+        Level::Confirmed => CommitmentLevel::Confirmed,
+        Level::Finalized => CommitmentLevel::Finalized,
+        _ => CommitmentLevel::Processed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_sequence() {
+        let mut backoff =
+            ReconnectBackoff::new(Duration::from_millis(500), Duration::from_secs(30));
+        let delays: Vec<u64> = (0..9)
+            .map(|_| backoff.next_delay().as_millis() as u64)
+            .collect();
+        assert_eq!(
+            delays,
+            [500, 1_000, 2_000, 4_000, 8_000, 16_000, 30_000, 30_000, 30_000]
+        );
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn test_subscription_commitment() {
+        use solana_sdk::commitment_config::CommitmentLevel as Level;
+        for (level, expected) in [
+            (Level::Processed, CommitmentLevel::Processed),
+            (Level::Confirmed, CommitmentLevel::Confirmed),
+            (Level::Finalized, CommitmentLevel::Finalized),
+        ] {
+            let (event_sender, _events) = mpsc::unbounded_channel();
+            let config = Config {
+                commitment: Some(level),
+                ..Config::default()
+            };
+            let client = StreamClient::new(config, event_sender);
+            assert_eq!(
+                client.create_subscription_request().commitment,
+                Some(expected as i32)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watchlist_narrows_subscription() {
+        use crate::{common::StreamFilter, constants::PUMPFUN_PROGRAM_ID};
+        use solana_sdk::pubkey::Pubkey;
+
+        let (event_sender, _events) = mpsc::unbounded_channel();
+        let request = StreamClient::new(Config::default(), event_sender.clone())
+            .create_subscription_request();
+        assert_eq!(request.accounts.len(), 1);
+        assert!(request.accounts["bonding_curves"].account.is_empty());
+        let transactions = &request.transactions["pumpfun_transactions"];
+        assert_eq!(
+            transactions.account_include,
+            [PUMPFUN_PROGRAM_ID.to_string()]
+        );
+        assert!(transactions.account_required.is_empty());
+
+        let mint = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let config = Config {
+            stream_filter: StreamFilter {
+                mints: [mint].into(),
+                creators: [creator].into(),
+            },
+            ..Config::default()
+        };
+        let request = StreamClient::new(config, event_sender).create_subscription_request();
+
+        let transactions = &request.transactions["pumpfun_transactions"];
+        assert_eq!(
+            transactions.account_include,
+            [mint.to_string(), creator.to_string()]
+        );
+        assert_eq!(
+            transactions.account_required,
+            [PUMPFUN_PROGRAM_ID.to_string()]
+        );
+
+        assert_eq!(request.accounts.len(), 2);
+        assert_eq!(
+            request.accounts["watched_curves"].account,
+            [derive_bonding_curve_pda(&mint).unwrap().to_string()]
+        );
+        let creator_curves = &request.accounts[&format!("creator_curves_{}", creator)];
+        assert!(creator_curves.account.is_empty());
+        let Some(subscribe_request_filter_accounts_filter::Filter::Memcmp(memcmp)) =
+            &creator_curves.filters[1].filter
+        else {
+            panic!("creator filter should be a memcmp");
+        };
+        assert_eq!(memcmp.offset, BondingCurveAccount::CREATOR_OFFSET as u64);
+        assert_eq!(
+            memcmp.data,
+            Some(
+                subscribe_request_filter_accounts_filter_memcmp::Data::Bytes(
+                    creator.to_bytes().to_vec()
+                )
+            )
+        );
+
+        // the creator sits where the curve layout puts it
+        let curve = BondingCurveAccount {
+            creator,
+            ..crate::utils::test_utils::test_curve()
+        };
+        let data = borsh::to_vec(&curve).unwrap();
+        assert_eq!(
+            &data[BondingCurveAccount::CREATOR_OFFSET..][..32],
+            creator.as_ref()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ping_is_answered_with_pong() {
+        let (event_sender, mut events) = mpsc::unbounded_channel();
+        let mut client = StreamClient::new(Config::default(), event_sender);
+        let ping = SubscribeUpdate {
+            filters: vec![],
+            update_oneof: Some(subscribe_update::UpdateOneof::Ping(SubscribeUpdatePing {})),
+        };
+        let mut updates = futures::stream::iter(vec![Ok(ping)]);
+        let (mut subscribe_tx, mut sent) = futures::channel::mpsc::unbounded();
+
+        client
+            .consume_updates(&mut updates, &mut subscribe_tx)
+            .await
+            .unwrap();
+
+        let pong = sent.try_next().unwrap().expect("pong queued");
+        assert_eq!(pong.ping, Some(SubscribeRequestPing { id: 1 }));
+        assert!(pong.transactions.is_empty() && pong.accounts.is_empty());
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_short_account_update_dropped() {
+        let (event_sender, mut events) = mpsc::unbounded_channel();
+        let client = StreamClient::new(Config::default(), event_sender);
+        let data = borsh::to_vec(&crate::utils::test_utils::test_curve()).unwrap();
+        let update = |data: &[u8]| SubscribeUpdateAccount {
+            account: Some(SubscribeUpdateAccountInfo {
+                pubkey: solana_sdk::pubkey::Pubkey::new_unique().to_bytes().to_vec(),
+                data: data.to_vec(),
+                ..Default::default()
+            }),
+            slot: 1,
+            is_startup: false,
+        };
+
+        client
+            .handle_account_update(update(&data[..40]))
+            .await
+            .unwrap();
+        assert!(events.try_recv().is_err());
+
+        client.handle_account_update(update(&data)).await.unwrap();
+        assert!(matches!(
+            events.try_recv(),
+            Ok(SniperEvent::BondingCurveUpdated { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_replay_client_paces_by_slot() {
+        let path = std::env::temp_dir().join(format!(
+            "pump-sniper-replay-{}.bin",
+            solana_sdk::pubkey::Pubkey::new_unique()
+        ));
+        let curve = crate::utils::test_utils::test_curve();
+        let account_at = |slot: u64| SubscribeUpdate {
+            filters: vec!["bonding_curves".to_string()],
+            update_oneof: Some(subscribe_update::UpdateOneof::Account(
+                SubscribeUpdateAccount {
+                    account: Some(SubscribeUpdateAccountInfo {
+                        pubkey: solana_sdk::pubkey::Pubkey::new_unique().to_bytes().to_vec(),
+                        data: borsh::to_vec(&curve).unwrap(),
+                        ..Default::default()
+                    }),
+                    slot,
+                    is_startup: false,
+                },
+            )),
+        };
+        let mut capture = open_capture(&path).unwrap();
+        for update in [account_at(100), account_at(110)] {
+            capture
+                .write_all(&update.encode_length_delimited_to_vec())
+                .unwrap();
+        }
+        drop(capture);
+
+        // 10 slots at 100x is 40ms
+        let (event_sender, mut events) = mpsc::unbounded_channel();
+        let mut replay: Box<dyn StreamSource> = Box::new(ReplayClient::new(
+            Config::default(),
+            path.to_string_lossy(),
+            100.0,
+            event_sender,
+        ));
+        let started = Instant::now();
+        replay.start().await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(40));
+        assert_eq!(replay.latest_slot(), 110);
+
+        assert!(matches!(
+            events.try_recv(),
+            Ok(SniperEvent::ConnectionStatusChanged {
+                connected: true,
+                ..
+            })
+        ));
+        for _ in 0..2 {
+            assert!(matches!(
+                events.try_recv(),
+                Ok(SniperEvent::BondingCurveUpdated { data, .. }) if data == curve
+            ));
+        }
+        // the end of the capture reads as a disconnect
+        assert!(matches!(
+            events.try_recv(),
+            Ok(SniperEvent::ConnectionStatusChanged {
+                connected: false,
+                ..
+            })
+        ));
+        assert!(events.try_recv().is_err());
+
+        let unpaced = ReplayClient::new(
+            Config::default(),
+            "unused",
+            0.0,
+            mpsc::unbounded_channel().0,
+        );
+        assert_eq!(unpaced.delay(10), None);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_capture_replays_identical_events() {
+        let path = std::env::temp_dir().join(format!(
+            "pump-sniper-capture-{}.bin",
+            solana_sdk::pubkey::Pubkey::new_unique()
+        ));
+        let curve = crate::utils::test_utils::test_curve();
+        let account = SubscribeUpdate {
+            filters: vec!["bonding_curves".to_string()],
+            update_oneof: Some(subscribe_update::UpdateOneof::Account(
+                SubscribeUpdateAccount {
+                    account: Some(SubscribeUpdateAccountInfo {
+                        pubkey: solana_sdk::pubkey::Pubkey::new_unique().to_bytes().to_vec(),
+                        lamports: 1_000_000,
+                        owner: crate::constants::accounts::pumpfun_program_id()
+                            .to_bytes()
+                            .to_vec(),
+                        executable: false,
+                        rent_epoch: 0,
+                        data: borsh::to_vec(&curve).unwrap(),
+                        write_version: 1,
+                        txn_signature: None,
+                    }),
+                    slot: 42,
+                    is_startup: false,
+                },
+            )),
+        };
+
+        let (live_sender, mut live_events) = mpsc::unbounded_channel();
+        let mut live = StreamClient::new(Config::default(), live_sender)
+            .with_capture(&path)
+            .unwrap();
+        let mut updates = futures::stream::iter(vec![Ok(account.clone()), Ok(account)]);
+        let (mut subscribe_tx, _sent) = futures::channel::mpsc::unbounded();
+        live.consume_updates(&mut updates, &mut subscribe_tx)
+            .await
+            .unwrap();
+        drop(live);
+
+        let (replay_sender, mut replay_events) = mpsc::unbounded_channel();
+        let replay = StreamClient::new(Config::default(), replay_sender);
+        assert_eq!(replay.replay(&path).await.unwrap(), 2);
+        assert_eq!(replay.latest_slot(), 42);
+        std::fs::remove_file(&path).unwrap();
+
+        let mut live_output = Vec::new();
+        while let Ok(event) = live_events.try_recv() {
+            live_output.push(format!("{event:?}"));
+        }
+        let mut replay_output = Vec::new();
+        while let Ok(event) = replay_events.try_recv() {
+            replay_output.push(format!("{event:?}"));
+        }
+        assert_eq!(live_output.len(), 2);
+        assert_eq!(live_output, replay_output);
+    }
+}
@@ -0,0 +1,109 @@
+//! Rolling per-mint trade volume
+
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Window `volume_24h` is summed over
+pub const VOLUME_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// SOL traded per mint over a trailing window. Samples older than the window are dropped
+/// as new ones arrive and on `prune`
+#[derive(Debug, Clone)]
+pub struct VolumeTracker {
+    samples: HashMap<Pubkey, VecDeque<(Instant, u64)>>,
+    window: Duration,
+}
+
+impl VolumeTracker {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            samples: HashMap::new(),
+            window,
+        }
+    }
+
+    /// Count `sol_amount` lamports traded on `mint` at `now`
+    pub fn record(&mut self, mint: Pubkey, sol_amount: u64, now: Instant) {
+        let window = self.window;
+        let samples = self.samples.entry(mint).or_default();
+        samples.push_back((now, sol_amount));
+        Self::expire(samples, window, now);
+    }
+
+    /// Lamports traded on `mint` within the window ending at `now`
+    pub fn volume(&self, mint: &Pubkey, now: Instant) -> u64 {
+        self.samples.get(mint).map_or(0, |samples| {
+            samples
+                .iter()
+                .filter(|(at, _)| now.saturating_duration_since(*at) < self.window)
+                .map(|(_, amount)| amount)
+                .sum()
+        })
+    }
+
+    /// Drop expired samples and mints left without any; returns how many mints were dropped
+    pub fn prune(&mut self, now: Instant) -> usize {
+        let before = self.samples.len();
+        let window = self.window;
+        self.samples.retain(|_, samples| {
+            Self::expire(samples, window, now);
+            !samples.is_empty()
+        });
+        before - self.samples.len()
+    }
+
+    /// Mints with samples held
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    fn expire(samples: &mut VecDeque<(Instant, u64)>, window: Duration, now: Instant) {
+        while samples
+            .front()
+            .is_some_and(|(at, _)| now.saturating_duration_since(*at) >= window)
+        {
+            samples.pop_front();
+        }
+    }
+}
+
+impl Default for VolumeTracker {
+    fn default() -> Self {
+        Self::new(VOLUME_WINDOW)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume_rolls_over_window() {
+        let start = Instant::now();
+        let at = |hours: u64| start + Duration::from_secs(hours * 60 * 60);
+        let mut tracker = VolumeTracker::default();
+        let mint = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+
+        tracker.record(mint, 100, at(0));
+        tracker.record(mint, 50, at(12));
+        tracker.record(other, 7, at(1));
+        assert_eq!(tracker.volume(&mint, at(12)), 150);
+
+        // the first sample ages out at 24h
+        assert_eq!(tracker.volume(&mint, at(24)), 50);
+        tracker.record(mint, 25, at(30));
+        assert_eq!(tracker.volume(&mint, at(30)), 75);
+        assert_eq!(tracker.volume(&Pubkey::new_unique(), at(30)), 0);
+
+        assert_eq!(tracker.prune(at(30)), 1);
+        assert_eq!(tracker.len(), 1);
+        assert_eq!(tracker.prune(at(60)), 1);
+        assert!(tracker.is_empty());
+    }
+}
@@ -6,9 +6,31 @@ use std::str::FromStr;
 /// Pump.Fun program
 pub const PUMPFUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
 
+/// PumpSwap AMM program, where completed curves migrate
+pub const PUMPSWAP_PROGRAM_ID: &str = "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA";
+
 /// Discriminators
 pub const CREATE_DISCRIMINATOR: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
 pub const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+pub const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+/// Leading bytes of every bonding curve account
+pub const BONDING_CURVE_DISCRIMINATOR: [u8; 8] = [23, 183, 248, 55, 96, 216, 172, 96];
+
+/// Decimals of every pump token mint
+pub const TOKEN_DECIMALS: i32 = 6;
+
+/// Supply every pump token is minted with, in base units
+pub const TOKEN_TOTAL_SUPPLY: u64 = 1_000_000_000_000_000;
+
+/// Pump protocol fee used for quotes when the global account isn't at hand (1%)
+pub const DEFAULT_FEE_BASIS_POINTS: u64 = 100;
+
+/// Network base fee per signature in lamports
+pub const BASE_FEE_LAMPORTS: u64 = 5_000;
+
+/// Rent-exempt minimum for a 165-byte SPL token account in lamports
+pub const TOKEN_ACCOUNT_RENT_LAMPORTS: u64 = 2_039_280;
 
 /// Seeds for PDA derivation
 pub mod seeds {
@@ -17,6 +39,13 @@ pub mod seeds {
     pub const BONDING_CURVE_SEED: &[u8] = b"bonding-curve";
     pub const METADATA_SEED: &[u8] = b"metadata";
     pub const CREATOR_VAULT_SEED: &[u8] = b"creator-vault";
+    pub const POOL_AUTHORITY_SEED: &[u8] = b"pool-authority";
+
+    /// PumpSwap AMM seeds
+    pub const POOL_SEED: &[u8] = b"pool";
+    pub const AMM_GLOBAL_CONFIG_SEED: &[u8] = b"global_config";
+    pub const AMM_EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+    pub const AMM_CREATOR_VAULT_SEED: &[u8] = b"creator_vault";
 }
 
 /// Program addresses
@@ -27,6 +56,29 @@ pub mod accounts {
         Pubkey::from_str(PUMPFUN_PROGRAM_ID).unwrap()
     }
 
+    /// Primary pump protocol fee recipient
+    pub fn fee_recipient() -> Pubkey {
+        Pubkey::from_str("CebN5WGQ4jvEPvsVU4EoHEpgzq1VV7AbicfhtW4xC9iM").unwrap()
+    }
+
+    pub fn pumpswap_program_id() -> Pubkey {
+        Pubkey::from_str(PUMPSWAP_PROGRAM_ID).unwrap()
+    }
+
+    /// Pyth SOL/USD price feed account (pull oracle, shard 0), kept current by Pyth
+    pub fn pyth_sol_usd_price() -> Pubkey {
+        Pubkey::from_str("7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE").unwrap()
+    }
+
+    /// Pyth Solana receiver, the owner of price feed accounts
+    pub fn pyth_receiver_program_id() -> Pubkey {
+        Pubkey::from_str("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ").unwrap()
+    }
+
+    pub fn wsol_mint() -> Pubkey {
+        Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap()
+    }
+
     pub fn mpl_token_metadata() -> Pubkey {
         Pubkey::from_str("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s").unwrap()
     }
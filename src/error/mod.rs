@@ -3,7 +3,7 @@
 use thiserror::Error;
 
 /// Custom err
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone)]
 pub enum SniperError {
     #[error("gRPC connection failed: {0}")]
     GrpcConnectionFailed(String),
@@ -17,6 +17,9 @@ pub enum SniperError {
     #[error("Insufficient funds")]
     InsufficientFunds,
 
+    #[error("Insufficient liquidity: {0}")]
+    InsufficientLiquidity(String),
+
     #[error("Transaction failed: {0}")]
     TransactionFailed(String),
 
@@ -26,6 +29,9 @@ pub enum SniperError {
     #[error("Serialization error: {0}")]
     SerializationError(String),
 
+    #[error("I/O error: {0}")]
+    IoError(String),
+
     #[error("Invalid configuration: {0}")]
     InvalidConfig(String),
 
@@ -37,4 +43,26 @@ pub enum SniperError {
 
     #[error("Slippage exceeded")]
     SlippageExceeded,
+
+    /// Rejected by the node before it was forwarded, so it never reached a leader
+    #[error("Blockhash not found")]
+    BlockhashNotFound,
+}
+
+impl SniperError {
+    /// Whether the same request can safely be sent again: an expired blockhash, or a read
+    /// that failed before anything was sent. A failed or unanswered send may still land,
+    /// and a revert or rejected fee recipient would fail the same way again
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::BlockhashNotFound | Self::RpcError(_))
+    }
+
+    /// Classify an error returned by a transaction send
+    pub fn from_send_error(message: String) -> Self {
+        if message.contains("Blockhash not found") {
+            Self::BlockhashNotFound
+        } else {
+            Self::TransactionFailed(message)
+        }
+    }
 }
@@ -3,7 +3,7 @@
 use crate::{
     constants::{accounts, BUY_DISCRIMINATOR},
     error::SniperError,
-    utils::pda::{derive_bonding_curve_pda, derive_global_pda},
+    utils::pda::{derive_bonding_curve_pda, derive_creator_vault_pda, derive_global_pda},
 };
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_sdk::{
@@ -78,14 +78,3 @@ impl BuyInstruction {
         Ok(instruction)
     }
 }
-
-fn derive_creator_vault_pda(creator: &Pubkey) -> Result<Pubkey, SniperError> {
-    use crate::constants::seeds::CREATOR_VAULT_SEED;
-
-    let (creator_vault, _bump) = Pubkey::find_program_address(
-        &[CREATOR_VAULT_SEED, creator.as_ref()],
-        &accounts::pumpfun_program_id(),
-    );
-
-    Ok(creator_vault)
-}
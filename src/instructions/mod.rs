@@ -2,8 +2,10 @@
 
 pub mod buy;
 pub mod create;
+pub mod pump_swap;
 pub mod sell;
 
 pub use buy::*;
 pub use create::*;
+pub use pump_swap::*;
 pub use sell::*;
@@ -0,0 +1,104 @@
+//! PumpSwap AMM buy instruction
+
+use crate::{
+    accounts::PumpSwapPool,
+    constants::{accounts, BUY_DISCRIMINATOR},
+    error::SniperError,
+    utils::pda::{
+        derive_amm_creator_vault_authority_pda, derive_amm_event_authority_pda,
+        derive_amm_global_config_pda,
+    },
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+/// Buy an exact base amount paying at most `max_quote_amount_in` WSOL
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct PumpSwapBuyInstruction {
+    pub base_amount_out: u64,
+    pub max_quote_amount_in: u64,
+}
+
+impl PumpSwapBuyInstruction {
+    pub const fn discriminator() -> [u8; 8] {
+        BUY_DISCRIMINATOR
+    }
+
+    pub fn data(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(24);
+        data.extend_from_slice(&Self::discriminator());
+        self.serialize(&mut data).unwrap();
+        data
+    }
+
+    pub fn create_instruction(
+        &self,
+        payer: &Keypair,
+        pool_address: &Pubkey,
+        pool: &PumpSwapPool,
+        protocol_fee_recipient: &Pubkey,
+    ) -> Result<Instruction, SniperError> {
+        let global_config = derive_amm_global_config_pda()?;
+        let event_authority = derive_amm_event_authority_pda()?;
+        let creator_vault_authority = derive_amm_creator_vault_authority_pda(&pool.coin_creator)?;
+
+        let instruction = Instruction::new_with_bytes(
+            accounts::pumpswap_program_id(),
+            &self.data(),
+            vec![
+                // Pool
+                AccountMeta::new_readonly(*pool_address, false),
+                // Payer
+                AccountMeta::new(payer.pubkey(), true),
+                // Global config PDA
+                AccountMeta::new_readonly(global_config, false),
+                // Base (token) and quote (WSOL) mints
+                AccountMeta::new_readonly(pool.base_mint, false),
+                AccountMeta::new_readonly(pool.quote_mint, false),
+                // User's base and quote token accounts
+                AccountMeta::new(
+                    get_associated_token_address(&payer.pubkey(), &pool.base_mint),
+                    false,
+                ),
+                AccountMeta::new(
+                    get_associated_token_address(&payer.pubkey(), &pool.quote_mint),
+                    false,
+                ),
+                // Pool reserves
+                AccountMeta::new(pool.pool_base_token_account, false),
+                AccountMeta::new(pool.pool_quote_token_account, false),
+                // Protocol fee recipient and its WSOL account
+                AccountMeta::new_readonly(*protocol_fee_recipient, false),
+                AccountMeta::new(
+                    get_associated_token_address(protocol_fee_recipient, &pool.quote_mint),
+                    false,
+                ),
+                // Base and quote token programs
+                AccountMeta::new_readonly(accounts::token_program(), false),
+                AccountMeta::new_readonly(accounts::token_program(), false),
+                // System program
+                AccountMeta::new_readonly(accounts::system_program(), false),
+                // Associated token program
+                AccountMeta::new_readonly(accounts::associated_token_program(), false),
+                // Event authority
+                AccountMeta::new_readonly(event_authority, false),
+                // PumpSwap program
+                AccountMeta::new_readonly(accounts::pumpswap_program_id(), false),
+                // Coin creator vault and its authority
+                AccountMeta::new(
+                    get_associated_token_address(&creator_vault_authority, &pool.quote_mint),
+                    false,
+                ),
+                AccountMeta::new_readonly(creator_vault_authority, false),
+            ],
+        );
+
+        Ok(instruction)
+    }
+}
@@ -1,6 +1,18 @@
 //! Sell token instruction
 
+use crate::{
+    constants::{accounts, SELL_DISCRIMINATOR},
+    error::SniperError,
+    utils::pda::{derive_bonding_curve_pda, derive_creator_vault_pda, derive_global_pda},
+};
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
 
 /// Sell token instruction data
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
@@ -10,11 +22,62 @@ pub struct SellInstruction {
 }
 
 impl SellInstruction {
+    /// Get instruction discriminator
+    pub const fn discriminator() -> [u8; 8] {
+        SELL_DISCRIMINATOR
+    }
+
     /// Serialize instruction data with discriminator
     pub fn data(&self) -> Vec<u8> {
         let mut data = Vec::with_capacity(256);
-        // TODO: Add sell discriminator when needed
+        data.extend_from_slice(&Self::discriminator());
         self.serialize(&mut data).unwrap();
         data
     }
+
+    pub fn create_instruction(
+        &self,
+        payer: &Keypair,
+        mint: &Pubkey,
+        fee_recipient: &Pubkey,
+        creator: &Pubkey,
+    ) -> Result<Instruction, SniperError> {
+        // derive PDAs
+        let bonding_curve = derive_bonding_curve_pda(mint)?;
+        let global_pda = derive_global_pda()?;
+        let creator_vault = derive_creator_vault_pda(creator)?;
+
+        let instruction = Instruction::new_with_bytes(
+            accounts::pumpfun_program_id(),
+            &self.data(),
+            vec![
+                // Global config PDA
+                AccountMeta::new_readonly(global_pda, false),
+                // Fee recipient
+                AccountMeta::new(*fee_recipient, false),
+                // Token mint
+                AccountMeta::new_readonly(*mint, false),
+                // Bonding curve
+                AccountMeta::new(bonding_curve, false),
+                // Bonding curve token account
+                AccountMeta::new(get_associated_token_address(&bonding_curve, mint), false),
+                // User's token account
+                AccountMeta::new(get_associated_token_address(&payer.pubkey(), mint), false),
+                // Payer
+                AccountMeta::new(payer.pubkey(), true),
+                // System program
+                AccountMeta::new_readonly(accounts::system_program(), false),
+                // Creator vault
+                AccountMeta::new(creator_vault, false),
+                // Token program
+                AccountMeta::new_readonly(accounts::token_program(), false),
+                // Event authority
+                AccountMeta::new_readonly(accounts::event_authority(), false),
+                // Pump.fun program
+                AccountMeta::new_readonly(accounts::pumpfun_program_id(), false),
+            ],
+        );
+
+        Ok(instruction)
+    }
 }
@@ -8,57 +8,628 @@ pub mod instructions;
 pub mod utils;
 
 pub use accounts::{BondingCurveAccount, TokenInfo};
-pub use common::{Config, MarketData, SniperEvent};
+pub use common::{
+    BuySource, Config, EventEnvelope, MarketData, PositionReport, SellReason, SniperEvent,
+    SniperStats,
+};
 pub use error::SniperError;
 
 use anyhow::Result;
-use common::{Config as StreamConfig, StreamClient};
-use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
-use std::collections::{HashMap, HashSet};
-use tokio::sync::mpsc;
-use tracing::{error, info};
-use utils::PriceFetcher;
+use common::{
+    spawn_admin_listener, spawn_metrics_server, AdaptiveThreshold, AdminCommand, AdminRequest,
+    Config as StreamConfig, CreatorReputation, CurveCache, Heartbeat, LaunchOutcome,
+    MetricsRequest, MetricsSnapshot, NameFilter, Position, ReplayClient, ScoringStrategy,
+    ShadowReport, StreamClient, StreamSource, TokenSignals, VolumeTracker,
+};
+use futures::FutureExt;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc,
+};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{broadcast, mpsc, Notify};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+use utils::{BuyOutcome, MetadataFetcher, PriceFetcher, RugChecker, RugSignals, SwapEvent};
+
+/// How often the stream slot is compared against the RPC slot
+const SLOT_LAG_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often the heartbeat is checked for being due
+const HEARTBEAT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Events buffered per subscriber before a slow one starts missing them
+const EVENT_BROADCAST_CAPACITY: usize = 1_024;
+
+/// How often the global account is re-read for fee changes
+const CURVE_FEES_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often changed creator reputation is written to disk
+const REPUTATION_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Most recent creations remembered for dropping redelivered create transactions
+const SEEN_MINTS_CAPACITY: usize = 10_000;
+
+/// Window `daily_spend_cap_sol` applies over
+const DAILY_SPEND_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Wait before the first buy resend, growing linearly with each further attempt
+const BUY_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Longest a stopping sniper waits for concurrent buys and sells already sent to report back
+const STOP_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Least time between balance lookups for a position whose fill isn't known yet, so
+/// busy curves don't cost an RPC round-trip per update
+const FILL_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a sent sell is polled for before its position is handed back for another try
+const SELL_CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Longest each safety score fetch may take before its signal counts as unknown
+const RUG_SIGNALS_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A token seen more recently than this is skipped by the RPC fallback: its create
+/// transaction hasn't reached the RPC node yet, so the fetch can only miss
+const RPC_FALLBACK_MIN_AGE_SECS: u64 = 1;
+
+/// Stops a running sniper from outside, e.g. while `Sniper::start` holds it
+#[derive(Clone)]
+pub struct StopHandle {
+    running: Arc<AtomicBool>,
+    shutdown: Arc<Notify>,
+}
+
+impl StopHandle {
+    /// Ask the event loop to finish; `start` then returns `Ok(())` once buys and sells
+    /// already sent have reported and the stream task is cancelled
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+        self.shutdown.notify_one();
+    }
+
+    /// Whether `start` is processing events
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+}
+
+/// A buy sent to the chain, with what the event loop needs to account for it
+struct BuyAttempt {
+    token_info: TokenInfo,
+    buy_amount: u64,
+    market_cap: u64,
+    expected_tokens: Option<u64>,
+    result: Result<BuyOutcome, SniperError>,
+    /// Token balance growth once confirmed, None if unconfirmed or the lookup failed
+    tokens_received: Option<u64>,
+    retry_count: u32,
+    /// Time spent sending, retries included
+    elapsed: Duration,
+}
+
+impl BuyAttempt {
+    /// Send a buy, resending after transient failures up to `max_buy_retries` times,
+    /// and read back the fill. Needs nothing from the sniper, so it can run on its own task
+    async fn send(
+        executor: utils::TransactionExecutor,
+        wallet: Arc<Keypair>,
+        config: StreamConfig,
+        token_info: TokenInfo,
+        buy_amount: u64,
+        market_cap: u64,
+        expected_tokens: Option<u64>,
+    ) -> Self {
+        let owner = wallet.pubkey();
+        let balance_before = if config.verify_buy_balance {
+            executor
+                .fetch_token_balance(&owner, &token_info.mint)
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or_default()
+        } else {
+            0
+        };
+
+        let started = Instant::now();
+        let mut retry_count = 0;
+        let result = loop {
+            match executor.execute_buy(&wallet, &token_info, buy_amount).await {
+                Err(e) if e.is_transient() && retry_count < config.max_buy_retries => {
+                    retry_count += 1;
+                    warn!(
+                        "Buy attempt {} for {} failed, retrying: {}",
+                        retry_count,
+                        token_info.display_name(),
+                        e
+                    );
+                    tokio::time::sleep(BUY_RETRY_BACKOFF * retry_count).await;
+                }
+                result => break result,
+            }
+        };
+        let elapsed = started.elapsed();
+
+        let tokens_received = match &result {
+            Ok(outcome) if outcome.confirmed => {
+                match executor.fetch_token_balance(&owner, &token_info.mint).await {
+                    Ok(balance) => Some(balance.unwrap_or_default().saturating_sub(balance_before)),
+                    Err(e) => {
+                        warn!(
+                            "Balance check failed for {}: {}",
+                            token_info.display_name(),
+                            e
+                        );
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        Self {
+            token_info,
+            buy_amount,
+            market_cap,
+            expected_tokens,
+            result,
+            tokens_received,
+            retry_count,
+            elapsed,
+        }
+    }
+
+    /// `send` on the blocking pool, since the executor's RPC calls block the thread
+    async fn send_blocking(
+        executor: utils::TransactionExecutor,
+        wallet: Arc<Keypair>,
+        config: StreamConfig,
+        token_info: TokenInfo,
+        buy_amount: u64,
+        market_cap: u64,
+        expected_tokens: Option<u64>,
+    ) -> Self {
+        let runtime = tokio::runtime::Handle::current();
+        let attempt = Self::send(
+            executor,
+            wallet,
+            config,
+            token_info.clone(),
+            buy_amount,
+            market_cap,
+            expected_tokens,
+        );
+        match tokio::task::spawn_blocking(move || runtime.block_on(attempt)).await {
+            Ok(attempt) => attempt,
+            Err(e) => Self {
+                token_info,
+                buy_amount,
+                market_cap,
+                expected_tokens,
+                result: Err(SniperError::TransactionFailed(format!(
+                    "Buy task failed: {}",
+                    e
+                ))),
+                tokens_received: None,
+                retry_count: 0,
+                elapsed: Duration::ZERO,
+            },
+        }
+    }
+}
+
+/// A sell sent on its own task, so the event loop keeps serving other positions while it
+/// confirms
+struct SellAttempt {
+    token_info: TokenInfo,
+    expected_sol_output: u64,
+    reason: SellReason,
+    /// Signature of the confirmed sell, None while the tokens are still held
+    confirmed: Option<Signature>,
+}
+
+impl SellAttempt {
+    /// Send a sell and poll it for up to `SELL_CONFIRM_TIMEOUT`
+    async fn send(
+        executor: utils::TransactionExecutor,
+        wallet: Arc<Keypair>,
+        token_info: TokenInfo,
+        token_amount: u64,
+        expected_sol_output: u64,
+        reason: SellReason,
+    ) -> Self {
+        let confirmed = match executor
+            .execute_sell(&wallet, &token_info, token_amount)
+            .await
+        {
+            Ok(signature) => match executor
+                .confirm_signature(&signature, SELL_CONFIRM_TIMEOUT)
+                .await
+            {
+                Ok(Some(_)) => Some(signature),
+                Ok(None) => {
+                    warn!(
+                        "Sell {} for {} not confirmed within {}s, keeping the position",
+                        signature,
+                        token_info.display_name(),
+                        SELL_CONFIRM_TIMEOUT.as_secs()
+                    );
+                    None
+                }
+                Err(e) => {
+                    error!("Sell failed for {}: {}", token_info.display_name(), e);
+                    None
+                }
+            },
+            Err(e) => {
+                error!("Sell failed for {}: {}", token_info.display_name(), e);
+                None
+            }
+        };
+
+        Self {
+            token_info,
+            expected_sol_output,
+            reason,
+            confirmed,
+        }
+    }
+
+    /// `send` on the blocking pool, since the executor's RPC calls block the thread
+    async fn send_blocking(
+        executor: utils::TransactionExecutor,
+        wallet: Arc<Keypair>,
+        token_info: TokenInfo,
+        token_amount: u64,
+        expected_sol_output: u64,
+        reason: SellReason,
+    ) -> Self {
+        let runtime = tokio::runtime::Handle::current();
+        let attempt = Self::send(
+            executor,
+            wallet,
+            token_info.clone(),
+            token_amount,
+            expected_sol_output,
+            reason,
+        );
+        match tokio::task::spawn_blocking(move || runtime.block_on(attempt)).await {
+            Ok(attempt) => attempt,
+            Err(e) => {
+                error!("Sell task failed for {}: {}", token_info.display_name(), e);
+                Self {
+                    token_info,
+                    expected_sol_output,
+                    reason,
+                    confirmed: None,
+                }
+            }
+        }
+    }
+}
+
+/// What a dispatched buy holds against the limits until `finish_buy` accounts for it
+#[derive(Debug, Clone, Copy)]
+struct BuyReservation {
+    /// Estimated lamports counted against the spend caps
+    cost: u64,
+    /// Lamports counted against the exposure cap
+    amount: u64,
+    /// Dispatch time, standing in for the buy in the cooldown and the creator's window
+    dispatched_at: Instant,
+    /// `last_buy_at` before the dispatch, restored if the buy fails
+    previous_buy_at: Option<Instant>,
+}
 
 pub struct Sniper {
     config: StreamConfig,
     tracked_tokens: HashMap<String, TokenInfo>,
+    /// Mint of each tracked token by its bonding curve
+    bonding_curve_to_mint: HashMap<Pubkey, String>,
     bought_tokens: HashSet<String>,
-    bonding_curve_cache: HashMap<Pubkey, BondingCurveAccount>,
+    /// Mints with a `BuyTriggered` queued but not yet handled
+    in_flight: HashSet<String>,
+    positions: HashMap<String, Position>,
+    /// Worst drawdown among positions closed this session, basis points
+    closed_max_drawdown_bps: u64,
+    bonding_curve_cache: CurveCache,
+    /// SOL traded per tracked or held mint over the last 24h
+    volume: VolumeTracker,
+    /// SOL-adding curve updates seen per tracked bonding curve
+    buy_counts: HashMap<Pubkey, u32>,
+    strategy: Option<ScoringStrategy>,
+    /// Compiled `name_include_regex`/`name_exclude_regex`
+    name_filter: NameFilter,
+    /// Moves `market_cap_threshold_usd` by realized win rate when enabled
+    adaptive: Option<AdaptiveThreshold>,
+    /// Named configs from `strategies_file`
+    strategies: HashMap<String, StreamConfig>,
+    active_strategy: Option<String>,
+    /// Highest real SOL reserves seen per tracked bonding curve
+    peak_sol_reserves: HashMap<Pubkey, u64>,
+    reputation: CreatorReputation,
     event_receiver: mpsc::UnboundedReceiver<SniperEvent>,
     event_sender: mpsc::UnboundedSender<SniperEvent>,
+    admin_receiver: mpsc::UnboundedReceiver<AdminRequest>,
+    admin_sender: mpsc::UnboundedSender<AdminRequest>,
+    admin_task: Option<JoinHandle<()>>,
+    metrics_receiver: mpsc::UnboundedReceiver<MetricsRequest>,
+    metrics_sender: mpsc::UnboundedSender<MetricsRequest>,
+    metrics_task: Option<JoinHandle<()>>,
+    /// Set by the `pause` admin command
+    buys_paused: bool,
     transaction_executor: utils::TransactionExecutor,
     price_fetcher: PriceFetcher,
-    wallet: Option<Keypair>,
+    metadata_fetcher: MetadataFetcher,
+    wallet: Option<Arc<Keypair>>,
+    /// Buys sent on their own tasks report back here in concurrent mode
+    buy_result_sender: mpsc::UnboundedSender<BuyAttempt>,
+    buy_result_receiver: mpsc::UnboundedReceiver<BuyAttempt>,
+    /// Concurrent buys sent but not yet reported
+    in_flight_buys: usize,
+    /// Sells run on their own tasks and report back here
+    sell_result_sender: mpsc::UnboundedSender<SellAttempt>,
+    sell_result_receiver: mpsc::UnboundedReceiver<SellAttempt>,
+    /// Sells sent but not yet reported
+    in_flight_sells: usize,
     test_mode_single_buy: bool,
+    dry_run: bool,
     has_bought_once: bool,
+    shadow_report: ShadowReport,
+    stream_slot: Arc<AtomicU64>,
+    slot_lag: Option<u64>,
+    /// Signalled to stop the event loop and the stream task
+    shutdown: Arc<Notify>,
+    /// Set while `start` is processing events
+    running: Arc<AtomicBool>,
+    started_at: Instant,
+    heartbeat: Option<Heartbeat>,
+    /// Emits `StatsUpdate` on its interval
+    stats_timer: Option<Heartbeat>,
+    /// Mirrors stats updates to StatsD
+    #[cfg(feature = "statsd")]
+    statsd: Option<common::StatsdEmitter>,
+    /// Posts critical events to `webhook_url`
+    webhook: Option<utils::WebhookNotifier>,
+    successful_buys: usize,
+    failed_buys: usize,
+    /// Lamports committed to buys this run, fees included
+    total_spent: u64,
+    /// Spends within the daily window, oldest first. Not persisted
+    recent_spends: VecDeque<(Instant, u64)>,
+    last_buy_at: Option<Instant>,
+    /// Limits held by buys sent but not yet accounted for, by mint
+    reserved_buys: HashMap<String, BuyReservation>,
+    /// Recent buy times per creator, for the per-creator rate limit
+    creator_buys: HashMap<Pubkey, VecDeque<Instant>>,
+    /// Copy of every handled event for `subscribe` receivers
+    event_broadcast: broadcast::Sender<EventEnvelope>,
+    /// `seq` of the next handled event
+    next_event_seq: u64,
+    /// Mints already handled, oldest first in `seen_mint_order`
+    seen_mints: HashSet<Pubkey>,
+    seen_mint_order: VecDeque<Pubkey>,
+    /// Shared with the metrics server, which answers `/health` from it
+    stream_connected: Arc<AtomicBool>,
+    /// Times the stream has reported connected, the first connect included
+    stream_connects: u64,
 }
 
 impl Sniper {
     pub async fn new(config: StreamConfig) -> Result<Self, SniperError> {
+        let transaction_executor = utils::TransactionExecutor::new(config.clone());
+        Self::with_executor(config, transaction_executor).await
+    }
+
+    /// Sniper making its RPC calls through `rpc_client`, shared with the caller
+    pub async fn with_rpc_client(
+        config: StreamConfig,
+        rpc_client: Arc<RpcClient>,
+    ) -> Result<Self, SniperError> {
+        let transaction_executor =
+            utils::TransactionExecutor::with_rpc_client(rpc_client, config.clone());
+        Self::with_executor(config, transaction_executor).await
+    }
+
+    async fn with_executor(
+        mut config: StreamConfig,
+        transaction_executor: utils::TransactionExecutor,
+    ) -> Result<Self, SniperError> {
         config.validate()?;
 
         let (event_sender, event_receiver) = mpsc::unbounded_channel();
-        let transaction_executor = utils::TransactionExecutor::new(config.clone());
-        let price_fetcher = PriceFetcher::new();
+        let (admin_sender, admin_receiver) = mpsc::unbounded_channel();
+        let (metrics_sender, metrics_receiver) = mpsc::unbounded_channel();
+        let (buy_result_sender, buy_result_receiver) = mpsc::unbounded_channel();
+        let (sell_result_sender, sell_result_receiver) = mpsc::unbounded_channel();
+        let price_fetcher = PriceFetcher::from_source_name(
+            &config.sol_price_source,
+            transaction_executor.rpc_client(),
+            config.coingecko_api_key.as_deref(),
+            Duration::from_millis(config.price_timeout_ms),
+            Duration::from_secs(config.price_cache_secs),
+        )
+        .map_err(|e| SniperError::InvalidConfig(e.to_string()))?;
+        let metadata_fetcher = MetadataFetcher::new(utils::shared_http_client())
+            .with_ipfs_gateway(config.ipfs_gateway.clone());
+        let shadow_report = ShadowReport::new(config.shadow_thresholds_usd.clone());
+        let bonding_curve_cache = CurveCache::new(
+            (config.cache_ttl_secs > 0).then(|| Duration::from_secs(config.cache_ttl_secs)),
+            config.max_cached_curves,
+        );
+        let adaptive = AdaptiveThreshold::from_config(&config);
+        if let Some(adaptive) = &adaptive {
+            config.market_cap_threshold_usd = adaptive.threshold_usd();
+        }
+        let strategy = ScoringStrategy::from_config(&config);
+        let name_filter = NameFilter::from_config(&config)?;
+        let strategies = config.load_strategies()?;
+        let reputation =
+            CreatorReputation::load(config.creator_reputation_path.as_deref().map(Path::new))?;
+        let heartbeat = (config.heartbeat_interval_seconds > 0).then(|| {
+            Heartbeat::new(
+                Duration::from_secs(config.heartbeat_interval_seconds),
+                Instant::now(),
+            )
+        });
+        let stats_timer = (config.stats_interval_seconds > 0).then(|| {
+            Heartbeat::new(
+                Duration::from_secs(config.stats_interval_seconds),
+                Instant::now(),
+            )
+        });
+        #[cfg(feature = "statsd")]
+        let statsd = match &config.statsd_addr {
+            Some(address) => Some(common::StatsdEmitter::new(address).map_err(|e| {
+                SniperError::InvalidConfig(format!("Invalid StatsD address {}: {}", address, e))
+            })?),
+            None => None,
+        };
+        #[cfg(not(feature = "statsd"))]
+        if config.statsd_addr.is_some() {
+            warn!("STATSD_ADDR is set but this build has no statsd feature; not sending metrics");
+        }
+
+        let webhook = config
+            .webhook_url
+            .as_ref()
+            .map(|url| utils::WebhookNotifier::new(utils::shared_http_client(), url.clone()));
 
-        Ok(Self {
+        let initial_strategy = config.active_strategy.clone();
+        let mut sniper = Self {
             config,
             tracked_tokens: HashMap::new(),
+            bonding_curve_to_mint: HashMap::new(),
             bought_tokens: HashSet::new(),
-            bonding_curve_cache: HashMap::new(),
+            in_flight: HashSet::new(),
+            positions: HashMap::new(),
+            closed_max_drawdown_bps: 0,
+            bonding_curve_cache,
+            volume: VolumeTracker::default(),
+            buy_counts: HashMap::new(),
+            strategy,
+            name_filter,
+            adaptive,
+            strategies,
+            active_strategy: None,
+            peak_sol_reserves: HashMap::new(),
+            reputation,
             event_receiver,
             event_sender,
+            admin_receiver,
+            admin_sender,
+            admin_task: None,
+            metrics_receiver,
+            metrics_sender,
+            metrics_task: None,
+            buys_paused: false,
             transaction_executor,
             price_fetcher,
+            metadata_fetcher,
             wallet: None,
+            buy_result_sender,
+            buy_result_receiver,
+            in_flight_buys: 0,
+            sell_result_sender,
+            sell_result_receiver,
+            in_flight_sells: 0,
             test_mode_single_buy: false,
+            dry_run: false,
             has_bought_once: false,
-        })
+            shadow_report,
+            stream_slot: Arc::new(AtomicU64::new(0)),
+            slot_lag: None,
+            shutdown: Arc::new(Notify::new()),
+            running: Arc::new(AtomicBool::new(false)),
+            started_at: Instant::now(),
+            heartbeat,
+            stats_timer,
+            #[cfg(feature = "statsd")]
+            statsd,
+            webhook,
+            successful_buys: 0,
+            failed_buys: 0,
+            total_spent: 0,
+            recent_spends: VecDeque::new(),
+            last_buy_at: None,
+            reserved_buys: HashMap::new(),
+            creator_buys: HashMap::new(),
+            event_broadcast: broadcast::channel(EVENT_BROADCAST_CAPACITY).0,
+            next_event_seq: 0,
+            seen_mints: HashSet::new(),
+            seen_mint_order: VecDeque::new(),
+            stream_connected: Arc::new(AtomicBool::new(false)),
+            stream_connects: 0,
+        };
+
+        if let Some(name) = initial_strategy {
+            sniper.set_active_strategy(&name)?;
+        }
+        Ok(sniper)
+    }
+
+    /// Switch to a named strategy; later buy decisions and transactions use its config.
+    /// Connection, stream and timer settings keep their startup values
+    pub fn set_active_strategy(&mut self, name: &str) -> Result<(), SniperError> {
+        let mut config =
+            self.strategies.get(name).cloned().ok_or_else(|| {
+                SniperError::InvalidConfig(format!("Unknown strategy {:?}", name))
+            })?;
+        config.validate()?;
+
+        self.adaptive = AdaptiveThreshold::from_config(&config);
+        if let Some(adaptive) = &self.adaptive {
+            config.market_cap_threshold_usd = adaptive.threshold_usd();
+        }
+        self.strategy = ScoringStrategy::from_config(&config);
+        self.name_filter = NameFilter::from_config(&config)?;
+        self.transaction_executor.set_config(config.clone());
+        self.config = config;
+        self.active_strategy = Some(name.to_string());
+        info!("Active strategy: {}", name);
+        Ok(())
+    }
+
+    pub fn active_strategy(&self) -> Option<&str> {
+        self.active_strategy.as_deref()
+    }
+
+    /// Client for the primary RPC endpoint, to share instead of opening another
+    pub fn rpc_client(&self) -> Arc<RpcClient> {
+        self.transaction_executor.rpc_client()
     }
 
     pub fn set_wallet(&mut self, wallet: Keypair) {
         info!("Wallet configured: {}", wallet.pubkey());
-        self.wallet = Some(wallet);
+        self.wallet = Some(Arc::new(wallet));
+    }
+
+    /// Handle that can stop this sniper while it runs
+    pub fn stop_handle(&self) -> StopHandle {
+        StopHandle {
+            running: self.running.clone(),
+            shutdown: self.shutdown.clone(),
+        }
+    }
+
+    /// Stop the event loop, see `StopHandle::stop`
+    pub fn stop(&self) {
+        self.stop_handle().stop();
+    }
+
+    /// Whether `start` is processing events, see `StopHandle::is_running`
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
     }
 
     pub fn enable_test_mode(&mut self) {
@@ -66,12 +637,30 @@ impl Sniper {
         self.test_mode_single_buy = true;
     }
 
+    /// Receiver seeing every event the sniper handles. A subscriber that falls more than
+    /// `EVENT_BROADCAST_CAPACITY` events behind misses the oldest ones; the sniper never
+    /// waits for it. `common::recv_event` skips the gap with a warning
+    pub fn subscribe(&self) -> broadcast::Receiver<EventEnvelope> {
+        self.event_broadcast.subscribe()
+    }
+
+    pub fn enable_dry_run(&mut self) {
+        info!("DRY RUN ENABLED: Buys will be simulated, never sent");
+        self.dry_run = true;
+    }
+
     pub async fn start(&mut self) -> Result<(), SniperError> {
         info!("Starting Pump Sniper Bot");
-        info!(
-            "Market cap threshold: ${:.2} USD",
-            self.config.market_cap_threshold_usd_display()
-        );
+        match self.config.market_cap_threshold_sol {
+            Some(threshold_sol) => info!(
+                "Market cap threshold: {:.2} SOL",
+                threshold_sol as f64 / 1e9
+            ),
+            None => info!(
+                "Market cap threshold: ${:.2} USD",
+                self.config.market_cap_threshold_usd_display()
+            ),
+        }
         info!(
             "Buy amount: {:.2} SOL",
             self.config.buy_amount_sol_display()
@@ -81,7 +670,18 @@ impl Sniper {
             self.config.priority_fee_sol_display()
         );
 
-        if self.wallet.is_none() {
+        if self.config.replay_path.is_some() && !self.shadow_report.is_enabled() && !self.dry_run {
+            // a capture is history, so its buys are only ever simulated
+            self.enable_dry_run();
+        }
+        if self.shadow_report.is_enabled() {
+            info!(
+                "SHADOW MODE: recording threshold hits, no buys ({})",
+                self.shadow_report.summary()
+            );
+        } else if self.dry_run {
+            info!("DRY RUN: simulating buys, no transactions will be sent");
+        } else if self.wallet.is_none() {
             return Err(SniperError::InvalidConfig(
                 "No wallet configured".to_string(),
             ));
@@ -91,27 +691,355 @@ impl Sniper {
             error!("Failed to fetch initial SOL price: {}", e);
         }
 
-        let mut stream_client = StreamClient::new(self.config.clone(), self.event_sender.clone());
+        let mut stream_client: Box<dyn StreamSource> = match &self.config.replay_path {
+            Some(path) => Box::new(
+                ReplayClient::new(
+                    self.config.clone(),
+                    path.clone(),
+                    self.config.replay_speed,
+                    self.event_sender.clone(),
+                )
+                .with_slot_tracker(self.stream_slot.clone()),
+            ),
+            None => Box::new(
+                StreamClient::new(self.config.clone(), self.event_sender.clone())
+                    .with_slot_tracker(self.stream_slot.clone()),
+            ),
+        };
 
-        tokio::spawn(async move {
+        // a stop from before this run must not end it straight away
+        let _ = self.shutdown.notified().now_or_never();
+        self.running.store(true, Ordering::SeqCst);
+        let stream_task = tokio::spawn(async move {
             if let Err(e) = stream_client.start().await {
                 error!("gRPC streaming failed: {}", e);
             }
         });
 
-        self.process_events().await
+        if let Some(address) = self.config.admin_socket.clone() {
+            self.start_admin_listener(&address).await?;
+        }
+        if let Some(port) = self.config.metrics_port {
+            self.start_metrics_server(port).await?;
+        }
+
+        let result = self.process_events().await;
+        stream_task.abort();
+        if let Some(admin_task) = self.admin_task.take() {
+            admin_task.abort();
+        }
+        if let Some(metrics_task) = self.metrics_task.take() {
+            metrics_task.abort();
+        }
+        self.running.store(false, Ordering::SeqCst);
+        let stats = self.stats();
+        info!(
+            "Sniper stopped: {} successful buys | {} failed | max drawdown {:.2}%",
+            stats.successful_buys,
+            stats.failed_buys,
+            stats.max_drawdown_bps as f64 / 100.0
+        );
+        result
     }
 
     async fn process_events(&mut self) -> Result<(), SniperError> {
-        while let Some(event) = self.event_receiver.recv().await {
-            if let Err(e) = self.handle_event(event).await {
-                error!("Error handling event: {}", e);
+        let mut slot_lag_interval = tokio::time::interval(SLOT_LAG_CHECK_INTERVAL);
+        let mut heartbeat_interval = tokio::time::interval(HEARTBEAT_POLL_INTERVAL);
+        let mut fees_interval = tokio::time::interval(CURVE_FEES_REFRESH_INTERVAL);
+        let mut reputation_interval = tokio::time::interval(REPUTATION_SAVE_INTERVAL);
+        let shutdown = self.shutdown.clone();
+
+        loop {
+            tokio::select! {
+                _ = shutdown.notified() => break,
+                event = self.event_receiver.recv() => {
+                    let Some(event) = event else { break };
+                    if let Err(e) = self.handle_event(event).await {
+                        error!("Error handling event: {}", e);
+                    }
+                }
+                _ = heartbeat_interval.tick(), if self.heartbeat.is_some() || self.stats_timer.is_some() => {
+                    let now = Instant::now();
+                    self.maybe_heartbeat(now);
+                    self.maybe_emit_stats(now);
+                }
+                _ = slot_lag_interval.tick() => {
+                    self.check_slot_lag().await;
+                    let expired = self.bonding_curve_cache.prune(Instant::now());
+                    if expired > 0 {
+                        debug!("Evicted {} cached bonding curves", expired);
+                    }
+                    self.volume.prune(Instant::now());
+                }
+                _ = fees_interval.tick() => self.refresh_curve_fees().await,
+                _ = reputation_interval.tick() => self.flush_reputation(),
+                Some(attempt) = self.buy_result_receiver.recv() => {
+                    self.in_flight_buys -= 1;
+                    self.finish_buy(attempt);
+                }
+                Some(attempt) = self.sell_result_receiver.recv() => {
+                    self.in_flight_sells -= 1;
+                    self.finish_sell(attempt);
+                }
+                Some(request) = self.admin_receiver.recv() => {
+                    let response = self.handle_admin_command(request.command).await;
+                    let _ = request.reply.send(response);
+                }
+                Some(request) = self.metrics_receiver.recv() => {
+                    let _ = request.reply.send(self.metrics_snapshot());
+                }
             }
         }
+
+        // however the loop ended, buys and sells already sent still get accounted for
+        self.drain_in_flight().await;
+        self.flush_reputation();
         Ok(())
     }
 
+    /// Write creator reputation if it changed since the last write
+    fn flush_reputation(&mut self) {
+        if let Err(e) = self.reputation.flush() {
+            error!("Failed to save creator reputation: {}", e);
+        }
+    }
+
+    /// Account for concurrent buys and sells already sent, waiting up to
+    /// `STOP_DRAIN_TIMEOUT`
+    async fn drain_in_flight(&mut self) {
+        let deadline = tokio::time::Instant::now() + STOP_DRAIN_TIMEOUT;
+        while self.in_flight_buys + self.in_flight_sells > 0 {
+            tokio::select! {
+                Some(attempt) = self.buy_result_receiver.recv(), if self.in_flight_buys > 0 => {
+                    self.in_flight_buys -= 1;
+                    self.finish_buy(attempt);
+                }
+                Some(attempt) = self.sell_result_receiver.recv(), if self.in_flight_sells > 0 => {
+                    self.in_flight_sells -= 1;
+                    self.finish_sell(attempt);
+                }
+                _ = tokio::time::sleep_until(deadline) => {
+                    warn!(
+                        "Stopping with {} buys and {} sells unreported; check the wallet for their outcome",
+                        self.in_flight_buys, self.in_flight_sells
+                    );
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Re-read the global account so quotes use the current fees
+    async fn refresh_curve_fees(&mut self) {
+        let before = self.transaction_executor.curve_fees();
+        match self.transaction_executor.fetch_global_account().await {
+            Ok(global) if global.fees() != before => info!(
+                "FEES: {} bps protocol + {} bps creator",
+                global.fee_basis_points, global.creator_fee_basis_points
+            ),
+            Ok(_) => {}
+            Err(e) => warn!("Failed to refresh curve fees: {}", e),
+        }
+    }
+
+    /// Log a liveness summary if the heartbeat is due at `now`; returns whether it fired
+    fn maybe_heartbeat(&mut self, now: Instant) -> bool {
+        let due = self
+            .heartbeat
+            .as_mut()
+            .is_some_and(|heartbeat| heartbeat.poll(now));
+        if due {
+            info!(
+                "HEARTBEAT: up {}s | {} tracked | {} open positions | {} buys | stream {}",
+                now.saturating_duration_since(self.started_at).as_secs(),
+                self.tracked_tokens.len(),
+                self.positions.len(),
+                self.successful_buys,
+                if self.stream_connected.load(Ordering::Relaxed) {
+                    "connected"
+                } else {
+                    "disconnected"
+                }
+            );
+        }
+        due
+    }
+
+    /// Emit a `StatsUpdate` if one is due at `now`; returns whether it fired
+    fn maybe_emit_stats(&mut self, now: Instant) -> bool {
+        let due = self
+            .stats_timer
+            .as_mut()
+            .is_some_and(|timer| timer.poll(now));
+        if due {
+            let stats = self.stats_at(now);
+            #[cfg(feature = "statsd")]
+            if let Some(statsd) = &mut self.statsd {
+                statsd.emit_stats(&stats);
+            }
+            let _ = self.event_sender.send(SniperEvent::StatsUpdate(stats));
+        }
+        due
+    }
+
+    /// Accept admin commands on `address`, returning the bound address
+    pub async fn start_admin_listener(&mut self, address: &str) -> Result<String, SniperError> {
+        let (bound, task) = spawn_admin_listener(address, self.admin_sender.clone())
+            .await
+            .map_err(|e| SniperError::InvalidConfig(format!("Admin socket {}: {}", address, e)))?;
+        self.admin_task = Some(task);
+        Ok(bound)
+    }
+
+    /// Serve `/health` and `/metrics` on `port` at `metrics_bind`, returning the bound address
+    pub async fn start_metrics_server(&mut self, port: u16) -> Result<SocketAddr, SniperError> {
+        let address = SocketAddr::new(self.config.metrics_bind, port);
+        let (bound, task) = spawn_metrics_server(
+            address,
+            self.stream_connected.clone(),
+            self.metrics_sender.clone(),
+        )
+        .await
+        .map_err(|e| SniperError::InvalidConfig(format!("Metrics address {}: {}", address, e)))?;
+        self.metrics_task = Some(task);
+        Ok(bound)
+    }
+
+    fn metrics_snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            stats: self.stats(),
+            stream_connected: self.stream_connected.load(Ordering::Relaxed),
+            stream_reconnects: self.stream_connects.saturating_sub(1),
+        }
+    }
+
+    async fn handle_admin_command(&mut self, command: AdminCommand) -> String {
+        info!("ADMIN: {:?}", command);
+        match command {
+            AdminCommand::Stats => {
+                let stats = self.stats();
+                format!(
+                    "tracked={} bought={} successful_buys={} failed_buys={} positions={} paused={} uptime_seconds={} max_drawdown_pct={:.2}",
+                    stats.tracked,
+                    stats.bought,
+                    stats.successful_buys,
+                    stats.failed_buys,
+                    self.positions.len(),
+                    self.buys_paused,
+                    stats.uptime_seconds,
+                    stats.max_drawdown_bps as f64 / 100.0
+                )
+            }
+            AdminCommand::Pause => {
+                self.buys_paused = true;
+                "paused".to_string()
+            }
+            AdminCommand::Resume => {
+                self.buys_paused = false;
+                "resumed".to_string()
+            }
+            AdminCommand::Liquidate => {
+                let count = self.liquidate_positions().await;
+                format!("liquidating {} positions", count)
+            }
+            AdminCommand::Stop => {
+                self.stop();
+                "stopping".to_string()
+            }
+        }
+    }
+
+    /// Fire a manual sell for every filled position without one pending
+    async fn liquidate_positions(&mut self) -> usize {
+        let mints: Vec<String> = self
+            .positions
+            .iter()
+            .filter(|(_, position)| !position.sell_pending && position.tokens_received.is_some())
+            .map(|(mint, _)| mint.clone())
+            .collect();
+        let fee_basis_points = self.transaction_executor.curve_fees().total_basis_points();
+
+        for mint_str in &mints {
+            let bonding_curve = self.positions[mint_str].token_info.bonding_curve;
+            let curve = self
+                .transaction_executor
+                .fetch_bonding_curve_data(&bonding_curve)
+                .await
+                .ok();
+
+            let Some(position) = self.positions.get_mut(mint_str) else {
+                continue;
+            };
+            let expected_sol_output = curve
+                .and_then(|curve| position.current_value(&curve, fee_basis_points))
+                .unwrap_or_default();
+            position.sell_pending = true;
+            let _ = self.event_sender.send(SniperEvent::SellTriggered {
+                token_info: position.token_info.clone(),
+                token_amount: position.tokens_received.unwrap_or_default(),
+                expected_sol_output,
+                reason: SellReason::Manual,
+            });
+        }
+
+        mints.len()
+    }
+
+    /// Compare the stream's latest slot against the RPC slot
+    async fn check_slot_lag(&mut self) {
+        let stream_slot = self.stream_slot.load(Ordering::Relaxed);
+        if stream_slot == 0 {
+            return;
+        }
+
+        let rpc_slot = match self.transaction_executor.get_slot().await {
+            Ok(slot) => slot,
+            Err(e) => {
+                error!("Slot lag check failed: {}", e);
+                return;
+            }
+        };
+
+        let lag = rpc_slot.saturating_sub(stream_slot);
+        self.slot_lag = Some(lag);
+
+        if let Some(max_lag) = self.config.max_acceptable_slot_lag {
+            if lag > max_lag {
+                warn!(
+                    "STREAM LAG: {} slots behind RPC (stream {}, rpc {}, max {})",
+                    lag, stream_slot, rpc_slot, max_lag
+                );
+            }
+        }
+    }
+
+    /// Slots the stream trailed the RPC at the last check
+    pub fn slot_lag(&self) -> Option<u64> {
+        self.slot_lag
+    }
+
+    fn buys_paused_for_lag(&self) -> bool {
+        match (self.config.max_acceptable_slot_lag, self.slot_lag) {
+            (Some(max_lag), Some(lag)) => self.config.pause_buys_on_lag && lag > max_lag,
+            _ => false,
+        }
+    }
+
     async fn handle_event(&mut self, event: SniperEvent) -> Result<(), SniperError> {
+        let seq = self.next_event_seq;
+        self.next_event_seq += 1;
+        if self.event_broadcast.receiver_count() > 0 {
+            // only fails with no receivers left
+            let _ = self.event_broadcast.send(EventEnvelope {
+                seq,
+                received_at: SystemTime::now(),
+                event: event.clone(),
+            });
+        }
+        if let Some(webhook) = &self.webhook {
+            webhook.notify(&event);
+        }
+
         match event {
             SniperEvent::TokenCreated(token_info) => self.handle_token_creation(token_info).await,
             SniperEvent::BondingCurveUpdated {
@@ -125,67 +1053,319 @@ impl Sniper {
                 token_info,
                 market_cap,
                 buy_amount,
+                trigger_source,
+                ..
+            } => {
+                let mint_str = token_info.mint.to_string();
+                let result = self
+                    .handle_buy_trigger(token_info, market_cap, buy_amount, trigger_source)
+                    .await;
+                // bought_tokens guards the mint from here if the buy went out
+                self.in_flight.remove(&mint_str);
+                result
+            }
+            SniperEvent::BuyExecuted {
+                token_info,
+                tokens_received,
+                ..
+            } => {
+                let mint_str = token_info.mint.to_string();
+                if matches!(self.positions.get(&mint_str), Some(p) if p.tokens_received.is_none()) {
+                    self.record_fill(&mint_str, tokens_received);
+                }
+                Ok(())
+            }
+            SniperEvent::SellTriggered {
+                token_info,
+                token_amount,
+                expected_sol_output,
+                reason,
             } => {
-                self.handle_buy_trigger(token_info, market_cap, buy_amount)
+                self.handle_sell_trigger(token_info, token_amount, expected_sol_output, reason)
                     .await
             }
+            SniperEvent::ConnectionStatusChanged { connected, .. } => {
+                self.stream_connected.store(connected, Ordering::Relaxed);
+                if connected {
+                    self.stream_connects += 1;
+                } else if self.config.replay_path.is_some() {
+                    info!("REPLAY: capture ended. Stopping sniper.");
+                    self.stop();
+                }
+                Ok(())
+            }
+            SniperEvent::SwapDetected(swap) => {
+                self.record_swap(swap);
+                Ok(())
+            }
             _ => Ok(()),
         }
     }
 
+    /// Count a swap towards its mint's volume if the mint is tracked or held
+    fn record_swap(&mut self, swap: SwapEvent) {
+        let mint_str = swap.mint.to_string();
+        if self.tracked_tokens.contains_key(&mint_str) || self.positions.contains_key(&mint_str) {
+            self.volume
+                .record(swap.mint, swap.sol_amount, Instant::now());
+        }
+    }
+
+    /// Market data for a curve state, with the mint's 24h volume
+    fn market_data(&self, token_info: &TokenInfo, curve: &BondingCurveAccount) -> MarketData {
+        MarketData {
+            volume_24h: Some(self.volume.volume(&token_info.mint, Instant::now())),
+            ..MarketData::new(token_info.clone(), curve.clone())
+        }
+    }
+
     async fn handle_token_creation(&mut self, token_info: TokenInfo) -> Result<(), SniperError> {
+        if !self.mark_seen(token_info.mint) {
+            debug!(
+                "Duplicate creation for {} ({})",
+                token_info.symbol, token_info.creation_signature
+            );
+            return Ok(());
+        }
         info!("TOKEN: {} ({})", token_info.symbol, token_info.mint);
+        self.reputation.record_launch(&token_info.creator);
+
+        if let Some(reason) = self.config.creator_filter_reason(&token_info.creator) {
+            info!(
+                "Skipping {}: {} {}",
+                token_info.display_name(),
+                reason,
+                token_info.creator
+            );
+            return Ok(());
+        }
+
+        if let Some(reason) = self.name_filter.skip_reason(&token_info) {
+            info!("Skipping {}: {}", token_info.display_name(), reason);
+            return Ok(());
+        }
+
+        if !self.dev_holdings_allowed(&token_info).await {
+            return Ok(());
+        }
 
-        self.tracked_tokens
-            .insert(token_info.mint.to_string(), token_info.clone());
+        if self.config.require_http_uri && !token_info.has_http_uri() {
+            info!(
+                "Skipping {}: suspicious metadata URI {:?}",
+                token_info.display_name(),
+                token_info.uri
+            );
+            return Ok(());
+        }
+
+        self.track_token(token_info.clone());
 
         self.check_market_cap(token_info).await
     }
 
-    async fn handle_bonding_curve_update(
-        &mut self,
-        bonding_curve: Pubkey,
-        data: BondingCurveAccount,
-    ) -> Result<(), SniperError> {
-        self.bonding_curve_cache.insert(bonding_curve, data);
-        for token_info in self.tracked_tokens.clone().values() {
-            if token_info.bonding_curve == bonding_curve {
-                if let Some(cached_data) = self.bonding_curve_cache.get(&bonding_curve) {
-                    let market_data = MarketData::new(token_info.clone(), cached_data.clone());
-
-                    // instant check, no RPC
-                    match self
-                        .price_fetcher
-                        .calculate_market_cap_usd(market_data.current_market_cap_sol)
-                        .await
-                    {
-                        Ok(market_cap_usd) => {
-                            if market_cap_usd >= self.config.market_cap_threshold_usd
-                                && !self.bought_tokens.contains(&token_info.mint.to_string())
-                            {
-                                if self.test_mode_single_buy && self.has_bought_once {
-                                    return Ok(());
-                                }
-
-                                info!(
-                                    "INSTANT BUY: {} ${:.0}K",
-                                    token_info.symbol,
-                                    market_cap_usd / 1000.0
-                                );
-
-                                let _ = self.event_sender.send(SniperEvent::BuyTriggered {
-                                    token_info: token_info.clone(),
-                                    market_cap: market_data.current_market_cap_sol,
-                                    buy_amount: self.config.buy_amount_sol,
-                                });
-                            }
-                        }
-                        Err(e) => {
-                            error!("Price fetch failed for {}: {}", token_info.symbol, e);
-                        }
-                    }
-                }
-                break;
+    /// Remember `mint` as handled; false if it was already seen within the last
+    /// `SEEN_MINTS_CAPACITY` creations
+    fn mark_seen(&mut self, mint: Pubkey) -> bool {
+        if !self.seen_mints.insert(mint) {
+            return false;
+        }
+        self.seen_mint_order.push_back(mint);
+        if self.seen_mint_order.len() > SEEN_MINTS_CAPACITY {
+            if let Some(oldest) = self.seen_mint_order.pop_front() {
+                self.seen_mints.remove(&oldest);
+            }
+        }
+        true
+    }
+
+    /// Start tracking a token, evicting the oldest once `max_tracked_tokens` is exceeded
+    fn track_token(&mut self, token_info: TokenInfo) {
+        let mint_str = token_info.mint.to_string();
+        self.bonding_curve_to_mint
+            .insert(token_info.bonding_curve, mint_str.clone());
+        self.tracked_tokens.insert(mint_str, token_info);
+
+        while self.tracked_tokens.len() > self.config.max_tracked_tokens {
+            let Some(oldest) = self
+                .tracked_tokens
+                .iter()
+                .min_by_key(|(_, token)| token.created_at)
+                .map(|(mint, _)| mint.clone())
+            else {
+                break;
+            };
+            self.untrack_token(&oldest);
+        }
+    }
+
+    /// Drop a token and every per-mint auxiliary entry kept for it
+    fn untrack_token(&mut self, mint_str: &str) {
+        if let Some(token_info) = self.tracked_tokens.remove(mint_str) {
+            self.bonding_curve_to_mint.remove(&token_info.bonding_curve);
+            self.bonding_curve_cache.remove(&token_info.bonding_curve);
+            self.buy_counts.remove(&token_info.bonding_curve);
+            self.peak_sol_reserves.remove(&token_info.bonding_curve);
+        }
+        self.shadow_report.forget(mint_str);
+    }
+
+    /// Credit a launch outcome to its creator and stop tracking the token
+    fn resolve_launch(&mut self, token_info: &TokenInfo, outcome: LaunchOutcome) {
+        info!(
+            "OUTCOME: {} {:?} (creator {})",
+            token_info.display_name(),
+            outcome,
+            token_info.creator
+        );
+        self.reputation.record_outcome(&token_info.creator, outcome);
+        self.untrack_token(&token_info.mint.to_string());
+    }
+
+    /// Drop a token whose bonding curve has completed instead of buying it. Buys of
+    /// completed curves only go through with `trade_migrated_on_amm`
+    fn skip_migrated(&mut self, token_info: &TokenInfo) {
+        info!(
+            "MIGRATED: {} curve complete, skipping",
+            token_info.display_name()
+        );
+        let _ = self.event_sender.send(SniperEvent::TokenMigrated {
+            token_info: token_info.clone(),
+        });
+        self.resolve_launch(token_info, LaunchOutcome::Migrated);
+    }
+
+    /// USD price of one token at the cached SOL price
+    async fn price_per_token_usd(&mut self, market_data: &MarketData) -> f64 {
+        match self.price_fetcher.get_sol_price_usd().await {
+            Ok(sol_price) => market_data.price_per_token_usd(sol_price),
+            Err(_) => 0.0,
+        }
+    }
+
+    /// Cap threshold by default, or the weighted score when a scoring strategy is configured
+    fn meets_buy_criteria(
+        &self,
+        token_info: &TokenInfo,
+        curve: &BondingCurveAccount,
+        market_cap_usd: f64,
+    ) -> bool {
+        if !self.has_min_liquidity(token_info, curve) {
+            return false;
+        }
+        let Some(strategy) = &self.strategy else {
+            return market_cap_usd >= self.config.market_cap_threshold_usd;
+        };
+
+        let signals = TokenSignals {
+            market_cap_usd,
+            buy_count: self
+                .buy_counts
+                .get(&token_info.bonding_curve)
+                .copied()
+                .unwrap_or(0),
+            real_sol_reserves: curve.real_sol_reserves,
+        };
+        let score = strategy.score(&signals);
+        debug!("SCORE: {} {:.3} {:?}", token_info.symbol, score, signals);
+        strategy.should_buy(&signals)
+    }
+
+    /// Whether the curve holds at least `min_real_sol_reserves` of real SOL
+    fn has_min_liquidity(&self, token_info: &TokenInfo, curve: &BondingCurveAccount) -> bool {
+        let Some(min_reserves) = self.config.min_real_sol_reserves else {
+            return true;
+        };
+        let sufficient = curve.has_sufficient_liquidity(min_reserves);
+        if !sufficient {
+            debug!(
+                "LIQUIDITY: {} holds {:.3} SOL, below {:.3}",
+                token_info.symbol,
+                curve.real_sol_reserves as f64 / 1e9,
+                min_reserves as f64 / 1e9
+            );
+        }
+        sufficient
+    }
+
+    async fn handle_bonding_curve_update(
+        &mut self,
+        bonding_curve: Pubkey,
+        data: BondingCurveAccount,
+    ) -> Result<(), SniperError> {
+        self.evaluate_position(&bonding_curve, &data).await;
+
+        let previous = self.bonding_curve_cache.get(&bonding_curve);
+        let unchanged = previous == Some(&data);
+        let was_complete = previous.is_some_and(|prev| prev.complete);
+        let sol_added =
+            previous.is_some_and(|prev| data.virtual_sol_reserves > prev.virtual_sol_reserves);
+
+        // refreshes the entry's ttl even when nothing changed
+        self.bonding_curve_cache.insert(bonding_curve, data.clone());
+        if unchanged && self.config.dedup_curve_updates {
+            return Ok(());
+        }
+        let Some(token_info) = self
+            .bonding_curve_to_mint
+            .get(&bonding_curve)
+            .and_then(|mint_str| self.tracked_tokens.get(mint_str))
+            .cloned()
+        else {
+            return Ok(());
+        };
+
+        let peak = self.peak_sol_reserves.entry(bonding_curve).or_default();
+        let previous_peak = *peak;
+        *peak = previous_peak.max(data.real_sol_reserves);
+        match LaunchOutcome::observe(&data, previous_peak) {
+            // kept tracked so the check below can buy on the AMM, credited only once
+            Some(LaunchOutcome::Migrated) if self.config.trade_migrated_on_amm && !was_complete => {
+                self.reputation
+                    .record_outcome(&token_info.creator, LaunchOutcome::Migrated);
+            }
+            Some(LaunchOutcome::Migrated) if self.config.trade_migrated_on_amm => {}
+            Some(LaunchOutcome::Migrated) => {
+                self.skip_migrated(&token_info);
+                return Ok(());
+            }
+            Some(outcome) => {
+                self.resolve_launch(&token_info, outcome);
+                return Ok(());
+            }
+            None => {}
+        }
+
+        if sol_added {
+            *self.buy_counts.entry(bonding_curve).or_default() += 1;
+        }
+
+        let market_data = self.market_data(&token_info, &data);
+        if self.config.market_cap_threshold_sol.is_some() {
+            self.check_sol_threshold(token_info, &market_data, BuySource::Instant);
+            return Ok(());
+        }
+
+        // instant check, no RPC
+        match self
+            .price_fetcher
+            .calculate_market_cap_usd(market_data.current_market_cap_sol)
+            .await
+        {
+            Ok(market_cap_usd) => {
+                if self.record_shadow(&token_info, market_cap_usd) {
+                    return Ok(());
+                }
+
+                self.check_usd_threshold(
+                    token_info,
+                    &market_data,
+                    market_cap_usd,
+                    BuySource::Instant,
+                )
+                .await;
+            }
+            Err(e) => {
+                error!("Price fetch failed for {}: {}", token_info.symbol, e);
             }
         }
 
@@ -194,8 +1374,20 @@ impl Sniper {
 
     async fn check_market_cap(&mut self, token_info: TokenInfo) -> Result<(), SniperError> {
         // cached data first
-        if let Some(cached_data) = self.bonding_curve_cache.get(&token_info.bonding_curve) {
-            let market_data = MarketData::new(token_info.clone(), cached_data.clone());
+        if let Some(cached_data) = self
+            .bonding_curve_cache
+            .get(&token_info.bonding_curve)
+            .cloned()
+        {
+            if cached_data.complete && !self.config.trade_migrated_on_amm {
+                self.skip_migrated(&token_info);
+                return Ok(());
+            }
+            let market_data = self.market_data(&token_info, &cached_data);
+            if self.config.market_cap_threshold_sol.is_some() {
+                self.check_sol_threshold(token_info, &market_data, BuySource::Cached);
+                return Ok(());
+            }
 
             match self
                 .price_fetcher
@@ -203,25 +1395,17 @@ impl Sniper {
                 .await
             {
                 Ok(market_cap_usd) => {
-                    if market_cap_usd >= self.config.market_cap_threshold_usd {
-                        info!(
-                            "CACHED BUY: {} ${:.0}K",
-                            token_info.symbol,
-                            market_cap_usd / 1000.0
-                        );
-
-                        if !self.bought_tokens.contains(&token_info.mint.to_string()) {
-                            if self.test_mode_single_buy && self.has_bought_once {
-                                return Ok(());
-                            }
-
-                            let _ = self.event_sender.send(SniperEvent::BuyTriggered {
-                                token_info,
-                                market_cap: market_data.current_market_cap_sol,
-                                buy_amount: self.config.buy_amount_sol,
-                            });
-                        }
+                    if self.record_shadow(&token_info, market_cap_usd) {
+                        return Ok(());
                     }
+
+                    self.check_usd_threshold(
+                        token_info,
+                        &market_data,
+                        market_cap_usd,
+                        BuySource::Cached,
+                    )
+                    .await;
                     return Ok(());
                 }
                 Err(e) => {
@@ -232,13 +1416,29 @@ impl Sniper {
         }
 
         // RPC fallback if not cached
+        let age = token_info.age_seconds();
+        if age < RPC_FALLBACK_MIN_AGE_SECS {
+            debug!(
+                "Skipping RPC fetch for {}: seen {}s ago, curve not readable yet",
+                token_info.symbol, age
+            );
+            return Ok(());
+        }
         match self
             .transaction_executor
             .fetch_bonding_curve_data(&token_info.bonding_curve)
             .await
         {
             Ok(bonding_curve_data) => {
-                let market_data = MarketData::new(token_info.clone(), bonding_curve_data);
+                if bonding_curve_data.complete && !self.config.trade_migrated_on_amm {
+                    self.skip_migrated(&token_info);
+                    return Ok(());
+                }
+                let market_data = self.market_data(&token_info, &bonding_curve_data);
+                if self.config.market_cap_threshold_sol.is_some() {
+                    self.check_sol_threshold(token_info, &market_data, BuySource::Rpc);
+                    return Ok(());
+                }
 
                 // cached SOL price
                 match self
@@ -247,35 +1447,17 @@ impl Sniper {
                     .await
                 {
                     Ok(market_cap_usd) => {
-                        if market_cap_usd >= self.config.market_cap_threshold_usd {
-                            info!(
-                                "BUY TARGET: {} ${:.0}K",
-                                token_info.symbol,
-                                market_cap_usd / 1000.0
-                            );
-                            if !self.bought_tokens.contains(&token_info.mint.to_string()) {
-                                if self.test_mode_single_buy && self.has_bought_once {
-                                    info!(
-                                        "TEST MODE: {} at ${:.2} meets threshold but skipping (already bought once)",
-                                        token_info.display_name(),
-                                        market_cap_usd
-                                    );
-                                    return Ok(());
-                                }
-
-                                info!(
-                                    "FAST BUY: {} ${:.0}K",
-                                    token_info.symbol,
-                                    market_cap_usd / 1000.0
-                                );
-
-                                let _ = self.event_sender.send(SniperEvent::BuyTriggered {
-                                    token_info,
-                                    market_cap: market_data.current_market_cap_sol,
-                                    buy_amount: self.config.buy_amount_sol,
-                                });
-                            }
+                        if self.record_shadow(&token_info, market_cap_usd) {
+                            return Ok(());
                         }
+
+                        self.check_usd_threshold(
+                            token_info,
+                            &market_data,
+                            market_cap_usd,
+                            BuySource::Rpc,
+                        )
+                        .await;
                     }
                     Err(e) => {
                         error!("Price fetch failed for {}: {}", token_info.symbol, e);
@@ -293,6 +1475,433 @@ impl Sniper {
         Ok(())
     }
 
+    /// Trigger a buy if the USD market cap meets the threshold or strategy. Nothing is
+    /// logged as a buy until the comparison has passed
+    async fn check_usd_threshold(
+        &mut self,
+        token_info: TokenInfo,
+        market_data: &MarketData,
+        market_cap_usd: f64,
+        trigger_source: BuySource,
+    ) {
+        if !self.meets_buy_criteria(&token_info, &market_data.bonding_curve_data, market_cap_usd) {
+            debug!(
+                "BELOW TARGET: {} ${:.0}K ({} trigger)",
+                token_info.symbol,
+                market_cap_usd / 1000.0,
+                trigger_source.as_str()
+            );
+            return;
+        }
+        if self.buy_pending(&token_info.mint) {
+            return;
+        }
+        if self.test_mode_single_buy && self.has_bought_once {
+            info!(
+                "TEST MODE: {} at ${:.2} meets threshold but skipping (already bought once)",
+                token_info.display_name(),
+                market_cap_usd
+            );
+            return;
+        }
+
+        info!(
+            "BUY TARGET: {} ${:.0}K ({} trigger)",
+            token_info.symbol,
+            market_cap_usd / 1000.0,
+            trigger_source.as_str()
+        );
+        let price_per_token_usd = self.price_per_token_usd(market_data).await;
+        self.trigger_buy(
+            token_info,
+            market_data.current_market_cap_sol,
+            trigger_source,
+            price_per_token_usd,
+        );
+    }
+
+    /// Trigger a buy if the market cap in SOL meets `market_cap_threshold_sol`, without
+    /// looking up the SOL price
+    fn check_sol_threshold(
+        &mut self,
+        token_info: TokenInfo,
+        market_data: &MarketData,
+        trigger_source: BuySource,
+    ) {
+        let Some(threshold_sol) = self.config.market_cap_threshold_sol else {
+            return;
+        };
+        let market_cap_sol = market_data.current_market_cap_sol;
+        if market_cap_sol < threshold_sol
+            || !self.has_min_liquidity(&token_info, &market_data.bonding_curve_data)
+            || self.buy_pending(&token_info.mint)
+            || (self.test_mode_single_buy && self.has_bought_once)
+        {
+            return;
+        }
+
+        info!(
+            "SOL THRESHOLD BUY: {} {:.2} SOL ({} trigger)",
+            token_info.symbol,
+            market_cap_sol as f64 / 1e9,
+            trigger_source.as_str()
+        );
+
+        // display only, so a stale price is fine
+        let price_per_token_usd = self
+            .price_fetcher
+            .last_sol_price_usd()
+            .map_or(0.0, |sol_price| market_data.price_per_token_usd(sol_price));
+        self.trigger_buy(
+            token_info,
+            market_cap_sol,
+            trigger_source,
+            price_per_token_usd,
+        );
+    }
+
+    /// Whether `mint` is bought or has a buy queued
+    fn buy_pending(&self, mint: &Pubkey) -> bool {
+        let mint_str = mint.to_string();
+        self.bought_tokens.contains(&mint_str) || self.in_flight.contains(&mint_str)
+    }
+
+    /// Queue a `BuyTriggered`, unless one is already queued for the mint. The cached,
+    /// instant and RPC paths can all fire before the first trigger is handled
+    fn trigger_buy(
+        &mut self,
+        token_info: TokenInfo,
+        market_cap: u64,
+        trigger_source: BuySource,
+        price_per_token_usd: f64,
+    ) {
+        if !self.in_flight.insert(token_info.mint.to_string()) {
+            debug!(
+                "Buy already queued for {}, dropping {} trigger",
+                token_info.display_name(),
+                trigger_source.as_str()
+            );
+            return;
+        }
+        let _ = self.event_sender.send(SniperEvent::BuyTriggered {
+            token_info,
+            market_cap,
+            buy_amount: self.config.buy_amount_sol,
+            trigger_source,
+            price_per_token_usd,
+        });
+    }
+
+    /// Record the tokens a buy actually delivered and report slippage against the quote
+    fn record_fill(&mut self, mint_str: &str, tokens_received: u64) {
+        let Some(position) = self.positions.get_mut(mint_str) else {
+            return;
+        };
+        position.tokens_received = Some(tokens_received);
+        let realized_slippage_bps = position.realized_slippage_bps();
+
+        if let Some(bps) = realized_slippage_bps {
+            info!(
+                "FILL: {} - expected {} tokens, received {} ({:+.2}% slippage)",
+                position.token_info.display_name(),
+                position.expected_tokens.unwrap_or_default(),
+                tokens_received,
+                bps as f64 / 100.0
+            );
+        }
+
+        let event = SniperEvent::BuyExecuted {
+            token_info: position.token_info.clone(),
+            transaction_signature: position.signature.clone(),
+            amount_spent: position.entry_cost_lamports,
+            tokens_received,
+            realized_slippage_bps,
+            snapshot: position.buy_snapshot,
+        };
+        let _ = self.event_sender.send(event);
+    }
+
+    /// Credit the launch outcome of a held token to its creator, once per position.
+    /// Bought tokens are no longer tracked, so `handle_bonding_curve_update` misses them
+    fn observe_held_launch(&mut self, mint_str: &str, data: &BondingCurveAccount) {
+        let Some(position) = self.positions.get_mut(mint_str) else {
+            return;
+        };
+        let previous_peak = position.peak_real_sol_lamports;
+        position.peak_real_sol_lamports = previous_peak.max(data.real_sol_reserves);
+        if position.launch_resolved {
+            return;
+        }
+        let Some(outcome) = LaunchOutcome::observe(data, previous_peak) else {
+            return;
+        };
+        position.launch_resolved = true;
+        let token_info = position.token_info.clone();
+        info!(
+            "OUTCOME: {} {:?} (creator {}, held)",
+            token_info.display_name(),
+            outcome,
+            token_info.creator
+        );
+        self.reputation.record_outcome(&token_info.creator, outcome);
+    }
+
+    /// Check an open position against the exit rules on a curve update
+    async fn evaluate_position(&mut self, bonding_curve: &Pubkey, data: &BondingCurveAccount) {
+        let Some(mint_str) = self
+            .positions
+            .iter()
+            .find(|(_, position)| position.token_info.bonding_curve == *bonding_curve)
+            .map(|(mint, _)| mint.clone())
+        else {
+            return;
+        };
+        self.observe_held_launch(&mint_str, data);
+
+        if data.complete {
+            return;
+        }
+
+        // buy may not have landed yet
+        let now = Instant::now();
+        let position = &self.positions[&mint_str];
+        let fill_check_due = position.tokens_received.is_none()
+            && position
+                .last_fill_check
+                .is_none_or(|checked| now.duration_since(checked) >= FILL_CHECK_INTERVAL);
+        if fill_check_due {
+            if let Some(position) = self.positions.get_mut(&mint_str) {
+                position.last_fill_check = Some(now);
+            }
+            if let Some(wallet) = &self.wallet {
+                let mint = self.positions[&mint_str].token_info.mint;
+                if let Ok(Some(balance)) = self
+                    .transaction_executor
+                    .fetch_token_balance(&wallet.pubkey(), &mint)
+                    .await
+                {
+                    if balance > 0 {
+                        self.record_fill(&mint_str, balance);
+                    }
+                }
+            }
+        }
+
+        let Some(position) = self.positions.get_mut(&mint_str) else {
+            return;
+        };
+        if position.sell_pending {
+            return;
+        }
+        let (Some(tokens), Some(value)) = (
+            position.tokens_received,
+            position.current_value(
+                data,
+                self.transaction_executor.curve_fees().total_basis_points(),
+            ),
+        ) else {
+            return;
+        };
+        position.record_value(value);
+
+        // stop-loss wins if both rules match
+        let pnl_bps = position.pnl_bps(value);
+        let reason = if self
+            .config
+            .stop_loss_bps
+            .is_some_and(|stop_loss_bps| pnl_bps <= -(stop_loss_bps as i64))
+        {
+            SellReason::StopLoss
+        } else if self
+            .config
+            .take_profit_bps
+            .is_some_and(|take_profit_bps| pnl_bps >= take_profit_bps as i64)
+        {
+            SellReason::TakeProfit
+        } else {
+            return;
+        };
+
+        let profit_lamports = value as i128 - position.entry_cost_lamports as i128;
+        if let (SellReason::TakeProfit, Some(floor)) = (reason, self.config.min_net_profit_usd) {
+            let symbol = position.token_info.symbol.clone();
+            let Ok(sol_price) = self.price_fetcher.get_sol_price_usd().await else {
+                warn!("No SOL price to check {} profit floor, holding", symbol);
+                return;
+            };
+            let profit_usd = profit_lamports as f64 / 1e9 * sol_price;
+            if profit_usd < floor {
+                debug!(
+                    "PROFIT FLOOR: {} ${:.2} net below ${:.2}, holding",
+                    symbol, profit_usd, floor
+                );
+                return;
+            }
+        }
+        let Some(position) = self.positions.get_mut(&mint_str) else {
+            return;
+        };
+
+        info!(
+            "{}: {} {:+.4} SOL ({:+.2}%)",
+            match reason {
+                SellReason::StopLoss => "STOP LOSS",
+                SellReason::TakeProfit => "TAKE PROFIT",
+                SellReason::Manual => "MANUAL SELL",
+            },
+            position.token_info.symbol,
+            profit_lamports as f64 / 1e9,
+            pnl_bps as f64 / 100.0
+        );
+
+        position.sell_pending = true;
+        let _ = self.event_sender.send(SniperEvent::SellTriggered {
+            token_info: position.token_info.clone(),
+            token_amount: tokens,
+            expected_sol_output: value,
+            reason,
+        });
+    }
+
+    /// Send a triggered sell on its own task; `sell_pending` holds off further triggers
+    /// until `finish_sell` takes its result
+    async fn handle_sell_trigger(
+        &mut self,
+        token_info: TokenInfo,
+        token_amount: u64,
+        expected_sol_output: u64,
+        reason: SellReason,
+    ) -> Result<(), SniperError> {
+        let Some(wallet) = self.wallet.clone() else {
+            error!("No wallet configured for selling");
+            if let Some(position) = self.positions.get_mut(&token_info.mint.to_string()) {
+                position.sell_pending = false;
+            }
+            return Ok(());
+        };
+
+        let attempt = SellAttempt::send_blocking(
+            self.transaction_executor.clone(),
+            wallet,
+            token_info,
+            token_amount,
+            expected_sol_output,
+            reason,
+        );
+        self.in_flight_sells += 1;
+        let results = self.sell_result_sender.clone();
+        tokio::spawn(async move {
+            let _ = results.send(attempt.await);
+        });
+
+        Ok(())
+    }
+
+    /// Close the position of a confirmed sell, or hand an unconfirmed one back to the exit
+    /// rules
+    fn finish_sell(&mut self, attempt: SellAttempt) {
+        let SellAttempt {
+            token_info,
+            expected_sol_output,
+            reason,
+            confirmed,
+        } = attempt;
+        let mint_str = token_info.mint.to_string();
+
+        let Some(signature) = confirmed else {
+            // still holding the tokens, so the exit rules get another go
+            if let Some(position) = self.positions.get_mut(&mint_str) {
+                position.sell_pending = false;
+            }
+            return;
+        };
+
+        info!(
+            "SELL SUCCESSFUL! {} - TX: {} - Expected: {} SOL ({})",
+            token_info.display_name(),
+            signature,
+            expected_sol_output as f64 / 1e9,
+            reason.as_str()
+        );
+        if let Some(position) = self.positions.remove(&mint_str) {
+            let pnl_lamports = expected_sol_output as i128 - position.entry_cost_lamports as i128;
+            info!(
+                "REALIZED PnL: {} {:+.4} SOL ({:+.2}%) | max drawdown {:.2}%",
+                token_info.symbol,
+                pnl_lamports as f64 / 1e9,
+                position.pnl_bps(expected_sol_output) as f64 / 100.0,
+                position.max_drawdown_percent()
+            );
+            self.closed_max_drawdown_bps =
+                self.closed_max_drawdown_bps.max(position.max_drawdown_bps);
+            self.record_trade_outcome(pnl_lamports > 0);
+        }
+    }
+
+    /// Feed a realized outcome to the adaptive threshold, if enabled
+    fn record_trade_outcome(&mut self, won: bool) {
+        let Some(adaptive) = &mut self.adaptive else {
+            return;
+        };
+
+        let threshold = adaptive.record(won);
+        if threshold != self.config.market_cap_threshold_usd {
+            info!(
+                "ADAPTIVE: threshold ${:.0} -> ${:.0} (win rate {:.0}%)",
+                self.config.market_cap_threshold_usd,
+                threshold,
+                adaptive.win_rate().unwrap_or_default() * 100.0
+            );
+            self.config.market_cap_threshold_usd = threshold;
+            self.strategy = ScoringStrategy::from_config(&self.config);
+        }
+    }
+
+    /// Record shadow threshold hits; returns true when shadow mode swallows the buy decision
+    fn record_shadow(&mut self, token_info: &TokenInfo, market_cap_usd: f64) -> bool {
+        if !self.shadow_report.is_enabled() {
+            return false;
+        }
+
+        let crossed = self
+            .shadow_report
+            .record(&token_info.mint.to_string(), market_cap_usd);
+        if !crossed.is_empty() {
+            info!(
+                "SHADOW: {} ${:.0}K crossed {:?} | {}",
+                token_info.symbol,
+                market_cap_usd / 1000.0,
+                crossed,
+                self.shadow_report.summary()
+            );
+        }
+
+        true
+    }
+
+    /// Buys of `creator`'s tokens within the rate limit window ending at `now`, forgetting older ones
+    fn recent_creator_buys(&mut self, creator: &Pubkey, now: Instant) -> usize {
+        let window = Duration::from_secs(self.config.creator_buy_window_seconds);
+        let Some(buys) = self.creator_buys.get_mut(creator) else {
+            return 0;
+        };
+        while buys
+            .front()
+            .is_some_and(|bought_at| now.saturating_duration_since(*bought_at) >= window)
+        {
+            buys.pop_front();
+        }
+        let recent = buys.len();
+        if recent == 0 {
+            self.creator_buys.remove(creator);
+        }
+        recent
+    }
+
+    pub fn shadow_report(&self) -> &ShadowReport {
+        &self.shadow_report
+    }
+
     async fn handle_market_cap_update(
         &mut self,
         _market_data: MarketData,
@@ -303,8 +1912,9 @@ impl Sniper {
     async fn handle_buy_trigger(
         &mut self,
         token_info: TokenInfo,
-        _market_cap: u64,
-        buy_amount: u64,
+        market_cap: u64,
+        mut buy_amount: u64,
+        trigger_source: BuySource,
     ) -> Result<(), SniperError> {
         let mint_str = token_info.mint.to_string();
         if self.bought_tokens.contains(&mint_str) {
@@ -320,61 +1930,3294 @@ impl Sniper {
             return Ok(());
         }
 
-        // prevents double buys
-        self.bought_tokens.insert(mint_str.clone());
-
-        info!(
-            "Executing buy for {} - Amount: {} SOL",
-            token_info.display_name(),
-            buy_amount as f64 / 1e9
-        );
+        if self.buys_paused {
+            info!("PAUSED: Skipping buy for {}", token_info.display_name());
+            return Ok(());
+        }
 
-        if let Some(wallet) = &self.wallet {
-            match self
-                .transaction_executor
-                .execute_buy(wallet, &token_info, buy_amount)
-                .await
-            {
-                Ok(signature) => {
-                    info!(
-                        "BUY SUCCESSFUL! {} - TX: {} - Amount: {} SOL",
-                        token_info.display_name(),
-                        signature,
-                        buy_amount as f64 / 1e9
-                    );
+        if self.buys_paused_for_lag() {
+            warn!(
+                "LAG PAUSE: Skipping buy for {} (stream {} slots behind)",
+                token_info.display_name(),
+                self.slot_lag.unwrap_or_default()
+            );
+            return Ok(());
+        }
 
-                    self.has_bought_once = true;
-                    self.tracked_tokens.remove(&mint_str);
-                    if self.test_mode_single_buy {
-                        info!("TEST MODE: First buy completed successfully. Stopping sniper.");
-                        std::process::exit(0);
-                    }
-                }
-                Err(e) => {
-                    error!("Buy failed for {}: {}", token_info.display_name(), e);
-                    // allow retry
-                    self.bought_tokens.remove(&mint_str);
-                }
+        if let Some(last_buy_at) = self.last_buy_at {
+            let since_last = last_buy_at.elapsed();
+            if since_last < Duration::from_millis(self.config.buy_cooldown_ms) {
+                info!(
+                    "COOLDOWN: Skipping buy for {} ({}ms since last buy, cooldown {}ms)",
+                    token_info.display_name(),
+                    since_last.as_millis(),
+                    self.config.buy_cooldown_ms
+                );
+                return Ok(());
             }
-        } else {
-            error!("No wallet configured for buying");
-            self.bought_tokens.remove(&mint_str);
         }
 
-        Ok(())
-    }
+        if let Some(max_buys) = self.config.max_buys_per_creator_per_window {
+            let recent = self.recent_creator_buys(&token_info.creator, Instant::now());
+            if recent >= max_buys as usize {
+                info!(
+                    "CREATOR LIMIT: Skipping buy for {} (creator {} bought {} times in {}s)",
+                    token_info.display_name(),
+                    token_info.creator,
+                    recent,
+                    self.config.creator_buy_window_seconds
+                );
+                return Ok(());
+            }
+        }
 
-    pub fn get_stats(&self) -> (usize, usize) {
-        (self.tracked_tokens.len(), 0)
-    }
-}
+        let age = token_info.age_seconds();
+        if self.config.min_token_age_secs.is_some_and(|min| age < min)
+            || self.config.max_token_age_secs.is_some_and(|max| age > max)
+        {
+            info!(
+                "TOKEN AGE: Skipping buy for {} ({}s old, window {:?}..{:?})",
+                token_info.display_name(),
+                age,
+                self.config.min_token_age_secs,
+                self.config.max_token_age_secs
+            );
+            return Ok(());
+        }
 
-pub async fn run() -> Result<()> {
-    tracing_subscriber::fmt::init();
+        if let Some(min_slots) = self.config.min_slots_since_creation {
+            let slots = self
+                .stream_slot
+                .load(Ordering::Relaxed)
+                .saturating_sub(token_info.creation_slot);
+            if slots < min_slots {
+                info!(
+                    "TOKEN SLOTS: Deferring buy for {} ({} of {} slots since creation)",
+                    token_info.display_name(),
+                    slots,
+                    min_slots
+                );
+                return Ok(());
+            }
+        }
 
-    let config = StreamConfig::from_env().unwrap_or_default();
-    let mut sniper = Sniper::new(config).await?;
+        if let Some(max_positions) = self.config.max_concurrent_positions {
+            let open = self.positions.len() + self.in_flight_buys;
+            if open >= max_positions {
+                info!(
+                    "POSITION LIMIT: Skipping {} with {} open or in-flight positions",
+                    token_info.display_name(),
+                    open
+                );
+                return Ok(());
+            }
+        }
 
-    sniper.start().await?;
-    Ok(())
+        // sized only once the cheap gates pass, since wallet and curve reads cost a round trip
+        if let Some(token_amount) = self.config.buy_token_amount {
+            match self.token_amount_cost(&token_info, token_amount).await {
+                Ok(cost) => buy_amount = cost,
+                Err(e) => {
+                    warn!("TARGET BUY: Skipping {} ({})", token_info.display_name(), e);
+                    return Ok(());
+                }
+            }
+            let max_cost = self
+                .config
+                .buy_token_max_cost_sol
+                .unwrap_or(self.config.buy_amount_sol);
+            if buy_amount > max_cost {
+                warn!(
+                    "TARGET BUY: Skipping {} ({} tokens cost {:.4} SOL, above {:.4})",
+                    token_info.display_name(),
+                    token_amount,
+                    buy_amount as f64 / 1e9,
+                    max_cost as f64 / 1e9
+                );
+                return Ok(());
+            }
+        } else if let Some(pct) = self.config.buy_amount_pct {
+            match self.buy_amount_from_wallet(pct).await {
+                Some(amount) => buy_amount = amount,
+                None => {
+                    warn!(
+                        "BALANCE: Skipping buy for {} (nothing to spend after the fee reserve)",
+                        token_info.display_name()
+                    );
+                    return Ok(());
+                }
+            }
+        } else if let Some(usd) = self.config.buy_amount_usd {
+            match self.price_fetcher.usd_to_lamports(usd).await {
+                Ok(amount) => buy_amount = amount,
+                Err(e) => {
+                    warn!(
+                        "PRICE: Skipping ${:.2} buy for {} ({})",
+                        usd,
+                        token_info.display_name(),
+                        e
+                    );
+                    return Ok(());
+                }
+            }
+        }
+
+        // buys still in flight count as spent until they report back
+        let reserved_cost: u64 = self.reserved_buys.values().map(|r| r.cost).sum();
+        if let Some(limit) = self.config.max_total_spend_sol {
+            let cost = self.amount_spent_estimate(buy_amount);
+            let spent = self.total_spent.saturating_add(reserved_cost);
+            if spent.saturating_add(cost) > limit {
+                warn!(
+                    "SPEND LIMIT: Skipping buy for {} ({} + {} lamports would exceed {})",
+                    token_info.display_name(),
+                    spent,
+                    cost,
+                    limit
+                );
+                let _ = self.event_sender.send(SniperEvent::SpendLimitReached {
+                    token_info,
+                    spent,
+                    limit,
+                });
+                return Ok(());
+            }
+        }
+
+        if let Some(cap) = self.config.daily_spend_cap_sol {
+            let cost = self.amount_spent_estimate(buy_amount);
+            let spent = self
+                .daily_spent(Instant::now())
+                .saturating_add(reserved_cost);
+            if spent.saturating_add(cost) > cap {
+                warn!(
+                    "DAILY SPEND LIMIT: Skipping buy for {} ({} + {} lamports in 24h would exceed {})",
+                    token_info.display_name(),
+                    spent,
+                    cost,
+                    cap
+                );
+                let _ = self.event_sender.send(SniperEvent::SpendLimitReached {
+                    token_info,
+                    spent,
+                    limit: cap,
+                });
+                return Ok(());
+            }
+        }
+
+        if let Some(cap) = self.config.max_portfolio_exposure_sol {
+            let exposure = self.portfolio_exposure_sol()
+                + self.reserved_buys.values().map(|r| r.amount).sum::<u64>();
+            if exposure.saturating_add(buy_amount) > cap {
+                warn!(
+                    "EXPOSURE LIMIT: Skipping buy for {} ({:.4} SOL held{} + {:.4} would exceed {:.4})",
+                    token_info.display_name(),
+                    exposure as f64 / 1e9,
+                    self.portfolio_exposure_usd()
+                        .map(|usd| format!(" / ${:.0}", usd))
+                        .unwrap_or_default(),
+                    buy_amount as f64 / 1e9,
+                    cap as f64 / 1e9
+                );
+                return Ok(());
+            }
+        }
+
+        if !self.authorities_allowed(&token_info).await || !self.metadata_allowed(&token_info).await
+        {
+            // don't re-check on every trigger
+            self.untrack_token(&mint_str);
+            return Ok(());
+        }
+
+        if let Some(min_reputation) = self.config.min_creator_reputation {
+            let score = self.reputation.score(&token_info.creator);
+            if score < min_reputation {
+                info!(
+                    "Skipping {}: creator {} reputation {:.2} below {:.2}",
+                    token_info.display_name(),
+                    token_info.creator,
+                    score,
+                    min_reputation
+                );
+                self.untrack_token(&mint_str);
+                return Ok(());
+            }
+        }
+
+        if let Some(min_score) = self.config.min_safety_score {
+            let checker = RugChecker::new(min_score);
+            let safety = checker.score(&self.rug_signals(&token_info).await);
+            if !checker.allows(&safety) {
+                info!(
+                    "SAFETY: Skipping {} (score {} below {}: {})",
+                    token_info.display_name(),
+                    safety.score,
+                    min_score,
+                    safety.reasons.join(", ")
+                );
+                self.untrack_token(&mint_str);
+                return Ok(());
+            }
+            debug!("SAFETY: {} scored {}", token_info.symbol, safety.score);
+        }
+
+        // prevents double buys
+        self.bought_tokens.insert(mint_str.clone());
+
+        let fees = self.transaction_executor.curve_fees();
+        let expected_tokens = self.config.buy_token_amount.or_else(|| {
+            self.bonding_curve_cache
+                .get(&token_info.bonding_curve)
+                .and_then(|curve| {
+                    curve
+                        .get_buy_price_with_fees(
+                            buy_amount,
+                            fees.fee_basis_points,
+                            fees.creator_fee_basis_points,
+                        )
+                        .ok()
+                })
+        });
+
+        info!(
+            "Executing buy for {} - Amount: {} SOL ({} trigger)",
+            token_info.display_name(),
+            buy_amount as f64 / 1e9,
+            trigger_source.as_str()
+        );
+
+        if self.dry_run {
+            self.simulate_buy_trigger(&token_info, buy_amount).await;
+            return Ok(());
+        }
+
+        let Some(wallet) = self.wallet.clone() else {
+            error!("No wallet configured for buying");
+            self.bought_tokens.remove(&mint_str);
+            return Ok(());
+        };
+
+        // hold the limits for this buy until it reports back, so concurrent triggers see it
+        let now = Instant::now();
+        self.reserved_buys.insert(
+            mint_str.clone(),
+            BuyReservation {
+                cost: self.amount_spent_estimate(buy_amount),
+                amount: buy_amount,
+                dispatched_at: now,
+                previous_buy_at: self.last_buy_at.replace(now),
+            },
+        );
+        self.creator_buys
+            .entry(token_info.creator)
+            .or_default()
+            .push_back(now);
+
+        let attempt = BuyAttempt::send_blocking(
+            self.transaction_executor.clone(),
+            wallet,
+            self.config.clone(),
+            token_info,
+            buy_amount,
+            market_cap,
+            expected_tokens,
+        );
+        if self.config.max_concurrent_positions.is_some() {
+            // bought_tokens already holds the mint, so a second trigger can't race this one
+            self.in_flight_buys += 1;
+            let results = self.buy_result_sender.clone();
+            tokio::spawn(async move {
+                let _ = results.send(attempt.await);
+            });
+        } else {
+            let attempt = attempt.await;
+            self.finish_buy(attempt);
+        }
+
+        Ok(())
+    }
+
+    /// Account for a sent buy: open the position, or free the mint and report the failure
+    fn finish_buy(&mut self, attempt: BuyAttempt) {
+        let BuyAttempt {
+            token_info,
+            buy_amount,
+            market_cap,
+            expected_tokens,
+            result,
+            tokens_received,
+            retry_count,
+            elapsed,
+        } = attempt;
+        #[cfg(feature = "statsd")]
+        if let Some(statsd) = &self.statsd {
+            statsd.timing("buy_ms", elapsed);
+        }
+        debug!(
+            "BUY SENT: {} in {}ms after {} retries",
+            token_info.symbol,
+            elapsed.as_millis(),
+            retry_count
+        );
+
+        let mint_str = token_info.mint.to_string();
+        let reservation = self.reserved_buys.remove(&mint_str);
+        match result {
+            Ok(outcome) if outcome.confirmed || self.config.confirm_timeout_secs == 0 => {
+                // the instruction's own minimum when the buy was built from a curve
+                let min_received = outcome
+                    .snapshot
+                    .map(|snapshot| snapshot.min_tokens_out)
+                    .unwrap_or_else(|| self.min_tokens_received(expected_tokens));
+                if let Some(received) = tokens_received
+                    .filter(|received| self.config.verify_buy_balance && *received < min_received)
+                {
+                    // landed without filling; free the mint for another attempt
+                    let reason = format!(
+                        "{} confirmed but balance grew by {} tokens, expected at least {}",
+                        outcome.signature, received, min_received
+                    );
+                    error!("Buy failed for {}: {}", token_info.display_name(), reason);
+                    self.failed_buys += 1;
+                    self.record_spend(
+                        self.amount_spent(buy_amount, outcome.snapshot.as_ref()),
+                        Instant::now(),
+                    );
+                    self.release_buy_slot(&token_info.creator, reservation);
+                    self.bought_tokens.remove(&mint_str);
+                    let _ = self.event_sender.send(SniperEvent::BuyFailed {
+                        token_info,
+                        error: reason,
+                        retry_count,
+                    });
+                    return;
+                }
+
+                let signature = outcome.signature;
+                info!(
+                    "BUY SUCCESSFUL! {} - TX: {} - Amount: {} SOL{}",
+                    token_info.display_name(),
+                    signature,
+                    buy_amount as f64 / 1e9,
+                    outcome
+                        .slot
+                        .map(|slot| format!(" - confirmed in slot {}", slot))
+                        .unwrap_or_default()
+                );
+
+                self.has_bought_once = true;
+                self.successful_buys += 1;
+                let peak_real_sol = self
+                    .peak_sol_reserves
+                    .get(&token_info.bonding_curve)
+                    .copied()
+                    .unwrap_or_default();
+                self.untrack_token(&mint_str);
+                let entry_cost = self.amount_spent(buy_amount, outcome.snapshot.as_ref());
+                self.record_spend(entry_cost, Instant::now());
+                let mut position = Position::new(token_info.clone(), entry_cost, market_cap);
+                position.peak_real_sol_lamports = peak_real_sol;
+                position.expected_tokens = expected_tokens;
+                position.signature = signature.to_string();
+                position.buy_snapshot = outcome.snapshot;
+                if let Some(snapshot) = &outcome.snapshot {
+                    info!("BUY SNAPSHOT: {} {:?}", token_info.symbol, snapshot);
+                }
+                self.positions.insert(mint_str.clone(), position);
+
+                if let Some(received) = tokens_received.filter(|received| *received > 0) {
+                    self.record_fill(&mint_str, received);
+                }
+
+                if self.test_mode_single_buy {
+                    info!("TEST MODE: First buy completed successfully. Stopping sniper.");
+                    self.stop();
+                }
+            }
+            Ok(outcome) => {
+                // may still land, so keep it in bought_tokens rather than risk a second buy
+                let reason = format!(
+                    "{} not confirmed within {}s",
+                    outcome.signature, self.config.confirm_timeout_secs
+                );
+                error!("Buy failed for {}: {}", token_info.display_name(), reason);
+                self.failed_buys += 1;
+                // count it against the cap in case it lands
+                self.record_spend(
+                    self.amount_spent(buy_amount, outcome.snapshot.as_ref()),
+                    Instant::now(),
+                );
+                self.untrack_token(&mint_str);
+                let _ = self.event_sender.send(SniperEvent::BuyFailed {
+                    token_info,
+                    error: reason,
+                    retry_count,
+                });
+            }
+            Err(e) => {
+                error!("Buy failed for {}: {}", token_info.display_name(), e);
+                self.failed_buys += 1;
+                // allow retry
+                self.release_buy_slot(&token_info.creator, reservation);
+                self.bought_tokens.remove(&mint_str);
+                let _ = self.event_sender.send(SniperEvent::BuyFailed {
+                    token_info,
+                    error: e.to_string(),
+                    retry_count,
+                });
+            }
+        }
+    }
+
+    /// Undo the cooldown and creator window entry a failed buy took at dispatch. An
+    /// unconfirmed buy keeps them, since it may still land
+    fn release_buy_slot(&mut self, creator: &Pubkey, reservation: Option<BuyReservation>) {
+        let Some(reservation) = reservation else {
+            return;
+        };
+        if self.last_buy_at == Some(reservation.dispatched_at) {
+            self.last_buy_at = reservation.previous_buy_at;
+        }
+        if let Some(buys) = self.creator_buys.get_mut(creator) {
+            buys.retain(|bought_at| *bought_at != reservation.dispatched_at);
+            if buys.is_empty() {
+                self.creator_buys.remove(creator);
+            }
+        }
+    }
+
+    /// Count `cost` lamports spent at `at` against the run and daily caps. Spends older
+    /// than the daily window are dropped here, so the window stays bounded without a cap
+    fn record_spend(&mut self, cost: u64, at: Instant) {
+        self.total_spent += cost;
+        self.prune_spends(at);
+        self.recent_spends.push_back((at, cost));
+    }
+
+    /// Lamports spent in the daily window ending at `now`, forgetting older spends.
+    /// The window lives in memory only, so a restart starts it empty
+    fn daily_spent(&mut self, now: Instant) -> u64 {
+        self.prune_spends(now);
+        self.recent_spends.iter().map(|(_, cost)| cost).sum()
+    }
+
+    fn prune_spends(&mut self, now: Instant) {
+        while self
+            .recent_spends
+            .front()
+            .is_some_and(|(at, _)| now.saturating_duration_since(*at) >= DAILY_SPEND_WINDOW)
+        {
+            self.recent_spends.pop_front();
+        }
+    }
+
+    /// Fewest tokens a verified buy without a snapshot must deliver: the quote less max
+    /// slippage, or any increase when there is no quote
+    fn min_tokens_received(&self, expected_tokens: Option<u64>) -> u64 {
+        expected_tokens.map_or(1, |expected| {
+            utils::min_token_output(expected, self.config.max_slippage_bps)
+        })
+    }
+
+    /// Lamports a buy costs for accounting: amount plus fees, without slippage headroom
+    /// or refundable ATA rent
+    fn amount_spent_estimate(&self, buy_amount: u64) -> u64 {
+        self.amount_spent(buy_amount, None)
+    }
+
+    /// `amount_spent_estimate` with the priority fee the buy was built with, when its
+    /// snapshot is known; dynamic fees can differ from `priority_fee_sol` either way
+    fn amount_spent(&self, buy_amount: u64, snapshot: Option<&utils::BuySnapshot>) -> u64 {
+        let tip = self.jito_tip();
+        let priority_fee = snapshot.map_or(self.config.priority_fee_sol, |snapshot| {
+            snapshot.priority_fee_lamports
+        });
+        utils::total_cost_estimate(
+            buy_amount,
+            0,
+            priority_fee + tip,
+            constants::BASE_FEE_LAMPORTS,
+            0,
+        )
+    }
+
+    /// Tip each buy pays when sent as a Jito bundle
+    fn jito_tip(&self) -> u64 {
+        match self.config.jito_block_engine_url {
+            Some(_) => self.config.jito_tip_lamports,
+            None => 0,
+        }
+    }
+
+    /// Lamports `token_amount` tokens cost on the mint's cached curve, or a fresh one when
+    /// nothing is cached, protocol and creator fees included
+    async fn token_amount_cost(
+        &self,
+        token_info: &TokenInfo,
+        token_amount: u64,
+    ) -> Result<u64, SniperError> {
+        let curve = match self.bonding_curve_cache.get(&token_info.bonding_curve) {
+            Some(curve) => curve.clone(),
+            None => {
+                self.transaction_executor
+                    .fetch_bonding_curve_data(&token_info.bonding_curve)
+                    .await?
+            }
+        };
+        let fees = self.transaction_executor.curve_fees();
+        let cost = curve.get_buy_cost_for_tokens_with_fees(
+            token_amount,
+            fees.fee_basis_points,
+            fees.creator_fee_basis_points,
+        )?;
+        info!(
+            "TARGET BUY: {} tokens of {} cost {} SOL",
+            token_amount,
+            token_info.symbol,
+            cost as f64 / 1e9
+        );
+        Ok(cost)
+    }
+
+    /// `pct` percent of the wallet balance, keeping back fees and ATA rent. Without a
+    /// wallet (dry runs) the configured fixed amount is used
+    async fn buy_amount_from_wallet(&self, pct: f64) -> Option<u64> {
+        let Some(wallet) = &self.wallet else {
+            return Some(self.config.buy_amount_sol);
+        };
+        let balance = match self
+            .transaction_executor
+            .fetch_sol_balance(&wallet.pubkey())
+            .await
+        {
+            Ok(balance) => balance,
+            Err(e) => {
+                error!("{}", e);
+                return None;
+            }
+        };
+        // priority fee (at the dynamic ceiling when dynamic), Jito tip, base fee and ATA rent
+        let priority_fee = if self.config.dynamic_priority_fee {
+            utils::priority_fee_lamports(
+                self.config.max_priority_fee,
+                self.config.compute_unit_limit,
+            )
+            .max(self.config.priority_fee_sol)
+        } else {
+            self.config.priority_fee_sol
+        };
+        let reserve = utils::total_cost_estimate(
+            0,
+            0,
+            priority_fee + self.jito_tip(),
+            constants::BASE_FEE_LAMPORTS,
+            constants::TOKEN_ACCOUNT_RENT_LAMPORTS,
+        );
+        utils::buy_amount_from_balance(balance, pct, self.config.max_slippage_bps, reserve)
+    }
+
+    /// Dry-run stand-in for a buy: simulate, log and report without sending
+    async fn simulate_buy_trigger(&mut self, token_info: &TokenInfo, buy_amount: u64) {
+        // an ephemeral payer lets the curve math run without a wallet
+        let payer = self
+            .wallet
+            .as_ref()
+            .map_or_else(Keypair::new, |wallet| wallet.insecure_clone());
+
+        let tokens_received = match self
+            .transaction_executor
+            .simulate_buy(&payer, token_info, buy_amount)
+            .await
+        {
+            Ok((expected_tokens, compute_units)) => {
+                info!(
+                    "DRY RUN BUY: {} - {} SOL -> {} tokens, {} CU",
+                    token_info.display_name(),
+                    buy_amount as f64 / 1e9,
+                    expected_tokens,
+                    compute_units
+                );
+                expected_tokens
+            }
+            Err(e) => {
+                // unfunded payers fail simulation; fall back to the curve quote
+                match self
+                    .transaction_executor
+                    .fetch_bonding_curve_data(&token_info.bonding_curve)
+                    .await
+                    .and_then(|curve| curve.get_buy_price(buy_amount))
+                {
+                    Ok(expected_tokens) => {
+                        warn!(
+                            "DRY RUN BUY: {} - {} SOL -> {} tokens (simulation failed: {})",
+                            token_info.display_name(),
+                            buy_amount as f64 / 1e9,
+                            expected_tokens,
+                            e
+                        );
+                        expected_tokens
+                    }
+                    Err(quote_error) => {
+                        error!(
+                            "Dry run failed for {}: {} ({})",
+                            token_info.display_name(),
+                            e,
+                            quote_error
+                        );
+                        self.bought_tokens.remove(&token_info.mint.to_string());
+                        return;
+                    }
+                }
+            }
+        };
+
+        self.has_bought_once = true;
+        self.untrack_token(&token_info.mint.to_string());
+        let _ = self.event_sender.send(SniperEvent::BuyExecuted {
+            token_info: token_info.clone(),
+            transaction_signature: format!("dry-run-{}", token_info.mint),
+            amount_spent: self.amount_spent_estimate(buy_amount),
+            tokens_received,
+            realized_slippage_bps: None,
+            snapshot: None,
+        });
+
+        if self.test_mode_single_buy {
+            info!("TEST MODE: First dry-run buy completed. Stopping sniper.");
+            self.stop();
+        }
+    }
+
+    /// Check the mint's authorities against the config; fails closed on fetch errors
+    async fn authorities_allowed(&self, token_info: &TokenInfo) -> bool {
+        if !self.config.require_no_mint_authority && !self.config.require_no_freeze_authority {
+            return true;
+        }
+
+        let (mint_authority, freeze_authority) = match self
+            .transaction_executor
+            .fetch_mint_authorities(&token_info.mint)
+            .await
+        {
+            Ok(authorities) => authorities,
+            Err(e) => {
+                warn!(
+                    "Skipping {}: could not read mint authorities: {}",
+                    token_info.display_name(),
+                    e
+                );
+                return false;
+            }
+        };
+
+        match authority_rejection(&self.config, mint_authority, freeze_authority) {
+            Some(reason) => {
+                warn!("Skipping {}: {}", token_info.display_name(), reason);
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Check the creator's share of supply against `max_dev_holding_pct`, using the create
+    /// transaction's token balances when the stream sent them and an RPC read otherwise;
+    /// fails closed on fetch errors
+    async fn dev_holdings_allowed(&self, token_info: &TokenInfo) -> bool {
+        let Some(max_pct) = self.config.max_dev_holding_pct else {
+            return true;
+        };
+
+        let holdings = match token_info.creator_initial_tokens {
+            Some(holdings) => holdings,
+            None => match self
+                .transaction_executor
+                .fetch_creator_holdings(&token_info.creator, &token_info.mint)
+                .await
+            {
+                Ok(holdings) => holdings,
+                Err(e) => {
+                    warn!(
+                        "Skipping {}: could not read creator holdings: {}",
+                        token_info.display_name(),
+                        e
+                    );
+                    return false;
+                }
+            },
+        };
+
+        let total_supply = self
+            .bonding_curve_cache
+            .get(&token_info.bonding_curve)
+            .map_or(constants::TOKEN_TOTAL_SUPPLY, |curve| {
+                curve.token_total_supply
+            });
+        let pct = utils::holding_pct(holdings, total_supply);
+        if pct > max_pct {
+            info!(
+                "Skipping {}: creator holds {:.2}% of supply (max {:.2}%)",
+                token_info.display_name(),
+                pct,
+                max_pct
+            );
+            return false;
+        }
+        true
+    }
+
+    /// Gather what `RugChecker` scores, leaving out signals that can't be read. Creator
+    /// holdings come from the create transaction rather than an RPC read; the curve (when
+    /// not cached) and the metadata are fetched together, each given `RUG_SIGNALS_TIMEOUT`
+    async fn rug_signals(&self, token_info: &TokenInfo) -> RugSignals {
+        let cached = self
+            .bonding_curve_cache
+            .get(&token_info.bonding_curve)
+            .cloned();
+        let curve = async {
+            match cached {
+                Some(curve) => Some(curve),
+                None => tokio::time::timeout(
+                    RUG_SIGNALS_TIMEOUT,
+                    self.transaction_executor
+                        .fetch_bonding_curve_data(&token_info.bonding_curve),
+                )
+                .await
+                .ok()
+                .and_then(Result::ok),
+            }
+        };
+        let metadata = async {
+            tokio::time::timeout(
+                RUG_SIGNALS_TIMEOUT,
+                self.metadata_fetcher.fetch(&token_info.uri),
+            )
+            .await
+            .ok()
+            .and_then(Result::ok)
+        };
+        let (curve, metadata) = tokio::join!(curve, metadata);
+
+        let dev_holding_pct = token_info.creator_initial_tokens.map(|holdings| {
+            let total_supply = curve
+                .as_ref()
+                .map_or(constants::TOKEN_TOTAL_SUPPLY, |curve| {
+                    curve.token_total_supply
+                });
+            utils::holding_pct(holdings, total_supply)
+        });
+        RugSignals {
+            dev_holding_pct,
+            curve,
+            age_secs: token_info.age_seconds(),
+            metadata,
+        }
+    }
+
+    /// Fetch and check the metadata JSON when required; fails closed on fetch errors
+    async fn metadata_allowed(&self, token_info: &TokenInfo) -> bool {
+        if !self.config.require_metadata {
+            return true;
+        }
+
+        match self.metadata_fetcher.fetch(&token_info.uri).await {
+            Ok(metadata) => {
+                let missing = metadata.missing_fields(&self.config.metadata_required_fields);
+                if missing.is_empty() {
+                    return true;
+                }
+                warn!(
+                    "Skipping {}: metadata missing {}",
+                    token_info.display_name(),
+                    missing.join(", ")
+                );
+                false
+            }
+            Err(e) => {
+                warn!(
+                    "Skipping {}: could not read metadata: {}",
+                    token_info.display_name(),
+                    e
+                );
+                false
+            }
+        }
+    }
+
+    /// Open positions with unrealized PnL against the cached curves, oldest first
+    pub fn positions(&self) -> Vec<PositionReport> {
+        let fee_basis_points = self.transaction_executor.curve_fees().total_basis_points();
+        let mut reports: Vec<PositionReport> = self
+            .positions
+            .values()
+            .map(|position| {
+                position.report(
+                    self.bonding_curve_cache
+                        .get(&position.token_info.bonding_curve),
+                    fee_basis_points,
+                )
+            })
+            .collect();
+        reports.sort_by_key(|report| report.opened_at);
+        reports
+    }
+
+    /// Current value of all open positions in lamports, valued against cached curves
+    pub fn portfolio_exposure_sol(&self) -> u64 {
+        let fee_basis_points = self.transaction_executor.curve_fees().total_basis_points();
+        self.positions
+            .values()
+            .map(|position| {
+                position.exposure_lamports(
+                    self.bonding_curve_cache
+                        .get(&position.token_info.bonding_curve),
+                    fee_basis_points,
+                )
+            })
+            .sum()
+    }
+
+    /// `portfolio_exposure_sol` in USD at the cached SOL price, if one has been fetched
+    pub fn portfolio_exposure_usd(&self) -> Option<f64> {
+        let sol_price = self.price_fetcher.last_sol_price_usd()?;
+        Some(self.portfolio_exposure_sol() as f64 / 1e9 * sol_price)
+    }
+
+    pub fn stats(&self) -> SniperStats {
+        self.stats_at(Instant::now())
+    }
+
+    fn stats_at(&self, now: Instant) -> SniperStats {
+        SniperStats {
+            tracked: self.tracked_tokens.len(),
+            bought: self.bought_tokens.len(),
+            successful_buys: self.successful_buys,
+            failed_buys: self.failed_buys,
+            uptime_seconds: now.saturating_duration_since(self.started_at).as_secs(),
+            max_drawdown_bps: self
+                .positions
+                .values()
+                .map(|position| position.max_drawdown_bps)
+                .fold(self.closed_max_drawdown_bps, u64::max),
+        }
+    }
+
+    /// Tracked tokens and successful buys
+    #[deprecated(note = "use `stats()`")]
+    pub fn get_stats(&self) -> (usize, usize) {
+        (self.tracked_tokens.len(), self.successful_buys)
+    }
+}
+
+/// Why a mint's authorities fail the configured requirements, if they do
+fn authority_rejection(
+    config: &StreamConfig,
+    mint_authority: Option<Pubkey>,
+    freeze_authority: Option<Pubkey>,
+) -> Option<&'static str> {
+    if config.require_no_mint_authority && mint_authority.is_some() {
+        return Some("mint authority is set");
+    }
+    if config.require_no_freeze_authority && freeze_authority.is_some() {
+        return Some("freeze authority is set");
+    }
+    None
+}
+
+pub async fn run() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let config = StreamConfig::from_env().unwrap_or_default();
+    let mut sniper = Sniper::new(config).await?;
+
+    sniper.start().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::ScoringWeights;
+    use crate::utils::test_utils::{self, test_curve, test_token, ScriptedSender};
+    use solana_client::{rpc_client::Mocks, rpc_request::RpcRequest};
+    use std::sync::atomic::AtomicUsize;
+
+    async fn test_sniper() -> Sniper {
+        let mut sniper = Sniper::new(test_utils::test_config()).await.unwrap();
+        sniper.price_fetcher.set_cached_price(1000.0);
+        sniper
+    }
+
+    /// A token first seen `secs` ago, old enough for the RPC fallback
+    fn seen_secs_ago(secs: u64) -> TokenInfo {
+        let token = test_token();
+        TokenInfo {
+            created_at: token.created_at - secs,
+            ..token
+        }
+    }
+
+    fn next_trigger_source(sniper: &mut Sniper) -> Option<BuySource> {
+        match sniper.event_receiver.try_recv() {
+            Ok(SniperEvent::BuyTriggered { trigger_source, .. }) => Some(trigger_source),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_min_tokens_received_matches_instruction_floor() {
+        let sniper = test_sniper().await;
+        assert_eq!(sniper.min_tokens_received(None), 1);
+        // large quotes must not overflow the slippage product
+        for expected in [1_000_000, u64::MAX] {
+            assert_eq!(
+                sniper.min_tokens_received(Some(expected)),
+                utils::min_token_output(expected, sniper.config.max_slippage_bps)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_instant_path_source() {
+        let mut sniper = test_sniper().await;
+        let token = test_token();
+        sniper.track_token(token.clone());
+
+        sniper
+            .handle_bonding_curve_update(token.bonding_curve, test_curve())
+            .await
+            .unwrap();
+
+        assert_eq!(next_trigger_source(&mut sniper), Some(BuySource::Instant));
+    }
+
+    #[tokio::test]
+    async fn test_curve_update_finds_token_among_many() {
+        let config = Config {
+            max_tracked_tokens: 5_000,
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config).await.unwrap();
+        sniper.price_fetcher.set_cached_price(1.0);
+        let tokens: Vec<TokenInfo> = (0..2_000).map(|_| test_token()).collect();
+        for token in &tokens {
+            sniper.track_token(token.clone());
+        }
+        // one index entry per tracked token, keyed by its curve
+        assert_eq!(sniper.bonding_curve_to_mint.len(), tokens.len());
+        for token in &tokens {
+            assert_eq!(
+                sniper.bonding_curve_to_mint[&token.bonding_curve],
+                token.mint.to_string()
+            );
+        }
+
+        let target = &tokens[1_234];
+        for _ in 0..1_000 {
+            let mut curve = test_curve();
+            curve.virtual_sol_reserves += 1;
+            sniper
+                .handle_bonding_curve_update(target.bonding_curve, test_curve())
+                .await
+                .unwrap();
+            sniper
+                .handle_bonding_curve_update(target.bonding_curve, curve)
+                .await
+                .unwrap();
+        }
+        assert_eq!(sniper.buy_counts.len(), 1);
+        assert_eq!(sniper.buy_counts[&target.bonding_curve], 1_000);
+
+        // updates resolve their token through the index, not a scan of tracked tokens
+        sniper.bonding_curve_to_mint.remove(&target.bonding_curve);
+        let mut curve = test_curve();
+        curve.virtual_sol_reserves += 2;
+        sniper
+            .handle_bonding_curve_update(target.bonding_curve, curve)
+            .await
+            .unwrap();
+        assert!(sniper.tracked_tokens.contains_key(&target.mint.to_string()));
+        assert_eq!(sniper.buy_counts[&target.bonding_curve], 1_000);
+
+        // updates for untracked curves are cached but otherwise ignored
+        let untracked = test_token();
+        sniper
+            .handle_bonding_curve_update(untracked.bonding_curve, test_curve())
+            .await
+            .unwrap();
+        assert!(!sniper.buy_counts.contains_key(&untracked.bonding_curve));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sol_threshold_needs_no_price() {
+        let config = Config {
+            // 30 SOL test curve, far below the USD threshold at any sane price
+            market_cap_threshold_sol: Some(25_000_000_000),
+            market_cap_threshold_usd: 1e12,
+            ..test_utils::test_config()
+        };
+        let source = utils::FixedPriceSource::new(150.0);
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.price_fetcher = PriceFetcher::with_source(Box::new(source.clone()));
+
+        // instant path
+        let token = test_token();
+        sniper.track_token(token.clone());
+        sniper
+            .handle_bonding_curve_update(token.bonding_curve, test_curve())
+            .await
+            .unwrap();
+        assert_eq!(next_trigger_source(&mut sniper), Some(BuySource::Instant));
+
+        // cached path
+        let token = test_token();
+        sniper
+            .bonding_curve_cache
+            .insert(token.bonding_curve, test_curve());
+        sniper.handle_token_creation(token).await.unwrap();
+        assert_eq!(next_trigger_source(&mut sniper), Some(BuySource::Cached));
+
+        // rpc path
+        let data = borsh::to_vec(&test_curve()).unwrap();
+        sniper.transaction_executor = test_utils::scripted_executor(
+            config,
+            ScriptedSender::new(move |_, _| Ok(test_utils::account_response(&data))),
+        );
+        sniper.check_market_cap(seen_secs_ago(5)).await.unwrap();
+        assert_eq!(next_trigger_source(&mut sniper), Some(BuySource::Rpc));
+
+        // below the SOL threshold nothing fires
+        sniper.config.market_cap_threshold_sol = Some(40_000_000_000);
+        let token = test_token();
+        sniper.track_token(token.clone());
+        sniper
+            .handle_bonding_curve_update(token.bonding_curve, test_curve())
+            .await
+            .unwrap();
+        assert_eq!(next_trigger_source(&mut sniper), None);
+
+        assert_eq!(source.calls(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_subscriber_sees_handled_events() {
+        let mut sniper = test_sniper().await;
+        let mut events = sniper.subscribe();
+        let token = test_token();
+        sniper
+            .bonding_curve_cache
+            .insert(token.bonding_curve, test_curve());
+
+        sniper
+            .handle_event(SniperEvent::TokenCreated(token))
+            .await
+            .unwrap();
+        let trigger = sniper.event_receiver.try_recv().unwrap();
+        // paused so handling the trigger sends nothing
+        sniper.buys_paused = true;
+        sniper.handle_event(trigger).await.unwrap();
+
+        let created = common::recv_event(&mut events).await.unwrap();
+        assert!(matches!(created.event, SniperEvent::TokenCreated(_)));
+        let trigger = common::recv_event(&mut events).await.unwrap();
+        assert!(matches!(
+            trigger.event,
+            SniperEvent::BuyTriggered {
+                trigger_source: BuySource::Cached,
+                ..
+            }
+        ));
+        assert!(trigger.is_critical());
+        assert_eq!(trigger.event_type(), "buy_triggered");
+        // stamped in handling order
+        assert_eq!((created.seq, trigger.seq), (0, 1));
+        assert!(created.received_at <= trigger.received_at);
+        assert!(created.received_at > SystemTime::UNIX_EPOCH);
+
+        // a subscriber that falls behind loses the oldest events, the sniper carries on
+        for connected in (0..EVENT_BROADCAST_CAPACITY + 10).map(|i| i % 2 == 0) {
+            sniper
+                .handle_event(SniperEvent::ConnectionStatusChanged {
+                    connected,
+                    endpoint: String::new(),
+                })
+                .await
+                .unwrap();
+        }
+        let mut received = 0;
+        while let Ok(Some(_)) =
+            tokio::time::timeout(Duration::from_millis(50), common::recv_event(&mut events)).await
+        {
+            received += 1;
+        }
+        assert_eq!(received, EVENT_BROADCAST_CAPACITY);
+        assert!(!sniper.stream_connected.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_creation_ignored() {
+        let mut sniper = test_sniper().await;
+        let token = test_token();
+        sniper
+            .bonding_curve_cache
+            .insert(token.bonding_curve, test_curve());
+
+        sniper.handle_token_creation(token.clone()).await.unwrap();
+        assert_eq!(next_trigger_source(&mut sniper), Some(BuySource::Cached));
+        sniper.handle_token_creation(token.clone()).await.unwrap();
+        assert_eq!(next_trigger_source(&mut sniper), None);
+        assert_eq!(sniper.reputation.record(&token.creator).launches, 1);
+
+        // the window is bounded, the oldest mint is forgotten first
+        for _ in 0..SEEN_MINTS_CAPACITY {
+            assert!(sniper.mark_seen(Pubkey::new_unique()));
+        }
+        assert_eq!(sniper.seen_mints.len(), SEEN_MINTS_CAPACITY);
+        assert!(sniper.mark_seen(token.mint));
+    }
+
+    #[tokio::test]
+    async fn test_identical_curve_update_skipped() {
+        let mut sniper = test_sniper().await;
+        let token = test_token();
+        sniper.track_token(token.clone());
+        let curve = test_curve();
+
+        for _ in 0..2 {
+            sniper
+                .handle_bonding_curve_update(token.bonding_curve, curve.clone())
+                .await
+                .unwrap();
+        }
+        assert_eq!(next_trigger_source(&mut sniper), Some(BuySource::Instant));
+        assert_eq!(next_trigger_source(&mut sniper), None);
+        // taken off the queue unhandled, so release the mint as handling would
+        sniper.in_flight.clear();
+
+        let changed = BondingCurveAccount {
+            virtual_sol_reserves: curve.virtual_sol_reserves + 1_000_000,
+            ..curve
+        };
+        sniper
+            .handle_bonding_curve_update(token.bonding_curve, changed.clone())
+            .await
+            .unwrap();
+        assert_eq!(next_trigger_source(&mut sniper), Some(BuySource::Instant));
+        sniper.in_flight.clear();
+
+        // with dedup off every update is evaluated
+        sniper.config.dedup_curve_updates = false;
+        sniper
+            .handle_bonding_curve_update(token.bonding_curve, changed)
+            .await
+            .unwrap();
+        assert_eq!(next_trigger_source(&mut sniper), Some(BuySource::Instant));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_queued_trigger_blocks_duplicates() {
+        let config = Config {
+            fetch_retry_delays_ms: vec![0],
+            ..test_utils::test_config()
+        };
+        let sender = test_utils::buy_sender();
+        let calls = sender.calls();
+        let mut sniper = test_sniper().await;
+        sniper.transaction_executor = test_utils::scripted_executor(config, sender);
+        sniper.set_wallet(Keypair::new());
+        let token = test_token();
+
+        // cached path at creation, then the instant path before that trigger is handled
+        sniper
+            .bonding_curve_cache
+            .insert(token.bonding_curve, test_curve());
+        sniper.handle_token_creation(token.clone()).await.unwrap();
+        sniper
+            .handle_bonding_curve_update(
+                token.bonding_curve,
+                BondingCurveAccount {
+                    real_sol_reserves: 1,
+                    ..test_curve()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(sniper.event_receiver.len(), 1);
+
+        while let Ok(event) = sniper.event_receiver.try_recv() {
+            sniper.handle_event(event).await.unwrap();
+        }
+        assert_eq!(sniper.successful_buys, 1);
+        assert!(sniper.in_flight.is_empty());
+        assert!(calls.load(Ordering::SeqCst) > 0);
+
+        // once handled, the bought guard takes over
+        sniper
+            .handle_bonding_curve_update(
+                token.bonding_curve,
+                BondingCurveAccount {
+                    real_sol_reserves: 2,
+                    ..test_curve()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(next_trigger_source(&mut sniper), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_completed_curve_skips_buy() {
+        let mut sniper = test_sniper().await;
+        let completed = BondingCurveAccount {
+            complete: true,
+            ..test_curve()
+        };
+        let drain = |sniper: &mut Sniper| {
+            std::iter::from_fn(|| sniper.event_receiver.try_recv().ok()).collect::<Vec<_>>()
+        };
+
+        // cached path
+        let token = test_token();
+        sniper
+            .bonding_curve_cache
+            .insert(token.bonding_curve, completed.clone());
+        sniper.handle_token_creation(token.clone()).await.unwrap();
+        let events = drain(&mut sniper);
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event, SniperEvent::BuyTriggered { .. })));
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, SniperEvent::TokenMigrated { .. })));
+        assert!(!sniper.tracked_tokens.contains_key(&token.mint.to_string()));
+
+        // rpc path
+        let data = borsh::to_vec(&completed).unwrap();
+        sniper.transaction_executor = test_utils::scripted_executor(
+            sniper.config.clone(),
+            ScriptedSender::new(move |_, _| Ok(test_utils::account_response(&data))),
+        );
+        let token = seen_secs_ago(5);
+        sniper.track_token(token.clone());
+        sniper.check_market_cap(token.clone()).await.unwrap();
+        let events = drain(&mut sniper);
+        assert!(matches!(
+            events.as_slice(),
+            [SniperEvent::TokenMigrated { .. }]
+        ));
+        assert!(!sniper.tracked_tokens.contains_key(&token.mint.to_string()));
+
+        // instant path
+        let token = test_token();
+        sniper.track_token(token.clone());
+        sniper
+            .handle_bonding_curve_update(token.bonding_curve, completed.clone())
+            .await
+            .unwrap();
+        let events = drain(&mut sniper);
+        assert!(matches!(
+            events.as_slice(),
+            [SniperEvent::TokenMigrated { .. }]
+        ));
+        assert!(!sniper.tracked_tokens.contains_key(&token.mint.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_completed_curve_bought_with_amm_trading() {
+        let mut sniper = test_sniper().await;
+        sniper.config.trade_migrated_on_amm = true;
+        let completed = BondingCurveAccount {
+            complete: true,
+            ..test_curve()
+        };
+
+        // the instant path goes on to the threshold check instead of dropping the token
+        let token = test_token();
+        sniper.track_token(token.clone());
+        for _ in 0..2 {
+            sniper
+                .handle_bonding_curve_update(token.bonding_curve, completed.clone())
+                .await
+                .unwrap();
+        }
+        assert_eq!(next_trigger_source(&mut sniper), Some(BuySource::Instant));
+        assert_eq!(sniper.reputation.record(&token.creator).migrated, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cached_path_source() {
+        let mut sniper = test_sniper().await;
+        let token = test_token();
+        sniper
+            .bonding_curve_cache
+            .insert(token.bonding_curve, test_curve());
+
+        sniper.handle_token_creation(token).await.unwrap();
+
+        assert_eq!(next_trigger_source(&mut sniper), Some(BuySource::Cached));
+    }
+
+    #[tokio::test]
+    async fn test_shadow_mode_records_without_buying() {
+        let config = Config {
+            shadow_thresholds_usd: vec![20_000.0, 40_000.0],
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config).await.unwrap();
+        sniper.price_fetcher.set_cached_price(1000.0);
+        let token = test_token();
+        sniper.track_token(token.clone());
+
+        sniper
+            .handle_bonding_curve_update(token.bonding_curve, test_curve())
+            .await
+            .unwrap();
+
+        assert!(sniper.event_receiver.try_recv().is_err());
+        assert_eq!(
+            sniper.shadow_report().hits(),
+            vec![(20_000.0, 1), (40_000.0, 0)]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dry_run_without_wallet_reports_quote() {
+        let global = vec![0u8; 512];
+        let curve = borsh::to_vec(&test_curve()).unwrap();
+        let sender = ScriptedSender::new(move |request, call| match request {
+            RpcRequest::GetAccountInfo if call == 0 => Ok(test_utils::account_response(&global)),
+            RpcRequest::GetAccountInfo => Ok(test_utils::account_response(&curve)),
+            RpcRequest::GetLatestBlockhash => Ok(serde_json::json!({
+                "context": { "slot": 1 },
+                "value": {
+                    "blockhash": solana_sdk::hash::Hash::default().to_string(),
+                    "lastValidBlockHeight": 100,
+                },
+            })),
+            // an unfunded payer can't simulate
+            RpcRequest::SimulateTransaction => Err("AccountNotFound".to_string()),
+            RpcRequest::SendTransaction => panic!("dry run sent a transaction"),
+            other => Err(format!("unexpected {other}")),
+        });
+        let mut sniper = test_sniper().await;
+        sniper.transaction_executor =
+            test_utils::scripted_executor(test_utils::test_config(), sender);
+        sniper.enable_dry_run();
+        let token = test_token();
+        let buy_amount = 50_000_000;
+
+        for _ in 0..2 {
+            sniper
+                .handle_buy_trigger(token.clone(), 0, buy_amount, BuySource::Instant)
+                .await
+                .unwrap();
+        }
+
+        let expected = test_curve().get_buy_price(buy_amount).unwrap();
+        assert!(matches!(
+            sniper.event_receiver.try_recv(),
+            Ok(SniperEvent::BuyExecuted { tokens_received, .. }) if tokens_received == expected
+        ));
+        // marked bought, so the second trigger was skipped
+        assert!(sniper.bought_tokens.contains(&token.mint.to_string()));
+        assert!(sniper.event_receiver.try_recv().is_err());
+        assert!(sniper.positions.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_slot_lag_from_stream_and_rpc() {
+        let config = Config {
+            max_acceptable_slot_lag: Some(3),
+            pause_buys_on_lag: true,
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        let mut mocks = Mocks::new();
+        mocks.insert(RpcRequest::GetSlot, serde_json::json!(1_005));
+        sniper.transaction_executor = test_utils::mock_executor(config, mocks);
+
+        // no stream updates yet, nothing to compare
+        sniper.check_slot_lag().await;
+        assert_eq!(sniper.slot_lag(), None);
+
+        sniper.stream_slot.store(1_000, Ordering::Relaxed);
+        sniper.check_slot_lag().await;
+        assert_eq!(sniper.slot_lag(), Some(5));
+        assert!(sniper.buys_paused_for_lag());
+    }
+
+    fn pumped_curve(virtual_sol_reserves: u64, virtual_token_reserves: u64) -> BondingCurveAccount {
+        BondingCurveAccount {
+            virtual_sol_reserves,
+            virtual_token_reserves,
+            ..test_curve()
+        }
+    }
+
+    /// GET `path` from the metrics server, answering its snapshot request as the event
+    /// loop would
+    async fn get_metrics(sniper: &mut Sniper, addr: SocketAddr, path: &str) -> (u16, String) {
+        let url = format!("http://127.0.0.1:{}{}", addr.port(), path);
+        let response = tokio::spawn(async move {
+            let response = reqwest::get(url).await.unwrap();
+            (response.status().as_u16(), response.text().await.unwrap())
+        });
+        let request = sniper.metrics_receiver.recv().await.unwrap();
+        request.reply.send(sniper.metrics_snapshot()).unwrap();
+        response.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_health_follows_stream_connection() {
+        let mut sniper = test_sniper().await;
+        let addr = sniper.start_metrics_server(0).await.unwrap();
+        let connection = |connected| SniperEvent::ConnectionStatusChanged {
+            connected,
+            endpoint: "http://localhost:10000".to_string(),
+        };
+
+        // health answers without the event loop
+        let health = || async {
+            let response = reqwest::get(format!("http://127.0.0.1:{}/health", addr.port()))
+                .await
+                .unwrap();
+            (response.status().as_u16(), response.text().await.unwrap())
+        };
+        assert_eq!(health().await, (503, "stream disconnected\n".to_string()));
+
+        sniper.handle_event(connection(true)).await.unwrap();
+        assert_eq!(health().await, (200, "ok\n".to_string()));
+
+        sniper.handle_event(connection(false)).await.unwrap();
+        sniper.handle_event(connection(true)).await.unwrap();
+        sniper.successful_buys = 2;
+        let (status, body) = get_metrics(&mut sniper, addr, "/metrics").await;
+        assert_eq!(status, 200);
+        assert!(
+            body.contains("pump_sniper_stream_reconnects_total 1\n"),
+            "{}",
+            body
+        );
+        assert!(
+            body.contains("pump_sniper_successful_buys_total 2\n"),
+            "{}",
+            body
+        );
+
+        // unknown paths never reach the event loop
+        let url = format!("http://127.0.0.1:{}/nope", addr.port());
+        assert_eq!(reqwest::get(url).await.unwrap().status().as_u16(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_positions_report_unrealized_pnl() {
+        let mut sniper = test_sniper().await;
+        let entry = test_curve();
+        let tokens = entry.get_buy_price(1_000_000_000).unwrap();
+        let token = open_position(&mut sniper, Some(tokens));
+        let pending = open_position(&mut sniper, None);
+
+        // no curve cached yet
+        let reports = sniper.positions();
+        assert_eq!(reports.len(), 2);
+        assert!(reports.iter().all(|r| r.unrealized_pnl_lamports.is_none()));
+
+        let pumped = pumped_curve(60_000_000_000, 500_000_000);
+        sniper
+            .bonding_curve_cache
+            .insert(token.bonding_curve, pumped.clone());
+        let fee_basis_points = sniper
+            .transaction_executor
+            .curve_fees()
+            .total_basis_points();
+        let value = pumped.get_sell_price(tokens, fee_basis_points).unwrap();
+
+        let reports = sniper.positions();
+        let report = reports.iter().find(|r| r.mint == token.mint).unwrap();
+        assert_eq!(report.symbol, token.symbol);
+        assert_eq!(report.tokens_held, Some(tokens));
+        assert_eq!(report.entry_cost_lamports, 1_000_000_000);
+        assert_eq!(report.entry_market_cap_sol, 30_000_000_000);
+        assert_eq!(report.current_value_lamports, Some(value));
+        let pnl = value as i64 - 1_000_000_000;
+        assert!(pnl > 0);
+        assert_eq!(report.unrealized_pnl_lamports, Some(pnl));
+        assert_eq!(report.unrealized_pnl_sol(), Some(pnl as f64 / 1e9));
+        let percent = report.unrealized_pnl_percent.unwrap();
+        assert!((percent - pnl as f64 / 1e7).abs() < 1e-9, "{}", percent);
+
+        // the fill isn't known, so there's nothing to value
+        let report = reports.iter().find(|r| r.mint == pending.mint).unwrap();
+        assert_eq!(report.tokens_held, None);
+        assert_eq!(report.unrealized_pnl_percent, None);
+    }
+
+    fn open_position(sniper: &mut Sniper, tokens_received: Option<u64>) -> TokenInfo {
+        let token = test_token();
+        let mut position = Position::new(token.clone(), 1_000_000_000, 30_000_000_000);
+        position.tokens_received = tokens_received;
+        sniper.positions.insert(token.mint.to_string(), position);
+        token
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_quotes_follow_refreshed_fees() {
+        let curve = test_curve();
+        let curve_data = borsh::to_vec(&curve).unwrap();
+        let global_reads = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let reads = global_reads.clone();
+        let global_pda = utils::derive_global_pda().unwrap().to_string();
+        let mut sniper = test_sniper().await;
+        sniper.transaction_executor = test_utils::scripted_executor(
+            test_utils::test_config(),
+            ScriptedSender::with_params(move |_, params, _| {
+                if params[0] != global_pda.as_str() {
+                    return Ok(test_utils::account_response(&curve_data));
+                }
+                // fees are raised between the first and second read
+                let raised = reads.fetch_add(1, Ordering::SeqCst) > 0;
+                let global = accounts::GlobalAccount {
+                    fee_basis_points: if raised { 200 } else { 100 },
+                    creator_fee_basis_points: if raised { 50 } else { 5 },
+                    ..test_utils::test_global()
+                };
+                Ok(test_utils::account_response(
+                    &borsh::to_vec(&global).unwrap(),
+                ))
+            }),
+        );
+        let tokens = curve.get_buy_price(1_000_000_000).unwrap();
+        let token = open_position(&mut sniper, Some(tokens));
+
+        async fn liquidation_quote(sniper: &mut Sniper, mint: &str) -> u64 {
+            sniper.positions.get_mut(mint).unwrap().sell_pending = false;
+            sniper.liquidate_positions().await;
+            match sniper.event_receiver.try_recv() {
+                Ok(SniperEvent::SellTriggered {
+                    expected_sol_output,
+                    ..
+                }) => expected_sol_output,
+                other => panic!("expected a sell, got {:?}", other),
+            }
+        }
+        let mint = token.mint.to_string();
+
+        sniper.refresh_curve_fees().await;
+        assert_eq!(
+            liquidation_quote(&mut sniper, &mint).await,
+            curve.get_sell_price(tokens, 105).unwrap()
+        );
+
+        sniper.refresh_curve_fees().await;
+        assert_eq!(
+            sniper
+                .transaction_executor
+                .curve_fees()
+                .total_basis_points(),
+            250
+        );
+        assert_eq!(
+            liquidation_quote(&mut sniper, &mint).await,
+            curve.get_sell_price(tokens, 250).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_authority_filter_decisions() {
+        let authority = Some(Pubkey::new_unique());
+        let lenient = test_utils::test_config();
+        assert_eq!(authority_rejection(&lenient, authority, authority), None);
+
+        let strict = Config {
+            require_no_mint_authority: true,
+            require_no_freeze_authority: true,
+            ..test_utils::test_config()
+        };
+        assert_eq!(authority_rejection(&strict, None, None), None);
+        assert!(authority_rejection(&strict, authority, None).is_some());
+        assert!(authority_rejection(&strict, None, authority).is_some());
+
+        let freeze_only = Config {
+            require_no_freeze_authority: true,
+            ..test_utils::test_config()
+        };
+        assert_eq!(authority_rejection(&freeze_only, authority, None), None);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_fires_at_configured_interval() {
+        let config = Config {
+            heartbeat_interval_seconds: 15,
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config).await.unwrap();
+        let start = sniper.started_at;
+        sniper.heartbeat = Some(Heartbeat::new(Duration::from_secs(15), start));
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert!(!sniper.maybe_heartbeat(at(14)));
+        assert!(sniper.maybe_heartbeat(at(15)));
+        assert!(!sniper.maybe_heartbeat(at(20)));
+        assert!(sniper.maybe_heartbeat(at(30)));
+
+        let disabled = Config {
+            heartbeat_interval_seconds: 0,
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(disabled).await.unwrap();
+        assert!(!sniper.maybe_heartbeat(at(3_600)));
+    }
+
+    #[tokio::test]
+    async fn test_auxiliary_maps_pruned_with_tracked_tokens() {
+        let config = Config {
+            max_tracked_tokens: 2,
+            shadow_thresholds_usd: vec![1.0],
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config).await.unwrap();
+        let tokens: Vec<TokenInfo> = (0..3)
+            .map(|i| TokenInfo {
+                created_at: 1_000 + i,
+                ..test_token()
+            })
+            .collect();
+
+        for token in &tokens {
+            sniper
+                .bonding_curve_cache
+                .insert(token.bonding_curve, test_curve());
+            sniper.shadow_report.record(&token.mint.to_string(), 10.0);
+            sniper.track_token(token.clone());
+        }
+
+        let oldest = &tokens[0];
+        assert_eq!(sniper.tracked_tokens.len(), 2);
+        assert!(!sniper.tracked_tokens.contains_key(&oldest.mint.to_string()));
+        assert!(!sniper
+            .bonding_curve_cache
+            .contains_key(&oldest.bonding_curve));
+        assert!(!sniper
+            .bonding_curve_to_mint
+            .contains_key(&oldest.bonding_curve));
+        assert!(!sniper.shadow_report.is_tracking(&oldest.mint.to_string()));
+        for token in &tokens[1..] {
+            assert!(sniper
+                .bonding_curve_cache
+                .contains_key(&token.bonding_curve));
+            assert!(sniper.shadow_report.is_tracking(&token.mint.to_string()));
+        }
+        // eviction doesn't lose shadow statistics
+        assert_eq!(sniper.shadow_report.tokens_seen(), 3);
+
+        sniper.untrack_token(&tokens[1].mint.to_string());
+        assert_eq!(sniper.tracked_tokens.len(), 1);
+        assert!(!sniper
+            .bonding_curve_cache
+            .contains_key(&tokens[1].bonding_curve));
+        assert!(!sniper
+            .shadow_report
+            .is_tracking(&tokens[1].mint.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_scoring_mode_waits_for_momentum() {
+        let config = Config {
+            buy_score_threshold: Some(0.9),
+            score_weights: ScoringWeights {
+                market_cap: 1.0,
+                momentum: 1.0,
+                liquidity: 0.0,
+            },
+            score_momentum_target_buys: 2,
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config).await.unwrap();
+        sniper.price_fetcher.set_cached_price(1000.0);
+        let token = test_token();
+        sniper.track_token(token.clone());
+
+        let mut curve = test_curve();
+        for expected in [None, None, Some(BuySource::Instant)] {
+            sniper
+                .handle_bonding_curve_update(token.bonding_curve, curve.clone())
+                .await
+                .unwrap();
+            assert_eq!(next_trigger_source(&mut sniper), expected);
+            curve.virtual_sol_reserves += 1_000_000_000;
+        }
+        assert_eq!(sniper.buy_counts.get(&token.bonding_curve), Some(&2));
+
+        sniper.untrack_token(&token.mint.to_string());
+        assert!(sniper.buy_counts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_creator_lists_filter_token_creation() {
+        let listed = Pubkey::new_unique();
+        let cases = [
+            // unrestricted default
+            (test_utils::test_config(), Pubkey::new_unique(), true),
+            (
+                Config {
+                    creator_blacklist: HashSet::from([listed]),
+                    ..test_utils::test_config()
+                },
+                listed,
+                false,
+            ),
+            (
+                Config {
+                    creator_whitelist: HashSet::from([listed]),
+                    ..test_utils::test_config()
+                },
+                Pubkey::new_unique(),
+                false,
+            ),
+            (
+                Config {
+                    creator_whitelist: HashSet::from([listed]),
+                    ..test_utils::test_config()
+                },
+                listed,
+                true,
+            ),
+        ];
+
+        for (config, creator, allowed) in cases {
+            let mut sniper = Sniper::new(config).await.unwrap();
+            sniper.price_fetcher.set_cached_price(1000.0);
+            let token = TokenInfo {
+                creator,
+                ..test_token()
+            };
+            sniper
+                .bonding_curve_cache
+                .insert(token.bonding_curve, test_curve());
+
+            sniper.handle_token_creation(token.clone()).await.unwrap();
+
+            assert_eq!(
+                sniper.tracked_tokens.contains_key(&token.mint.to_string()),
+                allowed
+            );
+            assert_eq!(next_trigger_source(&mut sniper).is_some(), allowed);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reputation_updates_from_outcomes_and_gates_buys() {
+        let path = std::env::temp_dir().join(format!(
+            "pump-sniper-reputation-{}.json",
+            Pubkey::new_unique()
+        ));
+        let config = Config {
+            creator_reputation_path: Some(path.to_string_lossy().into_owned()),
+            min_creator_reputation: Some(0.5),
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config).await.unwrap();
+        sniper.price_fetcher.set_cached_price(1000.0);
+        let creator = Pubkey::new_unique();
+        let rugged = TokenInfo {
+            creator,
+            ..test_token()
+        };
+        sniper.handle_token_creation(rugged.clone()).await.unwrap();
+
+        // fills up, then the creator drains it
+        for real_sol_reserves in [1_000_000_000, 0] {
+            let curve = BondingCurveAccount {
+                real_sol_reserves,
+                ..test_curve()
+            };
+            sniper
+                .handle_bonding_curve_update(rugged.bonding_curve, curve)
+                .await
+                .unwrap();
+        }
+        assert!(!sniper.tracked_tokens.contains_key(&rugged.mint.to_string()));
+        assert!(sniper.peak_sol_reserves.is_empty());
+
+        // written on the save tick, not on every outcome
+        assert_eq!(CreatorReputation::load(Some(&path)).unwrap().len(), 0);
+        sniper.flush_reputation();
+        let persisted = CreatorReputation::load(Some(&path)).unwrap();
+        assert_eq!(persisted.record(&creator).launches, 1);
+        assert_eq!(persisted.record(&creator).rugged, 1);
+        assert!((persisted.score(&creator) - 1.0 / 3.0).abs() < 1e-9);
+
+        // known rugger is skipped, an unknown creator gets through to the wallet check
+        let next = TokenInfo {
+            creator,
+            ..test_token()
+        };
+        let unknown = test_token();
+        for token in [&next, &unknown] {
+            sniper.track_token(token.clone());
+            sniper
+                .handle_buy_trigger(token.clone(), 0, 1, BuySource::Cached)
+                .await
+                .unwrap();
+        }
+        assert!(!sniper.tracked_tokens.contains_key(&next.mint.to_string()));
+        assert!(sniper
+            .tracked_tokens
+            .contains_key(&unknown.mint.to_string()));
+
+        // a held token's drain is credited once, though it is no longer tracked
+        let held = open_position(&mut sniper, None);
+        for real_sol_reserves in [1_000_000_000, 0, 0] {
+            let curve = BondingCurveAccount {
+                real_sol_reserves,
+                ..test_curve()
+            };
+            sniper
+                .handle_bonding_curve_update(held.bonding_curve, curve)
+                .await
+                .unwrap();
+        }
+        assert_eq!(sniper.reputation.record(&held.creator).rugged, 1);
+        assert!(sniper.positions.contains_key(&held.mint.to_string()));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_stats_count_failed_buys_and_emit_updates() {
+        let config = Config {
+            fetch_retry_delays_ms: vec![0],
+            stats_interval_seconds: 30,
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor = test_utils::scripted_executor(
+            config,
+            ScriptedSender::new(|_, _| Err("connection reset".to_string())),
+        );
+        sniper.set_wallet(Keypair::new());
+        let token = test_token();
+        sniper.track_token(token.clone());
+
+        sniper
+            .handle_buy_trigger(token, 0, 1, BuySource::Cached)
+            .await
+            .unwrap();
+        assert!(matches!(
+            sniper.event_receiver.try_recv(),
+            Ok(SniperEvent::BuyFailed { .. })
+        ));
+
+        let start = sniper.started_at;
+        sniper.stats_timer = Some(Heartbeat::new(Duration::from_secs(30), start));
+        assert!(!sniper.maybe_emit_stats(start + Duration::from_secs(29)));
+        assert!(sniper.maybe_emit_stats(start + Duration::from_secs(30)));
+        match sniper.event_receiver.try_recv() {
+            Ok(SniperEvent::StatsUpdate(stats)) => assert_eq!(
+                stats,
+                SniperStats {
+                    tracked: 1,
+                    bought: 0,
+                    successful_buys: 0,
+                    failed_buys: 1,
+                    uptime_seconds: 30,
+                    max_drawdown_bps: 0,
+                }
+            ),
+            other => panic!("expected stats update, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_daily_spend_cap_rolls_over() {
+        let mut sniper = test_sniper().await;
+        let cost = sniper.amount_spent_estimate(100_000_000);
+        sniper.config.daily_spend_cap_sol = Some(2 * cost);
+        sniper.set_wallet(Keypair::new());
+
+        let start = Instant::now();
+        let hours = |h: u64| start + Duration::from_secs(h * 60 * 60);
+        sniper.record_spend(cost, start);
+        sniper.record_spend(cost, hours(6));
+        assert_eq!(sniper.daily_spent(hours(23)), 2 * cost);
+
+        // the first spend ages out at 24h, the second at 30h
+        assert_eq!(sniper.daily_spent(hours(24)), cost);
+        assert_eq!(sniper.daily_spent(hours(30)), 0);
+        assert!(sniper.recent_spends.is_empty());
+        assert_eq!(sniper.total_spent, 2 * cost);
+
+        // a full window blocks the next buy
+        sniper.record_spend(cost, Instant::now());
+        sniper.record_spend(cost, Instant::now());
+        let token = test_token();
+        sniper
+            .handle_buy_trigger(token.clone(), 0, 100_000_000, BuySource::Cached)
+            .await
+            .unwrap();
+        assert!(matches!(
+            sniper.event_receiver.try_recv(),
+            Ok(SniperEvent::SpendLimitReached { spent, limit, .. })
+                if spent == 2 * cost && limit == 2 * cost
+        ));
+        assert!(!sniper.bought_tokens.contains(&token.mint.to_string()));
+
+        // recording alone keeps the window bounded
+        sniper.record_spend(cost, hours(48));
+        assert_eq!(sniper.recent_spends.len(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_concurrent_buys_capped() {
+        let config = Config {
+            max_concurrent_positions: Some(2),
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor =
+            test_utils::scripted_executor(config, test_utils::buy_sender());
+        sniper.set_wallet(Keypair::new());
+
+        // none of the sends has reported back, so the third is over the limit
+        let tokens = [test_token(), test_token(), test_token()];
+        for token in &tokens {
+            sniper
+                .handle_buy_trigger(token.clone(), 0, 10_000_000, BuySource::Cached)
+                .await
+                .unwrap();
+        }
+        assert_eq!(sniper.in_flight_buys, 2);
+        assert!(!sniper.bought_tokens.contains(&tokens[2].mint.to_string()));
+
+        for _ in 0..2 {
+            let attempt = sniper.buy_result_receiver.recv().await.unwrap();
+            sniper.in_flight_buys -= 1;
+            sniper.finish_buy(attempt);
+        }
+        assert_eq!(sniper.successful_buys, 2);
+        assert!(tokens[..2]
+            .iter()
+            .all(|token| sniper.positions.contains_key(&token.mint.to_string())));
+
+        // open positions hold the slots just the same
+        sniper
+            .handle_buy_trigger(tokens[2].clone(), 0, 10_000_000, BuySource::Cached)
+            .await
+            .unwrap();
+        assert_eq!(sniper.in_flight_buys, 0);
+        assert_eq!(sniper.positions.len(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_in_flight_buys_hold_limits() {
+        let config = Config {
+            max_concurrent_positions: Some(3),
+            ..test_utils::test_config()
+        };
+        let sends = Arc::new(AtomicUsize::new(0));
+        let sender = {
+            let sends = sends.clone();
+            ScriptedSender::with_params(move |request, params, _| match request {
+                RpcRequest::SendTransaction if sends.fetch_add(1, Ordering::SeqCst) == 1 => {
+                    Err("connection reset by peer".to_string())
+                }
+                _ => test_utils::buy_response(request, params),
+            })
+        };
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor = test_utils::scripted_executor(config, sender);
+        sniper.set_wallet(Keypair::new());
+        let cost = sniper.amount_spent_estimate(10_000_000);
+        sniper.config.max_total_spend_sol = Some(cost * 3 / 2);
+
+        // the first buy hasn't reported back, yet it already holds the spend cap
+        let tokens = [test_token(), test_token()];
+        for token in &tokens {
+            sniper
+                .handle_buy_trigger(token.clone(), 0, 10_000_000, BuySource::Cached)
+                .await
+                .unwrap();
+        }
+        assert_eq!(sniper.in_flight_buys, 1);
+        assert!(!sniper.bought_tokens.contains(&tokens[1].mint.to_string()));
+        assert!(sniper.last_buy_at.is_some());
+        assert_eq!(sniper.creator_buys[&tokens[0].creator].len(), 1);
+
+        let attempt = sniper.buy_result_receiver.recv().await.unwrap();
+        sniper.in_flight_buys -= 1;
+        sniper.finish_buy(attempt);
+        assert!(sniper.reserved_buys.is_empty());
+        assert_eq!(sniper.total_spent, cost);
+
+        // a failed send gives back the cooldown and the creator's window entry
+        sniper.config.max_total_spend_sol = None;
+        let before = sniper.last_buy_at;
+        sniper
+            .handle_buy_trigger(tokens[1].clone(), 0, 10_000_000, BuySource::Cached)
+            .await
+            .unwrap();
+        assert_ne!(sniper.last_buy_at, before);
+        let attempt = sniper.buy_result_receiver.recv().await.unwrap();
+        sniper.in_flight_buys -= 1;
+        sniper.finish_buy(attempt);
+        assert_eq!(sniper.failed_buys, 1);
+        assert_eq!(sniper.last_buy_at, before);
+        assert!(!sniper.creator_buys.contains_key(&tokens[1].creator));
+        assert!(sniper.reserved_buys.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_min_real_sol_reserves_gate() {
+        let mut sniper = test_sniper().await;
+        sniper.config.min_real_sol_reserves = Some(2_000_000_000);
+        let backed = BondingCurveAccount {
+            real_sol_reserves: 2_000_000_000,
+            ..test_curve()
+        };
+        let thin = BondingCurveAccount {
+            real_sol_reserves: 1_999_999_999,
+            ..backed.clone()
+        };
+
+        // 30 SOL at $1000 clears the USD threshold either way
+        for (curve, buys) in [(thin, false), (backed, true)] {
+            let token = test_token();
+            sniper
+                .bonding_curve_cache
+                .insert(token.bonding_curve, curve);
+            sniper.check_market_cap(token).await.unwrap();
+            assert_eq!(next_trigger_source(&mut sniper).is_some(), buys);
+        }
+
+        // the SOL threshold path is gated too
+        sniper.config.market_cap_threshold_sol = Some(1);
+        let token = test_token();
+        sniper
+            .bonding_curve_cache
+            .insert(token.bonding_curve, test_curve());
+        sniper.check_market_cap(token).await.unwrap();
+        assert_eq!(next_trigger_source(&mut sniper), None);
+    }
+
+    #[tokio::test]
+    async fn test_swaps_feed_tracked_volume() {
+        let mut sniper = test_sniper().await;
+        let token = test_token();
+        let stranger = test_token();
+        sniper.track_token(token.clone());
+
+        for (mint, sol_amount) in [(token.mint, 100), (token.mint, 250), (stranger.mint, 999)] {
+            sniper
+                .handle_event(SniperEvent::SwapDetected(SwapEvent {
+                    mint,
+                    is_buy: true,
+                    token_amount: 1,
+                    sol_amount,
+                }))
+                .await
+                .unwrap();
+        }
+
+        let curve = test_curve();
+        assert_eq!(sniper.market_data(&token, &curve).volume_24h, Some(350));
+        // untracked mints are not kept
+        assert_eq!(sniper.market_data(&stranger, &curve).volume_24h, Some(0));
+        assert_eq!(sniper.volume.len(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_transient_buy_failure_retried() {
+        let config = Config {
+            fetch_retry_delays_ms: vec![0],
+            ..test_utils::test_config()
+        };
+        let sends = Arc::new(AtomicUsize::new(0));
+        let blockhashes = Arc::new(AtomicUsize::new(0));
+        let sender = {
+            let (sends, blockhashes) = (sends.clone(), blockhashes.clone());
+            ScriptedSender::with_params(move |request, params, _| match request {
+                RpcRequest::SendTransaction if sends.fetch_add(1, Ordering::SeqCst) == 0 => {
+                    Err("Blockhash not found".to_string())
+                }
+                RpcRequest::GetLatestBlockhash => {
+                    blockhashes.fetch_add(1, Ordering::SeqCst);
+                    test_utils::buy_response(request, params)
+                }
+                _ => test_utils::buy_response(request, params),
+            })
+        };
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor = test_utils::scripted_executor(config, sender);
+        sniper.set_wallet(Keypair::new());
+
+        let token = test_token();
+        sniper
+            .handle_buy_trigger(token.clone(), 0, 10_000_000, BuySource::Cached)
+            .await
+            .unwrap();
+        assert_eq!(sniper.successful_buys, 1);
+        assert_eq!(sniper.failed_buys, 0);
+        assert_eq!(sends.load(Ordering::SeqCst), 2);
+        assert_eq!(blockhashes.load(Ordering::SeqCst), 2);
+        assert!(sniper.positions.contains_key(&token.mint.to_string()));
+
+        assert!(!SniperError::BondingCurveComplete.is_transient());
+        assert!(!SniperError::InsufficientFunds.is_transient());
+        // a revert or a rejected fee recipient fails the same way again
+        assert!(!SniperError::TransactionFailed("failed on-chain".to_string()).is_transient());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_failed_send_not_retried() {
+        let config = Config {
+            fetch_retry_delays_ms: vec![0],
+            ..test_utils::test_config()
+        };
+        let sends = Arc::new(AtomicUsize::new(0));
+        let sender = {
+            let sends = sends.clone();
+            ScriptedSender::with_params(move |request, params, _| match request {
+                RpcRequest::SendTransaction => {
+                    sends.fetch_add(1, Ordering::SeqCst);
+                    Err("connection reset by peer".to_string())
+                }
+                _ => test_utils::buy_response(request, params),
+            })
+        };
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor = test_utils::scripted_executor(config, sender);
+        sniper.set_wallet(Keypair::new());
+
+        // the buy may have reached a leader, so sending it again risks a double buy
+        sniper
+            .handle_buy_trigger(test_token(), 0, 10_000_000, BuySource::Cached)
+            .await
+            .unwrap();
+        assert_eq!(sends.load(Ordering::SeqCst), 1);
+        assert_eq!(sniper.failed_buys, 1);
+        assert_eq!(sniper.successful_buys, 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_buy_amount_in_usd() {
+        let config = Config {
+            buy_amount_usd: Some(15.0),
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor =
+            test_utils::scripted_executor(config, test_utils::buy_sender());
+        sniper.set_wallet(Keypair::new());
+        sniper.price_fetcher =
+            PriceFetcher::with_source(Box::new(utils::FixedPriceSource::new(0.0)));
+
+        // no usable price, no buy
+        let token = test_token();
+        sniper
+            .handle_buy_trigger(token.clone(), 0, 50_000_000, BuySource::Cached)
+            .await
+            .unwrap();
+        assert_eq!(sniper.successful_buys, 0);
+        assert!(!sniper.bought_tokens.contains(&token.mint.to_string()));
+
+        // $15 at $150 is 0.1 SOL, whatever the trigger carried
+        sniper.price_fetcher =
+            PriceFetcher::with_source(Box::new(utils::FixedPriceSource::new(150.0)));
+        sniper
+            .handle_buy_trigger(token.clone(), 0, 50_000_000, BuySource::Cached)
+            .await
+            .unwrap();
+        assert_eq!(sniper.successful_buys, 1);
+        assert_eq!(
+            sniper.positions[&token.mint.to_string()].entry_cost_lamports,
+            sniper.amount_spent_estimate(100_000_000)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_token_amount_cost_checked_before_caps() {
+        let token_amount = 3_000_000;
+        let fees = crate::accounts::CurveFees::default();
+        let cost = test_curve()
+            .get_buy_cost_for_tokens_with_fees(
+                token_amount,
+                fees.fee_basis_points,
+                fees.creator_fee_basis_points,
+            )
+            .unwrap();
+        let config = Config {
+            buy_token_amount: Some(token_amount),
+            max_total_spend_sol: Some(80_000_000),
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor =
+            test_utils::scripted_executor(config, test_utils::buy_sender());
+        sniper.set_wallet(Keypair::new());
+
+        // above BUY_AMOUNT_SOL, the default ceiling
+        assert!(cost > sniper.config.buy_amount_sol);
+        sniper
+            .handle_buy_trigger(test_token(), 0, 50_000_000, BuySource::Cached)
+            .await
+            .unwrap();
+        assert_eq!(sniper.successful_buys, 0);
+
+        // under the ceiling, but the solved cost is what the spend cap sees
+        sniper.config.buy_token_max_cost_sol = Some(200_000_000);
+        sniper
+            .handle_buy_trigger(test_token(), 0, 50_000_000, BuySource::Cached)
+            .await
+            .unwrap();
+        assert_eq!(sniper.successful_buys, 0);
+        assert!(std::iter::from_fn(|| sniper.event_receiver.try_recv().ok())
+            .any(|event| matches!(event, SniperEvent::SpendLimitReached { .. })));
+
+        sniper.config.max_total_spend_sol = Some(1_000_000_000);
+        let token = test_token();
+        sniper
+            .handle_buy_trigger(token.clone(), 0, 50_000_000, BuySource::Cached)
+            .await
+            .unwrap();
+        assert_eq!(sniper.successful_buys, 1);
+        assert_eq!(sniper.total_spent, sniper.amount_spent_estimate(cost));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_spend_records_dynamic_priority_fee() {
+        let config = Config {
+            dynamic_priority_fee: true,
+            max_priority_fee: 2_000_000,
+            ..test_utils::test_config()
+        };
+        let sender = ScriptedSender::with_params(|request, params, _| match request {
+            RpcRequest::GetRecentPrioritizationFees => {
+                Ok(serde_json::json!([{ "slot": 1, "prioritizationFee": 2_000_000 }]))
+            }
+            _ => test_utils::buy_response(request, params),
+        });
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor = test_utils::scripted_executor(config.clone(), sender);
+        sniper.set_wallet(Keypair::new());
+
+        let token = test_token();
+        sniper
+            .handle_buy_trigger(token.clone(), 0, 50_000_000, BuySource::Cached)
+            .await
+            .unwrap();
+
+        // 2 lamports per CU over the limit, not PRIORITY_FEE_SOL
+        let priority_fee = 2 * config.compute_unit_limit as u64;
+        assert_ne!(priority_fee, config.priority_fee_sol);
+        let snapshot = sniper.positions[&token.mint.to_string()]
+            .buy_snapshot
+            .unwrap();
+        assert_eq!(snapshot.priority_fee_lamports, priority_fee);
+        assert_eq!(
+            sniper.total_spent,
+            50_000_000 + priority_fee + constants::BASE_FEE_LAMPORTS
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_wallet_sizing_after_gates_and_reserves_fees() {
+        let balance = 2_000_000_000;
+        let config = Config {
+            buy_amount_pct: Some(100.0),
+            buy_cooldown_ms: 60_000,
+            dynamic_priority_fee: true,
+            max_priority_fee: 40_000_000,
+            jito_block_engine_url: Some("https://jito.example.com".to_string()),
+            jito_tip_lamports: 1_000_000,
+            ..test_utils::test_config()
+        };
+        let sender = ScriptedSender::new(move |request, _| match request {
+            RpcRequest::GetBalance => {
+                Ok(serde_json::json!({ "context": { "slot": 1 }, "value": balance }))
+            }
+            other => Err(format!("unexpected {other}")),
+        });
+        let balance_reads = sender.calls();
+        assert!(40 * config.compute_unit_limit as u64 > config.priority_fee_sol);
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor = test_utils::scripted_executor(config.clone(), sender);
+        sniper.set_wallet(Keypair::new());
+
+        // cooling down, so the wallet isn't read
+        sniper.last_buy_at = Some(Instant::now());
+        sniper
+            .handle_buy_trigger(test_token(), 0, 0, BuySource::Cached)
+            .await
+            .unwrap();
+        assert_eq!(balance_reads.load(Ordering::SeqCst), 0);
+
+        // 40 lamports per CU at the dynamic ceiling, plus the tip, base fee and ATA rent
+        let reserve = 40 * config.compute_unit_limit as u64
+            + 1_000_000
+            + constants::BASE_FEE_LAMPORTS
+            + constants::TOKEN_ACCOUNT_RENT_LAMPORTS;
+        assert_eq!(
+            sniper.buy_amount_from_wallet(100.0).await,
+            utils::buy_amount_from_balance(balance, 100.0, config.max_slippage_bps, reserve)
+        );
+        assert_eq!(balance_reads.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_exposure_cap_blocks_buys() {
+        let config = Config {
+            max_portfolio_exposure_sol: Some(5_000_000_000),
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor =
+            test_utils::scripted_executor(config, test_utils::buy_sender());
+        sniper.set_wallet(Keypair::new());
+        sniper.price_fetcher.set_cached_price(100.0);
+
+        let tokens = test_curve().get_buy_price(1_000_000_000).unwrap();
+        let pumped = open_position(&mut sniper, Some(tokens));
+        let curve = pumped_curve(60_000_000_000, 500_000_000);
+        sniper
+            .bonding_curve_cache
+            .insert(pumped.bonding_curve, curve.clone());
+        // no fill yet, counted at cost
+        open_position(&mut sniper, None);
+
+        let pumped_value = curve.get_sell_price(tokens, 100).unwrap();
+        let exposure = sniper.portfolio_exposure_sol();
+        assert_eq!(exposure, pumped_value + 1_000_000_000);
+        let usd = sniper.portfolio_exposure_usd().unwrap();
+        assert!((usd - exposure as f64 / 1e9 * 100.0).abs() < 1e-6);
+
+        // room for a buy up to the cap, not past it
+        let over = 5_000_000_000 - exposure + 1;
+        sniper
+            .handle_buy_trigger(test_token(), 0, over, BuySource::Cached)
+            .await
+            .unwrap();
+        assert_eq!(sniper.successful_buys, 0);
+
+        sniper
+            .handle_buy_trigger(test_token(), 0, over - 1, BuySource::Cached)
+            .await
+            .unwrap();
+        assert_eq!(sniper.successful_buys, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_spend_cap_stops_buys() {
+        let buy_amount = 100_000_000;
+        let config = Config {
+            priority_fee_sol: 1_000_000,
+            // room for two buys with fees, not three
+            max_total_spend_sol: Some(250_000_000),
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor =
+            test_utils::scripted_executor(config, test_utils::buy_sender());
+        sniper.set_wallet(Keypair::new());
+
+        for _ in 0..4 {
+            sniper
+                .handle_buy_trigger(test_token(), 0, buy_amount, BuySource::Cached)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(sniper.successful_buys, 2);
+        assert_eq!(sniper.total_spent, 2 * (buy_amount + 1_000_000 + 5_000));
+        let limit_events = std::iter::from_fn(|| sniper.event_receiver.try_recv().ok())
+            .filter(|event| matches!(event, SniperEvent::SpendLimitReached { .. }))
+            .count();
+        assert_eq!(limit_events, 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_cooldown_skips_rapid_second_buy() {
+        let config = Config {
+            buy_cooldown_ms: 60_000,
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor =
+            test_utils::scripted_executor(config, test_utils::buy_sender());
+        sniper.set_wallet(Keypair::new());
+        let (first, second) = (test_token(), test_token());
+
+        for token in [&first, &second] {
+            sniper
+                .handle_buy_trigger(token.clone(), 0, 1_000_000, BuySource::Cached)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(sniper.successful_buys, 1);
+        assert!(sniper.positions.contains_key(&first.mint.to_string()));
+        // skipped, not marked bought, so it can trigger again after the cooldown
+        assert!(!sniper.bought_tokens.contains(&second.mint.to_string()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_creator_buy_rate_limit_per_window() {
+        let config = Config {
+            max_buys_per_creator_per_window: Some(2),
+            creator_buy_window_seconds: 600,
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor =
+            test_utils::scripted_executor(config, test_utils::buy_sender());
+        sniper.set_wallet(Keypair::new());
+        let creator = Pubkey::new_unique();
+        let launch = || TokenInfo {
+            creator,
+            ..test_token()
+        };
+
+        let tokens: Vec<TokenInfo> = (0..4).map(|_| launch()).collect();
+        for token in &tokens {
+            sniper
+                .handle_buy_trigger(token.clone(), 0, 1_000_000, BuySource::Cached)
+                .await
+                .unwrap();
+        }
+        assert_eq!(sniper.successful_buys, 2);
+        assert!(!sniper.bought_tokens.contains(&tokens[2].mint.to_string()));
+        assert!(!sniper.bought_tokens.contains(&tokens[3].mint.to_string()));
+
+        // other creators aren't limited
+        sniper
+            .handle_buy_trigger(test_token(), 0, 1_000_000, BuySource::Cached)
+            .await
+            .unwrap();
+        assert_eq!(sniper.successful_buys, 3);
+
+        // once the window has passed the creator can be bought again
+        let later = Instant::now() + Duration::from_secs(600);
+        assert_eq!(sniper.recent_creator_buys(&creator, later), 0);
+        sniper
+            .handle_buy_trigger(tokens[2].clone(), 0, 1_000_000, BuySource::Cached)
+            .await
+            .unwrap();
+        assert_eq!(sniper.successful_buys, 4);
+        assert_eq!(sniper.creator_buys[&creator].len(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_token_age_window_gates_buys() {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let aged = |age: u64| TokenInfo {
+            created_at: now - age,
+            ..test_token()
+        };
+        let cases = [
+            (None, None, 5, true),
+            (None, None, 3_600, true),
+            (Some(10), Some(60), 5, false),
+            (Some(10), Some(60), 30, true),
+            (Some(10), Some(60), 120, false),
+        ];
+
+        for (min_token_age_secs, max_token_age_secs, age, bought) in cases {
+            let config = Config {
+                min_token_age_secs,
+                max_token_age_secs,
+                ..test_utils::test_config()
+            };
+            let mut sniper = Sniper::new(config.clone()).await.unwrap();
+            sniper.transaction_executor =
+                test_utils::scripted_executor(config, test_utils::buy_sender());
+            sniper.set_wallet(Keypair::new());
+            let token = aged(age);
+
+            sniper
+                .handle_buy_trigger(token.clone(), 0, 1_000_000, BuySource::Cached)
+                .await
+                .unwrap();
+
+            assert_eq!(sniper.successful_buys == 1, bought, "age {age}");
+            // skips leave the token free to trigger once it ages into the window
+            assert_eq!(
+                sniper.bought_tokens.contains(&token.mint.to_string()),
+                bought
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_buy_deferred_until_slots_since_creation() {
+        let config = Config {
+            min_slots_since_creation: Some(3),
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor =
+            test_utils::scripted_executor(config, test_utils::buy_sender());
+        sniper.set_wallet(Keypair::new());
+        let token = TokenInfo {
+            creation_slot: 1_000,
+            ..test_token()
+        };
+
+        for slot in [1_000, 1_002] {
+            sniper.stream_slot.store(slot, Ordering::Relaxed);
+            sniper
+                .handle_buy_trigger(token.clone(), 0, 1_000_000, BuySource::Instant)
+                .await
+                .unwrap();
+            assert_eq!(sniper.successful_buys, 0, "slot {slot}");
+            assert!(!sniper.bought_tokens.contains(&token.mint.to_string()));
+        }
+
+        sniper.stream_slot.store(1_003, Ordering::Relaxed);
+        sniper
+            .handle_buy_trigger(token.clone(), 0, 1_000_000, BuySource::Instant)
+            .await
+            .unwrap();
+        assert_eq!(sniper.successful_buys, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_confirmed_buy_without_balance_increase_fails() {
+        let config = Config {
+            confirm_timeout_secs: 5,
+            verify_buy_balance: true,
+            ..test_utils::test_config()
+        };
+        let wallet = Keypair::new();
+        let token = test_token();
+        let ata = spl_associated_token_account::get_associated_token_address(
+            &wallet.pubkey(),
+            &token.mint,
+        )
+        .to_string();
+        // confirms, but the token account never appears
+        let sender = ScriptedSender::with_params(move |request, params, _| match request {
+            RpcRequest::GetAccountInfo if params[0] == ata.as_str() => {
+                Ok(serde_json::json!({ "context": { "slot": 1 }, "value": null }))
+            }
+            RpcRequest::GetSignatureStatuses => Ok(serde_json::json!({
+                "context": { "slot": 1 },
+                "value": [{
+                    "slot": 42,
+                    "confirmations": 0,
+                    "status": { "Ok": null },
+                    "err": null,
+                    "confirmationStatus": "confirmed",
+                }]
+            })),
+            _ => test_utils::buy_response(request, params),
+        });
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor = test_utils::scripted_executor(config, sender);
+        sniper.set_wallet(wallet);
+
+        sniper
+            .handle_buy_trigger(token.clone(), 0, 1_000_000, BuySource::Cached)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            sniper.event_receiver.try_recv(),
+            Ok(SniperEvent::BuyFailed { error, .. }) if error.contains("balance grew by 0")
+        ));
+        assert_eq!((sniper.successful_buys, sniper.failed_buys), (0, 1));
+        assert!(sniper.positions.is_empty());
+        assert!(!sniper.bought_tokens.contains(&token.mint.to_string()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_buy_balance_checked_against_instruction_minimum() {
+        use solana_program::program_pack::Pack;
+
+        let config = Config {
+            confirm_timeout_secs: 5,
+            verify_buy_balance: true,
+            ..test_utils::test_config()
+        };
+        let buy_amount = 1_000_000;
+        let global = test_utils::test_global();
+        let min_tokens_out = utils::min_token_output(
+            test_curve()
+                .get_buy_price_with_fees(
+                    buy_amount,
+                    global.fee_basis_points,
+                    global.creator_fee_basis_points,
+                )
+                .unwrap(),
+            config.max_slippage_bps,
+        );
+
+        // no quote of its own, so only the instruction's minimum can fail these fills
+        for (filled, succeeds) in [(min_tokens_out - 1, false), (min_tokens_out, true)] {
+            let wallet = Keypair::new();
+            let token = test_token();
+            let ata = spl_associated_token_account::get_associated_token_address(
+                &wallet.pubkey(),
+                &token.mint,
+            )
+            .to_string();
+            let token_account = spl_token::state::Account {
+                mint: token.mint,
+                owner: wallet.pubkey(),
+                amount: filled,
+                state: spl_token::state::AccountState::Initialized,
+                ..Default::default()
+            };
+            let mut data = vec![0; spl_token::state::Account::LEN];
+            spl_token::state::Account::pack(token_account, &mut data).unwrap();
+            let ata_reads = AtomicUsize::new(0);
+            // empty before the buy, filled after
+            let sender = ScriptedSender::with_params(move |request, params, _| match request {
+                RpcRequest::GetAccountInfo if params[0] == ata.as_str() => {
+                    if ata_reads.fetch_add(1, Ordering::SeqCst) == 0 {
+                        Ok(serde_json::json!({ "context": { "slot": 1 }, "value": null }))
+                    } else {
+                        Ok(test_utils::account_response_owned_by(
+                            &data,
+                            &spl_token::id().to_string(),
+                        ))
+                    }
+                }
+                RpcRequest::GetSignatureStatuses => Ok(serde_json::json!({
+                    "context": { "slot": 1 },
+                    "value": [{
+                        "slot": 42,
+                        "confirmations": 0,
+                        "status": { "Ok": null },
+                        "err": null,
+                        "confirmationStatus": "confirmed",
+                    }]
+                })),
+                _ => test_utils::buy_response(request, params),
+            });
+            let mut sniper = Sniper::new(config.clone()).await.unwrap();
+            sniper.transaction_executor = test_utils::scripted_executor(config.clone(), sender);
+            sniper.set_wallet(wallet);
+
+            sniper
+                .handle_buy_trigger(token.clone(), 0, buy_amount, BuySource::Cached)
+                .await
+                .unwrap();
+
+            assert_eq!(sniper.successful_buys == 1, succeeds);
+            assert_eq!(
+                sniper.positions.contains_key(&token.mint.to_string()),
+                succeeds
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dev_holdings_read_from_create_transaction() {
+        let config = Config {
+            max_dev_holding_pct: Some(10.0),
+            ..test_utils::test_config()
+        };
+        // the RPC is unreachable, so only the create transaction's balances can pass a token
+        let sniper = Sniper::new(config).await.unwrap();
+        let holding = |tokens| TokenInfo {
+            creator_initial_tokens: tokens,
+            ..test_token()
+        };
+
+        assert!(sniper.dev_holdings_allowed(&holding(Some(0))).await);
+        assert!(
+            !sniper
+                .dev_holdings_allowed(&holding(Some(constants::TOKEN_TOTAL_SUPPLY / 5)))
+                .await
+        );
+        assert!(!sniper.dev_holdings_allowed(&holding(None)).await);
+    }
+
+    #[tokio::test]
+    async fn test_switch_between_named_strategies() {
+        let path = std::env::temp_dir().join(format!(
+            "pump-sniper-strategies-{}.json",
+            Pubkey::new_unique()
+        ));
+        std::fs::write(
+            &path,
+            r#"{
+                "aggressive": { "market_cap_threshold_usd": 5000.0, "buy_amount_sol": 100000000 },
+                "conservative": { "market_cap_threshold_usd": 50000.0 }
+            }"#,
+        )
+        .unwrap();
+        let config = Config {
+            strategies_file: Some(path.to_string_lossy().into_owned()),
+            active_strategy: Some("conservative".to_string()),
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let (token, curve) = (test_token(), test_curve());
+
+        assert_eq!(sniper.active_strategy(), Some("conservative"));
+        assert!(!sniper.meets_buy_criteria(&token, &curve, 30_000.0));
+
+        sniper.set_active_strategy("aggressive").unwrap();
+        assert!(sniper.meets_buy_criteria(&token, &curve, 30_000.0));
+        assert_eq!(sniper.config.buy_amount_sol, 100_000_000);
+
+        // unknown names leave the active strategy alone
+        assert!(sniper.set_active_strategy("yolo").is_err());
+        assert_eq!(sniper.active_strategy(), Some("aggressive"));
+
+        sniper.set_active_strategy("conservative").unwrap();
+        assert!(!sniper.meets_buy_criteria(&token, &curve, 30_000.0));
+        assert_eq!(
+            sniper.config.buy_amount_sol,
+            test_utils::test_config().buy_amount_sol
+        );
+    }
+
+    #[tokio::test]
+    async fn test_admin_socket_commands() {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let mut sniper = test_sniper().await;
+        let address = sniper.start_admin_listener("127.0.0.1:0").await.unwrap();
+        let running = tokio::spawn(async move {
+            sniper.process_events().await.unwrap();
+            sniper
+        });
+
+        let stream = tokio::net::TcpStream::connect(&address).await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        writer.write_all(b"stats\n").await.unwrap();
+        let stats = lines.next_line().await.unwrap().unwrap();
+        let fields: Vec<(&str, &str)> = stats
+            .split(' ')
+            .map(|field| field.split_once('=').unwrap())
+            .collect();
+        let keys: Vec<&str> = fields.iter().map(|(key, _)| *key).collect();
+        assert_eq!(
+            keys,
+            [
+                "tracked",
+                "bought",
+                "successful_buys",
+                "failed_buys",
+                "positions",
+                "paused",
+                "uptime_seconds",
+                "max_drawdown_pct"
+            ]
+        );
+        assert_eq!(fields[5], ("paused", "false"));
+
+        writer.write_all(b"pause\n").await.unwrap();
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), "paused");
+        writer.write_all(b"bogus\n").await.unwrap();
+        assert!(lines
+            .next_line()
+            .await
+            .unwrap()
+            .unwrap()
+            .starts_with("error:"));
+        writer.write_all(b"stop\n").await.unwrap();
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), "stopping");
+
+        let sniper = tokio::time::timeout(Duration::from_secs(1), running)
+            .await
+            .expect("event loop stopped")
+            .unwrap();
+        assert!(sniper.buys_paused);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_stop_returns_from_start() {
+        // a capture whose second slot is hours away stands in for a live stream
+        let path =
+            std::env::temp_dir().join(format!("pump-sniper-stop-{}.bin", Pubkey::new_unique()));
+        let slot = |slot| yellowstone_grpc_proto::prelude::SubscribeUpdate {
+            update_oneof: Some(
+                yellowstone_grpc_proto::prelude::subscribe_update::UpdateOneof::Slot(
+                    yellowstone_grpc_proto::prelude::SubscribeUpdateSlot {
+                        slot,
+                        ..Default::default()
+                    },
+                ),
+            ),
+            ..Default::default()
+        };
+        let capture: Vec<u8> = [slot(1), slot(100_000)]
+            .iter()
+            .flat_map(prost::Message::encode_length_delimited_to_vec)
+            .collect();
+        std::fs::write(&path, capture).unwrap();
+        let config = Config {
+            replay_path: Some(path.to_string_lossy().into_owned()),
+            replay_speed: 1.0,
+            fetch_retry_delays_ms: vec![0],
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config).await.unwrap();
+        sniper.price_fetcher.set_cached_price(1000.0);
+        sniper.enable_dry_run();
+        let handle = sniper.stop_handle();
+        let mut events = sniper.subscribe();
+        assert!(!handle.is_running());
+        // a stop before the run isn't carried into it
+        handle.stop();
+
+        let stopper = async {
+            let first = common::recv_event(&mut events).await.unwrap();
+            assert!(matches!(
+                first.event,
+                SniperEvent::ConnectionStatusChanged {
+                    connected: true,
+                    ..
+                }
+            ));
+            assert!(handle.is_running());
+            handle.stop();
+        };
+        let (result, ()) = tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::join!(sniper.start(), stopper)
+        })
+        .await
+        .expect("start returned after stop");
+        result.unwrap();
+        assert!(!sniper.is_running());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_replay_end_returns_from_start() {
+        let path =
+            std::env::temp_dir().join(format!("pump-sniper-end-{}.bin", Pubkey::new_unique()));
+        std::fs::write(&path, []).unwrap();
+        let config = Config {
+            grpc_endpoint: String::new(),
+            replay_path: Some(path.to_string_lossy().into_owned()),
+            replay_speed: 0.0,
+            fetch_retry_delays_ms: vec![0],
+            ..test_utils::test_config()
+        };
+        // no wallet and no dry run flag: replaying simulates buys by itself
+        let mut sniper = Sniper::new(config).await.unwrap();
+        sniper.price_fetcher.set_cached_price(1000.0);
+
+        tokio::time::timeout(Duration::from_secs(5), sniper.start())
+            .await
+            .expect("start returned at the end of the capture")
+            .unwrap();
+        assert!(sniper.dry_run);
+        assert!(!sniper.is_running());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_ends_event_loop() {
+        let mut sniper = test_sniper().await;
+        sniper.shutdown.notify_one();
+
+        tokio::time::timeout(Duration::from_secs(1), sniper.process_events())
+            .await
+            .expect("event loop stopped")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fill_reports_realized_slippage() {
+        let mut sniper = test_sniper().await;
+        let token = open_position(&mut sniper, None);
+        let mint_str = token.mint.to_string();
+        sniper.positions.get_mut(&mint_str).unwrap().expected_tokens = Some(1_000_000);
+
+        sniper.record_fill(&mint_str, 980_000);
+        assert_eq!(sniper.positions[&mint_str].tokens_received, Some(980_000));
+        assert!(matches!(
+            sniper.event_receiver.try_recv(),
+            Ok(SniperEvent::BuyExecuted {
+                realized_slippage_bps: Some(200),
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fill_check_throttled() {
+        let mut sniper = test_sniper().await;
+        // the buy hasn't landed, so the wallet has no token account yet
+        let sender = ScriptedSender::new(|_, _| {
+            Ok(serde_json::json!({ "context": { "slot": 1 }, "value": null }))
+        });
+        let calls = sender.calls();
+        sniper.transaction_executor =
+            test_utils::scripted_executor(test_utils::test_config(), sender);
+        sniper.set_wallet(Keypair::new());
+        let token = open_position(&mut sniper, None);
+
+        for _ in 0..5 {
+            sniper
+                .handle_bonding_curve_update(token.bonding_curve, test_curve())
+                .await
+                .unwrap();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let mint_str = token.mint.to_string();
+        let position = sniper.positions.get_mut(&mint_str).unwrap();
+        position.last_fill_check = Some(Instant::now() - FILL_CHECK_INTERVAL);
+        sniper
+            .handle_bonding_curve_update(token.bonding_curve, test_curve())
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_take_profit_fires_sell() {
+        let config = Config {
+            take_profit_bps: Some(5_000),
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config).await.unwrap();
+        let tokens = test_curve().get_buy_price(1_000_000_000).unwrap();
+        let token = open_position(&mut sniper, Some(tokens));
+
+        // flat curve, no trigger
+        sniper
+            .handle_bonding_curve_update(token.bonding_curve, test_curve())
+            .await
+            .unwrap();
+        assert!(sniper.event_receiver.try_recv().is_err());
+
+        sniper
+            .handle_bonding_curve_update(
+                token.bonding_curve,
+                pumped_curve(60_000_000_000, 500_000_000),
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            sniper.event_receiver.try_recv(),
+            Ok(SniperEvent::SellTriggered { token_amount, .. }) if token_amount == tokens
+        ));
+
+        // pending sell isn't re-fired
+        sniper
+            .handle_bonding_curve_update(
+                token.bonding_curve,
+                pumped_curve(70_000_000_000, 400_000_000),
+            )
+            .await
+            .unwrap();
+        assert!(sniper.event_receiver.try_recv().is_err());
+    }
+
+    /// Take the result of the one sell in flight, as the event loop would
+    async fn settle_sell(sniper: &mut Sniper) {
+        let attempt = sniper.sell_result_receiver.recv().await.unwrap();
+        sniper.in_flight_sells -= 1;
+        sniper.finish_sell(attempt);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sell_runs_off_event_loop() {
+        let sender = ScriptedSender::with_params(|request, params, _| match request {
+            RpcRequest::GetSignatureStatuses => {
+                let err = serde_json::json!({ "InstructionError": [2, { "Custom": 6003 }] });
+                Ok(serde_json::json!({
+                    "context": { "slot": 1 },
+                    "value": [{
+                        "slot": 42,
+                        "confirmations": 0,
+                        "status": { "Err": err },
+                        "err": err,
+                        "confirmationStatus": "confirmed",
+                    }],
+                }))
+            }
+            other => test_utils::buy_response(other, params),
+        })
+        .with_delay(Duration::from_millis(200));
+        let config = test_utils::test_config();
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor = test_utils::scripted_executor(config, sender);
+        sniper.set_wallet(Keypair::new());
+        let tokens = test_curve().get_buy_price(1_000_000_000).unwrap();
+        let token = open_position(&mut sniper, Some(tokens));
+        let mint = token.mint.to_string();
+
+        // the trigger returns while the sell is still being sent
+        sniper.positions.get_mut(&mint).unwrap().sell_pending = true;
+        sniper
+            .handle_sell_trigger(token, tokens, 1_000_000_000, SellReason::Manual)
+            .await
+            .unwrap();
+        assert_eq!(sniper.in_flight_sells, 1);
+        assert!(sniper.sell_result_receiver.try_recv().is_err());
+        assert!(sniper.positions[&mint].sell_pending);
+
+        // reverted on-chain, so the position is handed back unsold
+        sniper.drain_in_flight().await;
+        assert_eq!(sniper.in_flight_sells, 0);
+        assert!(!sniper.positions[&mint].sell_pending);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_position_closed_only_on_confirmed_sell() {
+        let reverted = Arc::new(AtomicBool::new(true));
+        let status_reverted = reverted.clone();
+        let sender = ScriptedSender::with_params(move |request, params, _| match request {
+            RpcRequest::GetSignatureStatuses => {
+                let (status, err) = if status_reverted.load(Ordering::SeqCst) {
+                    let err = serde_json::json!({ "InstructionError": [2, { "Custom": 6003 }] });
+                    (serde_json::json!({ "Err": err }), err)
+                } else {
+                    (serde_json::json!({ "Ok": null }), serde_json::Value::Null)
+                };
+                Ok(serde_json::json!({
+                    "context": { "slot": 1 },
+                    "value": [{
+                        "slot": 42,
+                        "confirmations": 0,
+                        "status": status,
+                        "err": err,
+                        "confirmationStatus": "confirmed",
+                    }],
+                }))
+            }
+            other => test_utils::buy_response(other, params),
+        });
+        let config = test_utils::test_config();
+        let mut sniper = Sniper::new(config.clone()).await.unwrap();
+        sniper.transaction_executor = test_utils::scripted_executor(config, sender);
+        sniper.set_wallet(Keypair::new());
+        let tokens = test_curve().get_buy_price(1_000_000_000).unwrap();
+        let token = open_position(&mut sniper, Some(tokens));
+        let mint = token.mint.to_string();
+
+        // reverted on-chain: the tokens are still held, so the position stays
+        sniper.positions.get_mut(&mint).unwrap().sell_pending = true;
+        sniper
+            .handle_sell_trigger(token.clone(), tokens, 1_000_000_000, SellReason::Manual)
+            .await
+            .unwrap();
+        settle_sell(&mut sniper).await;
+        assert!(!sniper.positions[&mint].sell_pending);
+
+        reverted.store(false, Ordering::SeqCst);
+        sniper.positions.get_mut(&mint).unwrap().sell_pending = true;
+        sniper
+            .handle_sell_trigger(token, tokens, 1_000_000_000, SellReason::Manual)
+            .await
+            .unwrap();
+        settle_sell(&mut sniper).await;
+        assert!(!sniper.positions.contains_key(&mint));
+    }
+
+    #[tokio::test]
+    async fn test_take_profit_waits_for_usd_floor() {
+        let config = Config {
+            take_profit_bps: Some(5_000),
+            min_net_profit_usd: Some(100.0),
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config).await.unwrap();
+        let tokens = test_curve().get_buy_price(1_000_000_000).unwrap();
+        let token = open_position(&mut sniper, Some(tokens));
+
+        // ~2.5 SOL up is well past take profit, but only ~$25 at $10 SOL
+        sniper.price_fetcher.set_cached_price(10.0);
+        sniper
+            .handle_bonding_curve_update(
+                token.bonding_curve,
+                pumped_curve(60_000_000_000, 500_000_000),
+            )
+            .await
+            .unwrap();
+        assert!(sniper.event_receiver.try_recv().is_err());
+
+        sniper.price_fetcher.set_cached_price(150.0);
+        sniper
+            .handle_bonding_curve_update(
+                token.bonding_curve,
+                pumped_curve(60_000_000_000, 400_000_000),
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            sniper.event_receiver.try_recv(),
+            Ok(SniperEvent::SellTriggered {
+                reason: SellReason::TakeProfit,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stop_loss_fires_once_on_descending_curve() {
+        let config = Config {
+            take_profit_bps: Some(5_000),
+            stop_loss_bps: Some(2_000),
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config).await.unwrap();
+        let tokens = test_curve().get_buy_price(1_000_000_000).unwrap();
+        let token = open_position(&mut sniper, Some(tokens));
+
+        let mut sells = Vec::new();
+        for virtual_sol in [30, 28, 26, 24, 22, 20] {
+            sniper
+                .handle_bonding_curve_update(
+                    token.bonding_curve,
+                    pumped_curve(virtual_sol * 1_000_000_000, 1_000_000_000),
+                )
+                .await
+                .unwrap();
+            while let Ok(event) = sniper.event_receiver.try_recv() {
+                if let SniperEvent::SellTriggered { reason, .. } = event {
+                    sells.push((virtual_sol, reason));
+                }
+            }
+        }
+
+        // value falls ~6% per step from -7%, crossing -20% at 24 SOL
+        assert_eq!(sells, vec![(24, SellReason::StopLoss)]);
+    }
+
+    #[tokio::test]
+    async fn test_take_profit_skips_complete_or_unconfirmed() {
+        let config = Config {
+            take_profit_bps: Some(5_000),
+            ..test_utils::test_config()
+        };
+        let mut sniper = Sniper::new(config).await.unwrap();
+        let tokens = test_curve().get_buy_price(1_000_000_000).unwrap();
+
+        let completed = open_position(&mut sniper, Some(tokens));
+        let mut curve = pumped_curve(60_000_000_000, 500_000_000);
+        curve.complete = true;
+        sniper
+            .handle_bonding_curve_update(completed.bonding_curve, curve)
+            .await
+            .unwrap();
+        assert!(sniper.event_receiver.try_recv().is_err());
+
+        let unconfirmed = open_position(&mut sniper, None);
+        sniper
+            .handle_bonding_curve_update(
+                unconfirmed.bonding_curve,
+                pumped_curve(60_000_000_000, 500_000_000),
+            )
+            .await
+            .unwrap();
+        assert!(sniper.event_receiver.try_recv().is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_rpc_path_source() {
+        let mut sniper = test_sniper().await;
+        let data = borsh::to_vec(&test_curve()).unwrap();
+        let mut mocks = Mocks::new();
+        mocks.insert(
+            RpcRequest::GetAccountInfo,
+            test_utils::account_response(&data),
+        );
+        sniper.transaction_executor = test_utils::mock_executor(test_utils::test_config(), mocks);
+
+        sniper
+            .handle_token_creation(seen_secs_ago(5))
+            .await
+            .unwrap();
+
+        assert_eq!(next_trigger_source(&mut sniper), Some(BuySource::Rpc));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_rug_signals_from_creation_data() {
+        let mut sniper = test_sniper().await;
+        let sender = ScriptedSender::new(|_, _| Err("unexpected RPC read".to_string()));
+        let calls = sender.calls();
+        sniper.transaction_executor =
+            test_utils::scripted_executor(test_utils::test_config(), sender);
+
+        // holdings from the create transaction and a cached curve need no RPC
+        let token = TokenInfo {
+            creator_initial_tokens: Some(test_curve().token_total_supply / 10),
+            uri: String::new(),
+            ..test_token()
+        };
+        sniper
+            .bonding_curve_cache
+            .insert(token.bonding_curve, test_curve());
+        let signals = sniper.rug_signals(&token).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert!(signals.curve.is_some());
+        assert!(signals.metadata.is_none());
+        let pct = signals.dev_holding_pct.unwrap();
+        assert!((pct - 10.0).abs() < 1e-9, "{}", pct);
+
+        // without them the holdings are unknown rather than read one by one
+        let token = TokenInfo {
+            creator_initial_tokens: None,
+            ..token
+        };
+        assert_eq!(sniper.rug_signals(&token).await.dev_holding_pct, None);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fresh_token_skips_rpc_fallback() {
+        let mut sniper = test_sniper().await;
+        let data = borsh::to_vec(&test_curve()).unwrap();
+        let sender = ScriptedSender::new(move |_, _| Ok(test_utils::account_response(&data)));
+        let calls = sender.calls();
+        sniper.transaction_executor =
+            test_utils::scripted_executor(test_utils::test_config(), sender);
+
+        sniper.handle_token_creation(test_token()).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert_eq!(next_trigger_source(&mut sniper), None);
+
+        // from the minimum age on, the fallback reads the curve
+        sniper
+            .handle_token_creation(seen_secs_ago(RPC_FALLBACK_MIN_AGE_SECS))
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(next_trigger_source(&mut sniper), Some(BuySource::Rpc));
+    }
+
+    #[tokio::test]
+    async fn test_fresh_token_threshold_boundary() {
+        let mut sniper = test_sniper().await;
+        let token = test_token();
+        let market_cap_sol = sniper
+            .market_data(&token, &test_curve())
+            .current_market_cap_sol;
+        let market_cap_usd = sniper
+            .price_fetcher
+            .calculate_market_cap_usd(market_cap_sol)
+            .await
+            .unwrap();
+        let cases = [
+            (market_cap_usd + 0.01, None),
+            (market_cap_usd, Some(BuySource::Cached)),
+            (market_cap_usd - 0.01, Some(BuySource::Cached)),
+        ];
+        for (threshold, expected) in cases {
+            sniper.config.market_cap_threshold_usd = threshold;
+            let token = test_token();
+            sniper
+                .bonding_curve_cache
+                .insert(token.bonding_curve, test_curve());
+            sniper.handle_token_creation(token).await.unwrap();
+            assert_eq!(next_trigger_source(&mut sniper), expected, "{}", threshold);
+        }
+    }
 }
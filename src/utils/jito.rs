@@ -0,0 +1,170 @@
+//! Jito bundle submission
+
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use serde_json::{json, Value};
+use solana_sdk::{
+    hash::Hash, instruction::Instruction, pubkey::Pubkey, signature::Keypair, signer::Signer,
+    system_instruction, transaction::Transaction,
+};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Mainnet tip accounts; any one of them credits the tip
+pub const JITO_TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+/// Bundle submissions give up after this long
+const BUNDLE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Tip account for the `index`th bundle, rotating through the set
+pub fn tip_account(index: usize) -> Pubkey {
+    Pubkey::from_str(JITO_TIP_ACCOUNTS[index % JITO_TIP_ACCOUNTS.len()]).expect("valid tip account")
+}
+
+/// Transfer of `tip_lamports` from `payer` to `tip_account`
+pub fn tip_instruction(payer: &Pubkey, tip_account: &Pubkey, tip_lamports: u64) -> Instruction {
+    system_instruction::transfer(payer, tip_account, tip_lamports)
+}
+
+/// Signed tip transaction to close out a bundle
+pub fn tip_transaction(
+    payer: &Keypair,
+    tip_account: &Pubkey,
+    tip_lamports: u64,
+    recent_blockhash: Hash,
+) -> Transaction {
+    Transaction::new_signed_with_payer(
+        &[tip_instruction(&payer.pubkey(), tip_account, tip_lamports)],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    )
+}
+
+/// JSON-RPC `sendBundle` body with base64 encoded transactions, executed in order
+pub fn bundle_request_body(transactions: &[Transaction]) -> Result<Value> {
+    let encoded = transactions
+        .iter()
+        .map(|transaction| {
+            bincode::serialize(transaction)
+                .map(|wire| base64::engine::general_purpose::STANDARD.encode(wire))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "sendBundle",
+        "params": [encoded, {"encoding": "base64"}],
+    }))
+}
+
+/// Sends bundles to a Jito block engine
+#[derive(Clone)]
+pub struct JitoExecutor {
+    client: Arc<reqwest::Client>,
+    bundles_url: String,
+}
+
+impl JitoExecutor {
+    /// `block_engine_url` is the engine's base URL, e.g. `https://mainnet.block-engine.jito.wtf`
+    pub fn new(client: Arc<reqwest::Client>, block_engine_url: &str) -> Self {
+        Self {
+            client,
+            bundles_url: format!("{}/api/v1/bundles", block_engine_url.trim_end_matches('/')),
+        }
+    }
+
+    /// Submit `transactions` as one bundle, returning the bundle id
+    pub async fn send_bundle(&self, transactions: &[Transaction]) -> Result<String> {
+        let body = bundle_request_body(transactions)?;
+        let response = self
+            .client
+            .post(&self.bundles_url)
+            .timeout(BUNDLE_TIMEOUT)
+            .json(&body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Jito block engine error: {}", response.status()));
+        }
+
+        let reply: Value = response.json().await?;
+        if let Some(error) = reply.get("error") {
+            return Err(anyhow!("Jito rejected bundle: {}", error));
+        }
+        reply
+            .get("result")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Jito response has no bundle id"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::system_program;
+
+    #[test]
+    fn test_tip_instruction() {
+        let payer = Pubkey::new_unique();
+        let tip = tip_account(9);
+        assert_eq!(tip, tip_account(1));
+        assert_ne!(tip_account(0), tip_account(1));
+
+        let instruction = tip_instruction(&payer, &tip, 10_000);
+        assert_eq!(instruction.program_id, system_program::id());
+        assert_eq!(instruction.accounts[0].pubkey, payer);
+        assert!(instruction.accounts[0].is_signer);
+        assert_eq!(instruction.accounts[1].pubkey, tip);
+        assert!(instruction.accounts[1].is_writable);
+        // system transfer: u32 index 2, then u64 lamports
+        assert_eq!(instruction.data[..4], 2u32.to_le_bytes());
+        assert_eq!(instruction.data[4..], 10_000u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_bundle_body() {
+        let payer = Keypair::new();
+        let blockhash = Hash::new_unique();
+        let tip = tip_transaction(&payer, &tip_account(0), 5_000, blockhash);
+        let other = tip_transaction(&payer, &tip_account(1), 6_000, blockhash);
+
+        let body = bundle_request_body(&[other.clone(), tip.clone()]).unwrap();
+        assert_eq!(body["method"], "sendBundle");
+        assert_eq!(body["params"][1]["encoding"], "base64");
+
+        let encoded = body["params"][0].as_array().unwrap();
+        assert_eq!(encoded.len(), 2);
+        let decoded: Transaction = bincode::deserialize(
+            &base64::engine::general_purpose::STANDARD
+                .decode(encoded[1].as_str().unwrap())
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(decoded, tip);
+        assert!(decoded.verify().is_ok());
+    }
+
+    #[test]
+    fn test_bundles_url() {
+        let client = Arc::new(reqwest::Client::new());
+        let jito = JitoExecutor::new(client, "https://mainnet.block-engine.jito.wtf/");
+        assert_eq!(
+            jito.bundles_url,
+            "https://mainnet.block-engine.jito.wtf/api/v1/bundles"
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! Off-chain token metadata
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Public gateway `ipfs://` URIs are resolved through
+pub const DEFAULT_IPFS_GATEWAY: &str = "https://ipfs.io/ipfs/";
+
+/// Metadata fetches give up after this long
+const METADATA_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Field names `METADATA_REQUIRED_FIELDS` accepts; `socials` means any of twitter,
+/// telegram or website
+pub const METADATA_FIELDS: &[&str] = &[
+    "name",
+    "symbol",
+    "description",
+    "image",
+    "twitter",
+    "telegram",
+    "website",
+    "socials",
+];
+
+/// Metadata JSON a create's URI points at
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub twitter: Option<String>,
+    pub telegram: Option<String>,
+    pub website: Option<String>,
+}
+
+impl TokenMetadata {
+    /// Whether `field` (one of `METADATA_FIELDS`) is present and non-blank
+    pub fn has_field(&self, field: &str) -> bool {
+        let filled =
+            |value: &Option<String>| value.as_deref().is_some_and(|v| !v.trim().is_empty());
+        match field {
+            "name" => !self.name.trim().is_empty(),
+            "symbol" => !self.symbol.trim().is_empty(),
+            "description" => filled(&self.description),
+            "image" => filled(&self.image),
+            "twitter" => filled(&self.twitter),
+            "telegram" => filled(&self.telegram),
+            "website" => filled(&self.website),
+            "socials" => filled(&self.twitter) || filled(&self.telegram) || filled(&self.website),
+            _ => false,
+        }
+    }
+
+    /// Required fields that are missing or blank
+    pub fn missing_fields<'a>(&self, required: &'a [String]) -> Vec<&'a str> {
+        required
+            .iter()
+            .map(String::as_str)
+            .filter(|field| !self.has_field(field))
+            .collect()
+    }
+}
+
+/// Fetches metadata JSON over HTTP(S), resolving `ipfs://` through a gateway
+pub struct MetadataFetcher {
+    client: Arc<reqwest::Client>,
+    ipfs_gateway: String,
+    timeout: Duration,
+}
+
+impl MetadataFetcher {
+    pub fn new(client: Arc<reqwest::Client>) -> Self {
+        Self {
+            client,
+            ipfs_gateway: DEFAULT_IPFS_GATEWAY.to_string(),
+            timeout: METADATA_TIMEOUT,
+        }
+    }
+
+    /// Use `gateway` (ending in `/ipfs/`) for `ipfs://` URIs
+    pub fn with_ipfs_gateway(mut self, gateway: impl Into<String>) -> Self {
+        self.ipfs_gateway = gateway.into();
+        self
+    }
+
+    /// HTTP URL to fetch for `uri`
+    pub fn resolve(&self, uri: &str) -> Result<String> {
+        let uri = uri.trim();
+        if let Some(path) = uri.strip_prefix("ipfs://") {
+            let path = path.trim_start_matches("ipfs/");
+            return Ok(format!("{}{}", self.ipfs_gateway, path));
+        }
+
+        let lower = uri.to_ascii_lowercase();
+        if lower.starts_with("https://") || lower.starts_with("http://") {
+            Ok(uri.to_string())
+        } else {
+            Err(anyhow!("Unsupported metadata URI: {:?}", uri))
+        }
+    }
+
+    pub async fn fetch(&self, uri: &str) -> Result<TokenMetadata> {
+        let url = self.resolve(uri)?;
+
+        let response = self.client.get(&url).timeout(self.timeout).send().await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Metadata fetch error: {}", response.status()));
+        }
+
+        // gateways often mislabel content types, so judge by the body
+        let body = response.bytes().await?;
+        serde_json::from_slice(&body).map_err(|e| anyhow!("Metadata is not valid JSON: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve `body` to every request on a local port, returning the base URL
+    async fn serve(status: &'static str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{}/", addr)
+    }
+
+    fn fetcher() -> MetadataFetcher {
+        MetadataFetcher::new(Arc::new(reqwest::Client::new()))
+    }
+
+    #[tokio::test]
+    async fn test_fetch_metadata_from_server() {
+        let url = serve(
+            "200 OK",
+            r#"{"name":"Test","symbol":"TEST","image":"https://example.com/i.png","twitter":"","showName":true}"#,
+        )
+        .await;
+        let metadata = fetcher().fetch(&format!("{}meta.json", url)).await.unwrap();
+        assert_eq!(metadata.symbol, "TEST");
+
+        let required = ["image", "socials", "description"].map(String::from);
+        assert_eq!(
+            metadata.missing_fields(&required),
+            vec!["socials", "description"]
+        );
+
+        // ipfs URIs go through the gateway
+        let gateway = fetcher().with_ipfs_gateway(format!("{}ipfs/", url));
+        assert!(gateway.fetch("ipfs://QmHash").await.is_ok());
+
+        let html = serve("200 OK", "<html>rate limited</html>").await;
+        let error = fetcher().fetch(&html).await.unwrap_err();
+        assert!(error.to_string().contains("not valid JSON"));
+
+        let missing = serve("404 Not Found", "").await;
+        assert!(fetcher().fetch(&missing).await.is_err());
+    }
+
+    #[test]
+    fn test_resolve_uri() {
+        let fetcher = fetcher();
+        assert_eq!(
+            fetcher.resolve("ipfs://QmHash").unwrap(),
+            "https://ipfs.io/ipfs/QmHash"
+        );
+        assert_eq!(
+            fetcher.resolve("ipfs://ipfs/QmHash").unwrap(),
+            "https://ipfs.io/ipfs/QmHash"
+        );
+        assert_eq!(
+            fetcher.resolve(" https://example.com/m.json").unwrap(),
+            "https://example.com/m.json"
+        );
+        assert!(fetcher.resolve("data:application/json,{}").is_err());
+        assert!(fetcher.resolve("").is_err());
+    }
+}
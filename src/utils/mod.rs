@@ -1,11 +1,26 @@
 //! Utils
 
+pub mod jito;
+pub mod metadata;
 pub mod parser;
 pub mod pda;
 pub mod price;
+pub mod rate_limiter;
+pub mod rpc_pool;
+pub mod rug_checker;
 pub mod transaction;
+pub mod webhook;
 
+#[cfg(test)]
+pub(crate) mod test_utils;
+
+pub use jito::*;
+pub use metadata::*;
 pub use parser::*;
 pub use pda::*;
 pub use price::*;
+pub use rate_limiter::*;
+pub use rpc_pool::*;
+pub use rug_checker::*;
 pub use transaction::*;
+pub use webhook::*;
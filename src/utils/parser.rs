@@ -1,13 +1,101 @@
 //! Transaction parsing utils
 
 use crate::{
-    accounts::TokenInfo, constants::CREATE_DISCRIMINATOR, instructions::CreateInstruction,
+    accounts::TokenInfo,
+    common::SniperEvent,
+    constants::{accounts, BUY_DISCRIMINATOR, CREATE_DISCRIMINATOR, SELL_DISCRIMINATOR},
+    instructions::{BuyInstruction, CreateInstruction, SellInstruction},
     utils::pda::derive_bonding_curve_pda,
 };
+use borsh::BorshDeserialize;
 use solana_sdk::pubkey::Pubkey;
-use tracing::{error, warn};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+use tracing::{error, info, warn};
 use yellowstone_grpc_proto::prelude::SubscribeUpdateTransactionInfo;
 
+/// Transactions queued per worker before submitters wait
+const PARSE_QUEUE_PER_WORKER: usize = 256;
+
+type ParseJob = (SubscribeUpdateTransactionInfo, String, u64);
+
+/// A buy or sell against a bonding curve
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapEvent {
+    pub mint: Pubkey,
+    pub is_buy: bool,
+    pub token_amount: u64,
+    /// SOL that moved through the curve, or the instruction's SOL bound when the
+    /// transaction has no balance metadata
+    pub sol_amount: u64,
+}
+
+/// Fixed set of tasks parsing create transactions off the stream task. Tokens are emitted
+/// in completion order; a curve update seen before its token is picked up from the cache.
+pub struct ParserPool {
+    jobs: mpsc::Sender<ParseJob>,
+}
+
+impl ParserPool {
+    /// Spawn `workers` parser tasks on the current runtime; they exit when the pool is dropped
+    pub fn spawn(workers: usize, events: mpsc::UnboundedSender<SniperEvent>) -> Self {
+        let workers = workers.max(1);
+        let (jobs, receiver) = mpsc::channel::<ParseJob>(workers * PARSE_QUEUE_PER_WORKER);
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for _ in 0..workers {
+            let receiver = receiver.clone();
+            let events = events.clone();
+            tokio::spawn(async move {
+                loop {
+                    let job = receiver.lock().await.recv().await;
+                    let Some((transaction, signature, slot)) = job else {
+                        break;
+                    };
+                    parse_and_emit(&transaction, signature, slot, &events);
+                }
+            });
+        }
+
+        Self { jobs }
+    }
+
+    /// Queue a transaction landed in `slot`, waiting while the backlog is full
+    pub async fn submit(
+        &self,
+        transaction: SubscribeUpdateTransactionInfo,
+        signature: String,
+        slot: u64,
+    ) {
+        if self
+            .jobs
+            .send((transaction, signature, slot))
+            .await
+            .is_err()
+        {
+            error!("Parser workers stopped, dropping transaction");
+        }
+    }
+}
+
+/// Parse a create transaction landed in `slot` and send its `TokenCreated` event
+pub fn parse_and_emit(
+    transaction: &SubscribeUpdateTransactionInfo,
+    signature: String,
+    slot: u64,
+    events: &mpsc::UnboundedSender<SniperEvent>,
+) {
+    info!("TOKEN CREATION DETECTED: {}", signature);
+
+    if let Some(mut token_info) = parse_token_creation(transaction, signature) {
+        token_info.creation_slot = slot;
+        token_info.creator_initial_tokens = creator_post_balance(transaction, &token_info);
+        if let Err(e) = events.send(SniperEvent::TokenCreated(token_info)) {
+            error!("Failed to send token creation event: {}", e);
+        }
+    }
+}
+
 /// Check if tx contains a create instruction
 pub fn is_create_transaction(transaction: &SubscribeUpdateTransactionInfo) -> bool {
     if let Some(ref transaction_data) = transaction.transaction {
@@ -75,6 +163,103 @@ pub fn parse_token_creation(
     None
 }
 
+/// Creator's balance of the new mint after its create transaction; a creator with no
+/// entry in the token balance metadata holds none. None without metadata
+pub fn creator_post_balance(
+    transaction: &SubscribeUpdateTransactionInfo,
+    token_info: &TokenInfo,
+) -> Option<u64> {
+    let meta = transaction.meta.as_ref()?;
+    let (mint, creator) = (token_info.mint.to_string(), token_info.creator.to_string());
+    Some(
+        meta.post_token_balances
+            .iter()
+            .filter(|balance| balance.mint == mint && balance.owner == creator)
+            .filter_map(|balance| balance.ui_token_amount.as_ref()?.amount.parse::<u64>().ok())
+            .sum(),
+    )
+}
+
+/// First Pump buy or sell in the transaction, in execution order, including ones made
+/// through another program such as a router or bot
+pub fn parse_swap(transaction: &SubscribeUpdateTransactionInfo) -> Option<SwapEvent> {
+    let message = transaction.transaction.as_ref()?.message.as_ref()?;
+    let meta = transaction.meta.as_ref();
+    let program_id = accounts::pumpfun_program_id();
+    // lookup table addresses follow the static keys, writable first
+    let loaded = meta.into_iter().flat_map(|meta| {
+        meta.loaded_writable_addresses
+            .iter()
+            .chain(&meta.loaded_readonly_addresses)
+    });
+    let keys: Vec<&Vec<u8>> = message.account_keys.iter().chain(loaded).collect();
+    let key = |index: u32| {
+        keys.get(index as usize)
+            .and_then(|key| Pubkey::try_from(key.as_slice()).ok())
+    };
+    let inner = |index: usize| {
+        meta.into_iter()
+            .flat_map(|meta| &meta.inner_instructions)
+            .filter(move |inner| inner.index as usize == index)
+            .flat_map(|inner| &inner.instructions)
+            .map(|instruction| {
+                (
+                    instruction.program_id_index,
+                    &instruction.accounts,
+                    &instruction.data,
+                )
+            })
+    };
+
+    let mut instructions =
+        message
+            .instructions
+            .iter()
+            .enumerate()
+            .flat_map(|(index, instruction)| {
+                std::iter::once((
+                    instruction.program_id_index,
+                    &instruction.accounts,
+                    &instruction.data,
+                ))
+                .chain(inner(index))
+            });
+    instructions.find_map(|(program_id_index, instruction_accounts, data)| {
+        // PumpSwap shares the buy and sell discriminators
+        if key(program_id_index) != Some(program_id) || data.len() < 8 {
+            return None;
+        }
+        let (discriminator, mut args) = data.split_at(8);
+        let (is_buy, token_amount, sol_bound) = if discriminator == BUY_DISCRIMINATOR {
+            let buy = BuyInstruction::deserialize(&mut args).ok()?;
+            (true, buy.amount, buy.max_sol_cost)
+        } else if discriminator == SELL_DISCRIMINATOR {
+            let sell = SellInstruction::deserialize(&mut args).ok()?;
+            (false, sell.amount, sell.min_sol_output)
+        } else {
+            return None;
+        };
+
+        // accounts: global, fee recipient, mint, bonding curve, ...
+        let mint = key(*instruction_accounts.get(2)? as u32)?;
+        let curve_index = *instruction_accounts.get(3)? as usize;
+        let sol_amount = meta
+            .and_then(|meta| {
+                let pre = *meta.pre_balances.get(curve_index)?;
+                let post = *meta.post_balances.get(curve_index)?;
+                Some(pre.abs_diff(post))
+            })
+            .unwrap_or(sol_bound);
+
+        Some(SwapEvent {
+            mint,
+            is_buy,
+            token_amount,
+            sol_amount,
+        })
+    })
+}
+
 /// Extract mint pubkey from instruction accounts
 fn extract_mint_from_instruction(
     message: &yellowstone_grpc_proto::prelude::Message,
@@ -100,4 +285,218 @@ mod tests {
         // Verify discriminator matches expected value
         assert_eq!(CREATE_DISCRIMINATOR, [24, 30, 200, 40, 5, 28, 7, 119]);
     }
+
+    fn create_transaction(mint: Pubkey) -> SubscribeUpdateTransactionInfo {
+        use yellowstone_grpc_proto::prelude::{CompiledInstruction, Message, Transaction};
+
+        let data = CreateInstruction {
+            name: "Parsed".to_string(),
+            symbol: "PRS".to_string(),
+            uri: "https://example.com/meta.json".to_string(),
+            creator: Pubkey::new_unique(),
+        }
+        .data();
+        SubscribeUpdateTransactionInfo {
+            transaction: Some(Transaction {
+                message: Some(Message {
+                    account_keys: vec![mint.to_bytes().to_vec()],
+                    instructions: vec![CompiledInstruction {
+                        program_id_index: 0,
+                        accounts: vec![0],
+                        data,
+                    }],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_swap() {
+        use yellowstone_grpc_proto::prelude::{
+            CompiledInstruction, InnerInstruction, InnerInstructions, Message, Transaction,
+            TransactionStatusMeta,
+        };
+
+        let mint = Pubkey::new_unique();
+        let curve = derive_bonding_curve_pda(&mint).unwrap();
+        let swap =
+            |data: Vec<u8>, meta: Option<TransactionStatusMeta>| SubscribeUpdateTransactionInfo {
+                transaction: Some(Transaction {
+                    message: Some(Message {
+                        account_keys: vec![
+                            accounts::pumpfun_program_id().to_bytes().to_vec(),
+                            Pubkey::new_unique().to_bytes().to_vec(),
+                            Pubkey::new_unique().to_bytes().to_vec(),
+                            mint.to_bytes().to_vec(),
+                            curve.to_bytes().to_vec(),
+                        ],
+                        instructions: vec![CompiledInstruction {
+                            program_id_index: 0,
+                            accounts: vec![1, 2, 3, 4],
+                            data,
+                        }],
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                meta,
+                ..Default::default()
+            };
+        let buy = BuyInstruction {
+            amount: 5_000_000,
+            max_sol_cost: 110_000_000,
+        }
+        .data();
+
+        // no balances, so the bound stands in
+        assert_eq!(
+            parse_swap(&swap(buy.clone(), None)),
+            Some(SwapEvent {
+                mint,
+                is_buy: true,
+                token_amount: 5_000_000,
+                sol_amount: 110_000_000,
+            })
+        );
+
+        // the curve's balance change is what actually traded
+        let meta = TransactionStatusMeta {
+            pre_balances: vec![0, 0, 0, 0, 2_000_000_000],
+            post_balances: vec![0, 0, 0, 0, 2_100_000_000],
+            ..Default::default()
+        };
+        assert_eq!(
+            parse_swap(&swap(buy.clone(), Some(meta.clone()))).map(|swap| swap.sol_amount),
+            Some(100_000_000)
+        );
+
+        let sell = SellInstruction {
+            amount: 5_000_000,
+            min_sol_output: 90_000_000,
+        }
+        .data();
+        let sold = parse_swap(&swap(sell, None)).unwrap();
+        assert!(!sold.is_buy);
+        assert_eq!(sold.sol_amount, 90_000_000);
+
+        assert_eq!(parse_swap(&create_transaction(mint)), None);
+
+        // a buy made through another program shows up only as an inner instruction, with
+        // the curve loaded from a lookup table
+        let router = SubscribeUpdateTransactionInfo {
+            transaction: Some(Transaction {
+                message: Some(Message {
+                    account_keys: vec![
+                        Pubkey::new_unique().to_bytes().to_vec(),
+                        accounts::pumpfun_program_id().to_bytes().to_vec(),
+                        Pubkey::new_unique().to_bytes().to_vec(),
+                        mint.to_bytes().to_vec(),
+                    ],
+                    instructions: vec![CompiledInstruction {
+                        program_id_index: 0,
+                        accounts: vec![1, 2, 3, 4],
+                        data: vec![1; 16],
+                    }],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            meta: Some(TransactionStatusMeta {
+                inner_instructions: vec![InnerInstructions {
+                    index: 0,
+                    instructions: vec![InnerInstruction {
+                        program_id_index: 1,
+                        accounts: vec![2, 2, 3, 4],
+                        data: buy,
+                        stack_height: Some(2),
+                    }],
+                }],
+                loaded_writable_addresses: vec![curve.to_bytes().to_vec()],
+                pre_balances: vec![0, 0, 0, 0, 2_000_000_000],
+                post_balances: vec![0, 0, 0, 0, 2_040_000_000],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            parse_swap(&router),
+            Some(SwapEvent {
+                mint,
+                is_buy: true,
+                token_amount: 5_000_000,
+                sol_amount: 40_000_000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_creator_post_balance() {
+        use yellowstone_grpc_proto::prelude::{TokenBalance, TransactionStatusMeta, UiTokenAmount};
+
+        let mint = Pubkey::new_unique();
+        let mut transaction = create_transaction(mint);
+        let token_info = parse_token_creation(&transaction, "sig".to_string()).unwrap();
+        assert_eq!(creator_post_balance(&transaction, &token_info), None);
+
+        let balance = |mint: Pubkey, owner: Pubkey, amount: u64| TokenBalance {
+            mint: mint.to_string(),
+            owner: owner.to_string(),
+            ui_token_amount: Some(UiTokenAmount {
+                amount: amount.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        transaction.meta = Some(TransactionStatusMeta::default());
+        assert_eq!(creator_post_balance(&transaction, &token_info), Some(0));
+
+        // only the creator's balance of this mint counts
+        transaction.meta = Some(TransactionStatusMeta {
+            post_token_balances: vec![
+                balance(mint, token_info.creator, 30_000_000),
+                balance(mint, Pubkey::new_unique(), 800_000_000),
+                balance(Pubkey::new_unique(), token_info.creator, 5),
+            ],
+            ..Default::default()
+        });
+        assert_eq!(
+            creator_post_balance(&transaction, &token_info),
+            Some(30_000_000)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parser_pool_offloads_and_drains_backlog() {
+        let (events, mut received) = mpsc::unbounded_channel();
+        let pool = ParserPool::spawn(2, events);
+
+        // single-threaded runtime: nothing is parsed until the submitter yields
+        let first = Pubkey::new_unique();
+        pool.submit(create_transaction(first), "sig".to_string(), 1)
+            .await;
+        assert!(received.try_recv().is_err());
+
+        // more than the queue holds, so submit has to wait on the workers
+        let backlog: Vec<Pubkey> = (0..3 * PARSE_QUEUE_PER_WORKER)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+        for mint in &backlog {
+            pool.submit(create_transaction(*mint), "sig".to_string(), 1)
+                .await;
+        }
+        drop(pool);
+
+        let mut mints = std::collections::HashSet::new();
+        while let Some(event) = received.recv().await {
+            match event {
+                SniperEvent::TokenCreated(token) => assert!(mints.insert(token.mint)),
+                other => panic!("unexpected {other:?}"),
+            }
+        }
+        assert_eq!(mints.len(), backlog.len() + 1);
+        assert!(mints.contains(&first) && backlog.iter().all(|mint| mints.contains(mint)));
+    }
 }
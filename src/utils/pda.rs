@@ -59,6 +59,60 @@ pub fn derive_creator_vault_pda(creator: &Pubkey) -> Result<Pubkey, SniperError>
     Ok(creator_vault)
 }
 
+/// Derive the pump program PDA that creates a migrated token's AMM pool
+pub fn derive_pool_authority_pda(mint: &Pubkey) -> Result<Pubkey, SniperError> {
+    let (pool_authority, _bump) = Pubkey::find_program_address(
+        &[seeds::POOL_AUTHORITY_SEED, mint.as_ref()],
+        &accounts::pumpfun_program_id(),
+    );
+    Ok(pool_authority)
+}
+
+/// Derive the canonical PumpSwap pool (index 0, mint/WSOL) a completed curve migrates to
+pub fn derive_pump_swap_pool_pda(mint: &Pubkey) -> Result<Pubkey, SniperError> {
+    let pool_authority = derive_pool_authority_pda(mint)?;
+    let (pool, _bump) = Pubkey::find_program_address(
+        &[
+            seeds::POOL_SEED,
+            &0u16.to_le_bytes(),
+            pool_authority.as_ref(),
+            mint.as_ref(),
+            accounts::wsol_mint().as_ref(),
+        ],
+        &accounts::pumpswap_program_id(),
+    );
+    Ok(pool)
+}
+
+/// Derive PumpSwap global config PDA
+pub fn derive_amm_global_config_pda() -> Result<Pubkey, SniperError> {
+    let (global_config, _bump) = Pubkey::find_program_address(
+        &[seeds::AMM_GLOBAL_CONFIG_SEED],
+        &accounts::pumpswap_program_id(),
+    );
+    Ok(global_config)
+}
+
+/// Derive PumpSwap event authority PDA
+pub fn derive_amm_event_authority_pda() -> Result<Pubkey, SniperError> {
+    let (event_authority, _bump) = Pubkey::find_program_address(
+        &[seeds::AMM_EVENT_AUTHORITY_SEED],
+        &accounts::pumpswap_program_id(),
+    );
+    Ok(event_authority)
+}
+
+/// Derive PumpSwap coin creator vault authority PDA
+pub fn derive_amm_creator_vault_authority_pda(
+    coin_creator: &Pubkey,
+) -> Result<Pubkey, SniperError> {
+    let (vault_authority, _bump) = Pubkey::find_program_address(
+        &[seeds::AMM_CREATOR_VAULT_SEED, coin_creator.as_ref()],
+        &accounts::pumpswap_program_id(),
+    );
+    Ok(vault_authority)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,4 +137,32 @@ mod tests {
         let global2 = derive_global_pda().unwrap();
         assert_eq!(global, global2);
     }
+
+    #[test]
+    fn test_derive_pump_swap_pool_pda() {
+        let mint = Pubkey::new_unique();
+        let pool = derive_pump_swap_pool_pda(&mint).unwrap();
+        assert_eq!(pool, derive_pump_swap_pool_pda(&mint).unwrap());
+        assert_ne!(
+            pool,
+            derive_pump_swap_pool_pda(&Pubkey::new_unique()).unwrap()
+        );
+
+        // pool created by the pump program's pool authority, index 0, paired with WSOL
+        let (pool_authority, _) = Pubkey::find_program_address(
+            &[b"pool-authority", mint.as_ref()],
+            &accounts::pumpfun_program_id(),
+        );
+        let (expected, _) = Pubkey::find_program_address(
+            &[
+                b"pool",
+                &[0, 0],
+                pool_authority.as_ref(),
+                mint.as_ref(),
+                accounts::wsol_mint().as_ref(),
+            ],
+            &accounts::pumpswap_program_id(),
+        );
+        assert_eq!(pool, expected);
+    }
 }
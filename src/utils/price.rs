@@ -1,10 +1,51 @@
 //! Price fetching utilities
 
-use anyhow::Result;
+use crate::constants::accounts;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use borsh::{BorshDeserialize, BorshSerialize};
 use serde::Deserialize;
-use std::time::{Duration, SystemTime};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, OnceLock,
+};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::info;
 
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const USDC_DECIMALS: i32 = 6;
+
+const COINGECKO_PRICE_URL: &str =
+    "https://api.coingecko.com/api/v3/simple/price?ids=solana&vs_currencies=usd";
+/// Paid tier, used when an API key is configured
+const COINGECKO_PRO_PRICE_URL: &str =
+    "https://pro-api.coingecko.com/api/v3/simple/price?ids=solana&vs_currencies=usd";
+const COINGECKO_PRO_KEY_HEADER: &str = "x-cg-pro-api-key";
+
+/// Price API request timeout unless configured
+pub const DEFAULT_PRICE_TIMEOUT: Duration = Duration::from_secs(3);
+/// How long a fetched SOL price is reused unless configured
+pub const DEFAULT_PRICE_CACHE_DURATION: Duration = Duration::from_secs(30);
+
+/// Process-wide HTTP client so keep-alive connections are reused across fetchers
+static SHARED_CLIENT: OnceLock<Arc<reqwest::Client>> = OnceLock::new();
+
+/// Get the lazily-initialized shared HTTP client
+pub fn shared_http_client() -> Arc<reqwest::Client> {
+    SHARED_CLIENT
+        .get_or_init(|| Arc::new(reqwest::Client::new()))
+        .clone()
+}
+
+/// Where the SOL/USD price comes from
+#[async_trait]
+pub trait SolPriceSource: Send + Sync {
+    async fn sol_price_usd(&self) -> Result<f64>;
+}
+
 #[derive(Debug, Deserialize)]
 struct CoinGeckoResponse {
     solana: SolanaPrice,
@@ -15,23 +56,347 @@ struct SolanaPrice {
     usd: f64,
 }
 
+/// CoinGecko simple price API, on the Pro endpoint when given an API key
+pub struct CoinGeckoSource {
+    client: Arc<reqwest::Client>,
+    api_key: Option<String>,
+    timeout: Duration,
+}
+
+impl CoinGeckoSource {
+    pub fn new(client: Arc<reqwest::Client>) -> Self {
+        Self {
+            client,
+            api_key: None,
+            timeout: DEFAULT_PRICE_TIMEOUT,
+        }
+    }
+
+    /// Use the Pro endpoint with `api_key`
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn request(&self) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(api_key) => self
+                .client
+                .get(COINGECKO_PRO_PRICE_URL)
+                .header(COINGECKO_PRO_KEY_HEADER, api_key),
+            None => self.client.get(COINGECKO_PRICE_URL),
+        }
+        .timeout(self.timeout)
+    }
+}
+
+#[async_trait]
+impl SolPriceSource for CoinGeckoSource {
+    async fn sol_price_usd(&self) -> Result<f64> {
+        let response = self.request().send().await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "CoinGecko API error: {}",
+                response.status()
+            ));
+        }
+
+        let data: CoinGeckoResponse = response.json().await?;
+
+        Ok(data.solana.usd)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JupiterQuote {
+    out_amount: String,
+}
+
+/// Jupiter quote for 1 SOL -> USDC
+pub struct JupiterSource {
+    client: Arc<reqwest::Client>,
+    timeout: Duration,
+}
+
+impl JupiterSource {
+    pub fn new(client: Arc<reqwest::Client>) -> Self {
+        Self {
+            client,
+            timeout: DEFAULT_PRICE_TIMEOUT,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[async_trait]
+impl SolPriceSource for JupiterSource {
+    async fn sol_price_usd(&self) -> Result<f64> {
+        let url = format!(
+            "https://quote-api.jup.ag/v6/quote?inputMint={}&outputMint={}&amount={}&slippageBps=50",
+            WSOL_MINT, USDC_MINT, LAMPORTS_PER_SOL
+        );
+
+        let response = self.client.get(url).timeout(self.timeout).send().await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Jupiter API error: {}", response.status()));
+        }
+
+        let quote: JupiterQuote = response.json().await?;
+        let usdc_out: u64 = quote
+            .out_amount
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid Jupiter outAmount: {}", e))?;
+
+        Ok(usdc_out as f64 / 10f64.powi(USDC_DECIMALS))
+    }
+}
+
+/// Anchor discriminator of the Pyth receiver's `PriceUpdateV2` account
+const PYTH_PRICE_UPDATE_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+/// Pyth SOL/USD feed id, 0xef0d8b6f...c280b56d
+const PYTH_SOL_USD_FEED_ID: [u8; 32] = [
+    239, 13, 139, 111, 218, 44, 235, 164, 29, 161, 93, 64, 149, 209, 218, 57, 42, 13, 47, 142, 208,
+    198, 199, 188, 15, 76, 250, 200, 194, 128, 181, 109,
+];
+/// Oldest update accepted
+const PYTH_MAX_AGE: Duration = Duration::from_secs(60);
+/// Widest confidence interval accepted, as a fraction of the price
+const PYTH_MAX_CONFIDENCE: f64 = 0.01;
+
+/// How many Wormhole guardian signatures the receiver checked for an update
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+enum VerificationLevel {
+    Partial { num_signatures: u8 },
+    Full,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+struct PriceFeedMessage {
+    feed_id: [u8; 32],
+    price: i64,
+    conf: u64,
+    exponent: i32,
+    publish_time: i64,
+    prev_publish_time: i64,
+    ema_price: i64,
+    ema_conf: u64,
+}
+
+/// `PriceUpdateV2` after its discriminator
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+struct PriceUpdateV2 {
+    write_authority: [u8; 32],
+    verification_level: VerificationLevel,
+    price_message: PriceFeedMessage,
+    posted_slot: u64,
+}
+
+/// SOL/USD price from a Pyth pull-oracle price update account
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PythPrice {
+    pub price: i64,
+    pub confidence: u64,
+    pub exponent: i32,
+    /// Unix seconds the price was published
+    pub publish_time: i64,
+}
+
+impl PythPrice {
+    /// Decode a fully verified SOL/USD update from raw `PriceUpdateV2` account data
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        let body = data
+            .strip_prefix(&PYTH_PRICE_UPDATE_DISCRIMINATOR)
+            .ok_or_else(|| anyhow!("Not a Pyth price update account"))?;
+        let update = PriceUpdateV2::deserialize(&mut &body[..])
+            .map_err(|e| anyhow!("Invalid Pyth price update: {}", e))?;
+        if update.verification_level != VerificationLevel::Full {
+            return Err(anyhow!("Pyth price update is only partially verified"));
+        }
+        let message = update.price_message;
+        if message.feed_id != PYTH_SOL_USD_FEED_ID {
+            return Err(anyhow!("Pyth price update is not the SOL/USD feed"));
+        }
+        Ok(Self {
+            price: message.price,
+            confidence: message.conf,
+            exponent: message.exponent,
+            publish_time: message.publish_time,
+        })
+    }
+
+    /// Price with the exponent applied
+    pub fn value(&self) -> f64 {
+        scale_pyth(self.price, self.exponent)
+    }
+
+    /// Price at `now` (unix seconds), unless stale or too uncertain
+    pub fn checked_value(&self, now: i64) -> Result<f64> {
+        let age = now.saturating_sub(self.publish_time);
+        if age > PYTH_MAX_AGE.as_secs() as i64 {
+            return Err(anyhow!("Pyth price is {}s old", age));
+        }
+        let price = self.value();
+        if price <= 0.0 {
+            return Err(anyhow!("Pyth price {} is not positive", price));
+        }
+        let confidence = scale_pyth(self.confidence as i64, self.exponent);
+        if confidence / price > PYTH_MAX_CONFIDENCE {
+            return Err(anyhow!(
+                "Pyth confidence ±{:.4} too wide for {:.4}",
+                confidence,
+                price
+            ));
+        }
+        Ok(price)
+    }
+}
+
+/// `raw * 10^exponent`, Pyth's fixed-point encoding
+pub fn scale_pyth(raw: i64, exponent: i32) -> f64 {
+    raw as f64 * 10f64.powi(exponent)
+}
+
+/// Pyth SOL/USD price feed account read from chain through the RPC
+pub struct PythPriceSource {
+    rpc_client: Arc<RpcClient>,
+}
+
+impl PythPriceSource {
+    pub fn new(rpc_client: Arc<RpcClient>) -> Self {
+        Self { rpc_client }
+    }
+}
+
+#[async_trait]
+impl SolPriceSource for PythPriceSource {
+    async fn sol_price_usd(&self) -> Result<f64> {
+        // the RPC client blocks, so keep it off the runtime's workers
+        let rpc_client = self.rpc_client.clone();
+        let account = tokio::task::spawn_blocking(move || {
+            rpc_client
+                .get_account(&accounts::pyth_sol_usd_price())
+                .map_err(|e| anyhow!("Failed to fetch Pyth price account: {}", e))
+        })
+        .await??;
+        if account.owner != accounts::pyth_receiver_program_id() {
+            return Err(anyhow!("Pyth price account has an unexpected owner"));
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        PythPrice::from_account_data(&account.data)?.checked_value(now)
+    }
+}
+
+/// Constant price, counting how often it is asked
+#[derive(Debug, Clone, Default)]
+pub struct FixedPriceSource {
+    price: f64,
+    calls: Arc<AtomicUsize>,
+}
+
+impl FixedPriceSource {
+    pub fn new(price: f64) -> Self {
+        Self {
+            price,
+            calls: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Number of lookups served so far
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl SolPriceSource for FixedPriceSource {
+    async fn sol_price_usd(&self) -> Result<f64> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        Ok(self.price)
+    }
+}
+
 /// Price fetcher for SOL/USD
 pub struct PriceFetcher {
-    client: reqwest::Client,
+    client: Arc<reqwest::Client>,
+    source: Box<dyn SolPriceSource>,
     cached_price: Option<(f64, SystemTime)>,
     cache_duration: Duration,
 }
 
 impl PriceFetcher {
-    /// Create new price fetcher
+    /// Create new price fetcher using the shared HTTP client
     pub fn new() -> Self {
+        Self::with_client(shared_http_client())
+    }
+
+    /// Create new price fetcher on top of an existing HTTP client
+    pub fn with_client(client: Arc<reqwest::Client>) -> Self {
+        let source = Box::new(CoinGeckoSource::new(client.clone()));
         Self {
-            client: reqwest::Client::new(),
+            client,
+            source,
             cached_price: None,
-            cache_duration: Duration::from_secs(30), // Cache for 30 seconds
+            cache_duration: DEFAULT_PRICE_CACHE_DURATION,
+        }
+    }
+
+    /// Create new price fetcher reading from `source`, cached like the default
+    pub fn with_source(source: Box<dyn SolPriceSource>) -> Self {
+        Self {
+            source,
+            ..Self::new()
         }
     }
 
+    /// Price fetcher for a configured source name (`coingecko`, `jupiter` or `pyth`). Pyth
+    /// is read through `rpc_client`; `timeout` bounds the HTTP sources and CoinGecko moves
+    /// to its Pro endpoint given `coingecko_api_key`. Prices are reused for `cache_duration`
+    pub fn from_source_name(
+        name: &str,
+        rpc_client: Arc<RpcClient>,
+        coingecko_api_key: Option<&str>,
+        timeout: Duration,
+        cache_duration: Duration,
+    ) -> Result<Self> {
+        let client = shared_http_client();
+        let source: Box<dyn SolPriceSource> = match name {
+            "coingecko" => {
+                let source = CoinGeckoSource::new(client).with_timeout(timeout);
+                Box::new(match coingecko_api_key {
+                    Some(api_key) => source.with_api_key(api_key),
+                    None => source,
+                })
+            }
+            "jupiter" => Box::new(JupiterSource::new(client).with_timeout(timeout)),
+            "pyth" => Box::new(PythPriceSource::new(rpc_client)),
+            other => return Err(anyhow::anyhow!("Unknown SOL price source: {}", other)),
+        };
+        Ok(Self {
+            cache_duration,
+            ..Self::with_source(source)
+        })
+    }
+
+    /// Get the underlying HTTP client
+    pub fn client(&self) -> &Arc<reqwest::Client> {
+        &self.client
+    }
+
     /// Get current SOL price in USD
     pub async fn get_sol_price_usd(&mut self) -> Result<f64> {
         if let Some((price, timestamp)) = self.cached_price {
@@ -41,7 +406,8 @@ impl PriceFetcher {
         }
 
         // If no cache or expired, fetch fresh price
-        let price = self.fetch_fresh_price().await?;
+        let price = self.source.sol_price_usd().await?;
+        info!("SOL PRICE: ${:.2}", price);
 
         // Update cache
         self.cached_price = Some((price, SystemTime::now()));
@@ -49,28 +415,14 @@ impl PriceFetcher {
         Ok(price)
     }
 
-    async fn fetch_fresh_price(&self) -> Result<f64> {
-        let url = "https://api.coingecko.com/api/v3/simple/price?ids=solana&vs_currencies=usd";
-
-        let response = self
-            .client
-            .get(url)
-            .timeout(Duration::from_secs(3))
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "CoinGecko API error: {}",
-                response.status()
-            ));
-        }
-
-        let data: CoinGeckoResponse = response.json().await?;
-
-        info!("SOL PRICE: ${:.2}", data.solana.usd);
+    /// Last fetched SOL price, however old, without a lookup
+    pub fn last_sol_price_usd(&self) -> Option<f64> {
+        self.cached_price.map(|(price, _)| price)
+    }
 
-        Ok(data.solana.usd)
+    #[cfg(test)]
+    pub(crate) fn set_cached_price(&mut self, price: f64) {
+        self.cached_price = Some((price, SystemTime::now()));
     }
 
     /// Calculate market cap in USD
@@ -81,6 +433,15 @@ impl PriceFetcher {
 
         Ok(market_cap_usd)
     }
+
+    /// Lamports worth `usd` at the current SOL price
+    pub async fn usd_to_lamports(&mut self, usd: f64) -> Result<u64> {
+        let sol_price = self.get_sol_price_usd().await?;
+        if !sol_price.is_finite() || sol_price <= 0.0 {
+            return Err(anyhow!("Unusable SOL price {}", sol_price));
+        }
+        Ok((usd / sol_price * 1e9) as u64)
+    }
 }
 
 impl Default for PriceFetcher {
@@ -103,6 +464,151 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_shared_client_reused() {
+        let client = Arc::new(reqwest::Client::new());
+        let first = PriceFetcher::with_client(client.clone());
+        let second = PriceFetcher::with_client(client);
+        assert!(Arc::ptr_eq(first.client(), second.client()));
+
+        let default_a = PriceFetcher::new();
+        let default_b = PriceFetcher::default();
+        assert!(Arc::ptr_eq(default_a.client(), default_b.client()));
+    }
+
+    #[tokio::test]
+    async fn test_fixed_source_is_cached() {
+        let source = FixedPriceSource::new(150.0);
+        let mut fetcher = PriceFetcher::with_source(Box::new(source.clone()));
+
+        assert_eq!(fetcher.get_sol_price_usd().await.unwrap(), 150.0);
+        assert_eq!(fetcher.get_sol_price_usd().await.unwrap(), 150.0);
+        assert_eq!(
+            fetcher
+                .calculate_market_cap_usd(2_000_000_000)
+                .await
+                .unwrap(),
+            300.0
+        );
+        assert_eq!(source.calls(), 1);
+
+        // expired cache goes back to the source
+        fetcher.cache_duration = Duration::ZERO;
+        fetcher.get_sol_price_usd().await.unwrap();
+        assert_eq!(source.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_usd_to_lamports() {
+        let mut fetcher = PriceFetcher::with_source(Box::new(FixedPriceSource::new(150.0)));
+        assert_eq!(fetcher.usd_to_lamports(15.0).await.unwrap(), 100_000_000);
+        assert_eq!(fetcher.usd_to_lamports(300.0).await.unwrap(), 2_000_000_000);
+
+        let mut broken = PriceFetcher::with_source(Box::new(FixedPriceSource::new(0.0)));
+        assert!(broken.usd_to_lamports(15.0).await.is_err());
+    }
+
+    #[test]
+    fn test_unknown_source_name_rejected() {
+        let rpc = Arc::new(RpcClient::new_mock("succeeds".to_string()));
+        let from_name = |name: &str| {
+            PriceFetcher::from_source_name(
+                name,
+                rpc.clone(),
+                None,
+                DEFAULT_PRICE_TIMEOUT,
+                Duration::from_secs(5),
+            )
+        };
+        assert!(from_name("jupiter").is_ok());
+        assert!(from_name("coingecko").is_ok());
+        assert!(from_name("pyth").is_ok());
+        assert!(from_name("binance").is_err());
+        assert_eq!(
+            from_name("coingecko").unwrap().cache_duration,
+            Duration::from_secs(5)
+        );
+    }
+
+    #[test]
+    fn test_coingecko_endpoint_follows_api_key() {
+        let client = Arc::new(reqwest::Client::new());
+
+        let free = CoinGeckoSource::new(client.clone())
+            .request()
+            .build()
+            .unwrap();
+        assert_eq!(free.url().host_str(), Some("api.coingecko.com"));
+        assert!(free.headers().get(COINGECKO_PRO_KEY_HEADER).is_none());
+        assert_eq!(free.timeout(), Some(&DEFAULT_PRICE_TIMEOUT));
+
+        let pro = CoinGeckoSource::new(client)
+            .with_api_key("CG-test")
+            .with_timeout(Duration::from_millis(750))
+            .request()
+            .build()
+            .unwrap();
+        assert_eq!(pro.url().host_str(), Some("pro-api.coingecko.com"));
+        assert_eq!(pro.url().path(), free.url().path());
+        assert_eq!(pro.headers()[COINGECKO_PRO_KEY_HEADER], "CG-test");
+        assert_eq!(pro.timeout(), Some(&Duration::from_millis(750)));
+    }
+
+    #[test]
+    fn test_pyth_price_scaling_and_checks() {
+        assert!((scale_pyth(14_523_456_789, -8) - 145.23456789).abs() < 1e-9);
+        assert_eq!(scale_pyth(15, 1), 150.0);
+
+        let update = PriceUpdateV2 {
+            write_authority: [7; 32],
+            verification_level: VerificationLevel::Full,
+            price_message: PriceFeedMessage {
+                feed_id: PYTH_SOL_USD_FEED_ID,
+                price: 14_500_000_000,
+                conf: 7_000_000,
+                exponent: -8,
+                publish_time: 1_000,
+                prev_publish_time: 999,
+                ema_price: 14_400_000_000,
+                ema_conf: 8_000_000,
+            },
+            posted_slot: 42,
+        };
+        let account_data = |update: &PriceUpdateV2| {
+            let mut data = PYTH_PRICE_UPDATE_DISCRIMINATOR.to_vec();
+            data.extend(borsh::to_vec(update).unwrap());
+            data
+        };
+        let data = account_data(&update);
+
+        let price = PythPrice::from_account_data(&data).unwrap();
+        assert_eq!(price.exponent, -8);
+        assert_eq!(price.checked_value(1_030).unwrap(), 145.0);
+
+        // stale and uncertain prices are refused
+        assert!(price.checked_value(1_061).is_err());
+        let uncertain = PythPrice {
+            confidence: 200_000_000,
+            ..price
+        };
+        assert!(uncertain.checked_value(1_030).is_err());
+
+        // as are partially verified updates and other feeds
+        let partial = PriceUpdateV2 {
+            verification_level: VerificationLevel::Partial { num_signatures: 5 },
+            ..update
+        };
+        assert!(PythPrice::from_account_data(&account_data(&partial)).is_err());
+        let mut other_feed = update;
+        other_feed.price_message.feed_id = [1; 32];
+        assert!(PythPrice::from_account_data(&account_data(&other_feed)).is_err());
+
+        assert!(PythPrice::from_account_data(&data[..100]).is_err());
+        let mut legacy = data.clone();
+        legacy[0] = 0;
+        assert!(PythPrice::from_account_data(&legacy).is_err());
+    }
+
     #[test]
     fn test_market_cap_calculation() {
         let sol_amount = 1_000_000_000;
@@ -0,0 +1,97 @@
+//! Token-bucket rate limiting for RPC calls
+
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// Allows `requests_per_sec` calls a second, with up to a second's worth in a burst.
+/// Share one behind an `Arc` so every caller draws from the same budget; waiters are
+/// served in order. A permit covers one call however many requests it makes, so a fetch
+/// that retries counts once
+pub struct RateLimiter {
+    rate: f64,
+    capacity: f64,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    /// `requests_per_sec` must be positive
+    pub fn new(requests_per_sec: f64) -> Self {
+        assert!(
+            requests_per_sec > 0.0 && requests_per_sec.is_finite(),
+            "RateLimiter needs a positive rate"
+        );
+        let capacity = requests_per_sec.max(1.0);
+        Self {
+            rate: requests_per_sec,
+            capacity,
+            bucket: Mutex::new(Bucket {
+                tokens: capacity,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until a request may be made
+    pub async fn acquire(&self) {
+        let mut bucket = self.bucket.lock().await;
+        self.refill(&mut bucket);
+        if bucket.tokens < 1.0 {
+            let wait = Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate);
+            tokio::time::sleep(wait).await;
+            self.refill(&mut bucket);
+        }
+        bucket.tokens = (bucket.tokens - 1.0).max(0.0);
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.capacity);
+        bucket.refilled_at = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_paces_to_rate() {
+        let limiter = Arc::new(RateLimiter::new(20.0));
+        let start = Instant::now();
+
+        // the first second's worth goes straight through
+        for _ in 0..20 {
+            limiter.acquire().await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // the next 10 are shared by two callers and take half a second
+        let tasks: Vec<_> = (0..2)
+            .map(|_| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move {
+                    for _ in 0..5 {
+                        limiter.acquire().await;
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed >= Duration::from_millis(500) && elapsed < Duration::from_millis(505),
+            "{:?}",
+            elapsed
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! RPC endpoint pool with failover
+
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use tracing::warn;
+
+/// RPC clients tried in turn; each call starts one endpoint further round-robin
+pub struct RpcPool {
+    clients: Vec<Arc<RpcClient>>,
+    next: AtomicUsize,
+}
+
+impl RpcPool {
+    /// Pool over `clients`; must not be empty
+    pub fn new(clients: Vec<RpcClient>) -> Self {
+        Self::from_shared(clients.into_iter().map(Arc::new).collect())
+    }
+
+    /// Pool over clients that may also be used elsewhere; must not be empty
+    pub fn from_shared(clients: Vec<Arc<RpcClient>>) -> Self {
+        assert!(!clients.is_empty(), "RpcPool needs at least one client");
+        Self {
+            clients,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn from_endpoints(endpoints: &[String], commitment: CommitmentConfig) -> Self {
+        Self::new(
+            endpoints
+                .iter()
+                .map(|endpoint| RpcClient::new_with_commitment(endpoint.clone(), commitment))
+                .collect(),
+        )
+    }
+
+    /// Client for the first endpoint
+    pub fn primary(&self) -> Arc<RpcClient> {
+        self.clients[0].clone()
+    }
+
+    /// Number of endpoints in the pool
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    /// Run `op` against each endpoint until one succeeds, returning the last error otherwise
+    pub fn call<T>(&self, op: impl Fn(&RpcClient) -> Result<T, String>) -> Result<T, String> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let mut last_error = String::new();
+
+        for offset in 0..self.clients.len() {
+            let client = &self.clients[(start + offset) % self.clients.len()];
+            match op(client) {
+                Ok(value) => return Ok(value),
+                Err(e) => {
+                    if self.clients.len() > 1 {
+                        warn!("RPC {} failed, trying next endpoint: {}", client.url(), e);
+                    }
+                    last_error = e.to_string();
+                }
+            }
+        }
+
+        Err(last_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::{scripted_client as scripted, ScriptedSender};
+    use solana_client::rpc_request::RpcRequest;
+
+    #[test]
+    fn test_round_robin_start() {
+        let first = ScriptedSender::new(|_, _| Ok(serde_json::json!(1)));
+        let second = ScriptedSender::new(|_, _| Ok(serde_json::json!(2)));
+        let (first_calls, second_calls) = (first.calls(), second.calls());
+        let pool = RpcPool::new(vec![scripted(first), scripted(second)]);
+
+        let slots: Vec<u64> = (0..4)
+            .map(|_| {
+                pool.call(|client| client.get_slot().map_err(|e| e.to_string()))
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(slots, [1, 2, 1, 2]);
+        assert_eq!(first_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(second_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_all_endpoints_failing() {
+        let pool = RpcPool::new(vec![
+            scripted(ScriptedSender::new(|_, _| Err("first down".to_string()))),
+            scripted(ScriptedSender::new(|request, _| {
+                assert_eq!(request, RpcRequest::GetSlot);
+                Err("second down".to_string())
+            })),
+        ]);
+
+        let error = pool
+            .call(|client| client.get_slot().map_err(|e| e.to_string()))
+            .unwrap_err();
+        assert!(error.contains("second down"), "{error}");
+    }
+}
@@ -0,0 +1,194 @@
+//! Composite anti-rug safety score
+
+use crate::accounts::BondingCurveAccount;
+use crate::utils::TokenMetadata;
+
+/// Real SOL below which a curve is too thin to exit cleanly
+const THIN_LIQUIDITY_LAMPORTS: u64 = 500_000_000;
+
+/// Curve progress (percent) past which little upside is left before migration
+const LATE_CURVE_PROGRESS: f64 = 80.0;
+
+/// Tokens younger than this haven't shown any trading yet. A buy made as the token is
+/// created nearly always falls inside it, so there the 10 points it costs act as a flat
+/// offset to allow for in `min_safety_score`
+const MIN_SEASONED_AGE_SECS: u64 = 5;
+
+/// What a `RugChecker` scores, gathered at buy time. `None` marks a signal that couldn't
+/// be read, which costs points rather than passing silently
+#[derive(Debug, Clone, Default)]
+pub struct RugSignals {
+    /// Creator's percent of total supply after the create transaction
+    pub dev_holding_pct: Option<f64>,
+    pub curve: Option<BondingCurveAccount>,
+    pub age_secs: u64,
+    pub metadata: Option<TokenMetadata>,
+}
+
+/// 0-100, higher is safer, with a reason for each deduction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafetyScore {
+    pub score: u8,
+    pub reasons: Vec<String>,
+}
+
+/// Scores `RugSignals` and gates buys on `min_safety_score`
+#[derive(Debug, Clone, Copy)]
+pub struct RugChecker {
+    min_score: u8,
+}
+
+impl RugChecker {
+    pub fn new(min_score: u8) -> Self {
+        Self { min_score }
+    }
+
+    pub fn score(&self, signals: &RugSignals) -> SafetyScore {
+        let mut penalty = 0u32;
+        let mut reasons = Vec::new();
+        let mut deduct = |points: u32, reason: String| {
+            penalty += points;
+            reasons.push(reason);
+        };
+
+        match signals.dev_holding_pct {
+            Some(pct) if pct > 20.0 => deduct(40, format!("creator holds {:.1}%", pct)),
+            Some(pct) if pct > 10.0 => deduct(20, format!("creator holds {:.1}%", pct)),
+            Some(_) => {}
+            None => deduct(15, "creator holdings unknown".to_string()),
+        }
+
+        match &signals.curve {
+            Some(curve) => {
+                if curve.real_sol_reserves < THIN_LIQUIDITY_LAMPORTS {
+                    deduct(
+                        15,
+                        format!(
+                            "{:.3} SOL real reserves",
+                            curve.real_sol_reserves as f64 / 1e9
+                        ),
+                    );
+                }
+                let progress = curve.get_curve_progress();
+                if progress > LATE_CURVE_PROGRESS {
+                    deduct(15, format!("curve {:.0}% complete", progress));
+                }
+            }
+            None => deduct(20, "bonding curve unknown".to_string()),
+        }
+
+        if signals.age_secs < MIN_SEASONED_AGE_SECS {
+            deduct(10, format!("{}s old", signals.age_secs));
+        }
+
+        match &signals.metadata {
+            Some(metadata) => {
+                for (field, points) in [("image", 10), ("socials", 10), ("description", 5)] {
+                    if !metadata.has_field(field) {
+                        deduct(points, format!("metadata has no {}", field));
+                    }
+                }
+            }
+            None => deduct(20, "metadata unavailable".to_string()),
+        }
+
+        SafetyScore {
+            score: 100u32.saturating_sub(penalty) as u8,
+            reasons,
+        }
+    }
+
+    /// Whether `safety` clears `min_safety_score`
+    pub fn allows(&self, safety: &SafetyScore) -> bool {
+        safety.score >= self.min_score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::test_curve;
+
+    fn metadata() -> TokenMetadata {
+        TokenMetadata {
+            name: "Test".to_string(),
+            symbol: "TST".to_string(),
+            description: Some("a token".to_string()),
+            image: Some("https://example.com/a.png".to_string()),
+            twitter: Some("https://x.com/test".to_string()),
+            ..TokenMetadata::default()
+        }
+    }
+
+    fn safe() -> RugSignals {
+        RugSignals {
+            dev_holding_pct: Some(2.0),
+            curve: Some(BondingCurveAccount {
+                real_sol_reserves: 5_000_000_000,
+                ..test_curve()
+            }),
+            age_secs: 30,
+            metadata: Some(metadata()),
+        }
+    }
+
+    #[test]
+    fn test_clean_token_scores_full() {
+        let safety = RugChecker::new(0).score(&safe());
+        assert_eq!(safety.score, 100);
+        assert!(safety.reasons.is_empty());
+    }
+
+    #[test]
+    fn test_penalties_combine() {
+        let checker = RugChecker::new(0);
+
+        let heavy_dev = RugSignals {
+            dev_holding_pct: Some(25.0),
+            age_secs: 1,
+            ..safe()
+        };
+        let safety = checker.score(&heavy_dev);
+        assert_eq!(safety.score, 50);
+        assert_eq!(safety.reasons, ["creator holds 25.0%", "1s old"]);
+
+        let bare = RugSignals {
+            metadata: Some(TokenMetadata::default()),
+            curve: Some(test_curve()),
+            ..safe()
+        };
+        let safety = checker.score(&bare);
+        assert_eq!(safety.score, 60);
+        assert_eq!(
+            safety.reasons,
+            [
+                "0.000 SOL real reserves",
+                "metadata has no image",
+                "metadata has no socials",
+                "metadata has no description"
+            ]
+        );
+
+        // signals that couldn't be read count against the token
+        let unknown = RugSignals {
+            dev_holding_pct: Some(50.0),
+            ..RugSignals::default()
+        };
+        let safety = checker.score(&unknown);
+        assert_eq!(safety.score, 10);
+        assert_eq!(safety.reasons.len(), 4);
+    }
+
+    #[test]
+    fn test_min_score_gate() {
+        let signals = RugSignals {
+            dev_holding_pct: Some(15.0),
+            ..safe()
+        };
+        let safety = RugChecker::new(0).score(&signals);
+        assert_eq!(safety.score, 80);
+
+        assert!(RugChecker::new(80).allows(&safety));
+        assert!(!RugChecker::new(81).allows(&safety));
+    }
+}
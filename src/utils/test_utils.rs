@@ -0,0 +1,223 @@
+//! Shared test fixtures and RPC doubles
+
+use crate::{
+    accounts::{BondingCurveAccount, GlobalAccount, TokenInfo},
+    common::Config,
+    constants::PUMPFUN_PROGRAM_ID,
+    utils::{derive_bonding_curve_pda, derive_global_pda, TransactionExecutor},
+};
+use async_trait::async_trait;
+use base64::Engine;
+use serde_json::{json, Value};
+use solana_client::{
+    client_error::{ClientErrorKind, Result as ClientResult},
+    rpc_client::{Mocks, RpcClient, RpcClientConfig},
+    rpc_request::RpcRequest,
+    rpc_sender::{RpcSender, RpcTransportStats},
+};
+use solana_sdk::{hash::Hash, pubkey::Pubkey, transaction::Transaction};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Default config with local endpoints, so it validates
+pub fn test_config() -> Config {
+    Config {
+        grpc_endpoint: "http://127.0.0.1:10000".to_string(),
+        rpc_endpoint: "http://127.0.0.1:8899".to_string(),
+        ..Config::default()
+    }
+}
+
+/// Fresh token with a derived bonding curve
+pub fn test_token() -> TokenInfo {
+    let mint = Pubkey::new_unique();
+    TokenInfo::new(
+        mint,
+        "Test Token".to_string(),
+        "TEST".to_string(),
+        Pubkey::new_unique(),
+        "https://example.com/meta.json".to_string(),
+        derive_bonding_curve_pda(&mint).unwrap(),
+        "sig".to_string(),
+    )
+}
+
+/// Curve with a 30 SOL market cap and 20% progress
+pub fn test_curve() -> BondingCurveAccount {
+    BondingCurveAccount {
+        discriminator: 1,
+        virtual_token_reserves: 1_000_000_000,
+        virtual_sol_reserves: 30_000_000_000,
+        real_token_reserves: 800_000_000,
+        real_sol_reserves: 0,
+        token_total_supply: 1_000_000_000,
+        complete: false,
+        creator: Pubkey::new_unique(),
+    }
+}
+
+/// Global account with a fresh fee recipient
+pub fn test_global() -> GlobalAccount {
+    GlobalAccount {
+        discriminator: 1,
+        initialized: true,
+        authority: Pubkey::new_unique(),
+        fee_recipient: Pubkey::new_unique(),
+        initial_virtual_token_reserves: 1_073_000_000_000_000,
+        initial_virtual_sol_reserves: 30_000_000_000,
+        initial_real_token_reserves: 793_100_000_000_000,
+        token_total_supply: 1_000_000_000_000_000,
+        fee_basis_points: 100,
+        withdraw_authority: Pubkey::new_unique(),
+        enable_migrate: true,
+        pool_migration_fee: 0,
+        creator_fee_basis_points: 5,
+        fee_recipients: [Pubkey::new_unique(); 7],
+        set_creator_authority: Pubkey::new_unique(),
+    }
+}
+
+/// Sender that lets buys through: serves the global account and `test_curve` for every
+/// other account, a blockhash, and accepts transactions
+pub fn buy_sender() -> ScriptedSender {
+    ScriptedSender::with_params(|request, params, _| buy_response(request, params))
+}
+
+/// `buy_sender`'s answers, for scripts that override some requests
+pub fn buy_response(request: RpcRequest, params: &Value) -> Result<Value, String> {
+    let global_pda = derive_global_pda().unwrap().to_string();
+
+    match request {
+        RpcRequest::GetAccountInfo if params[0] == global_pda.as_str() => {
+            Ok(account_response(&borsh::to_vec(&test_global()).unwrap()))
+        }
+        RpcRequest::GetAccountInfo => Ok(account_response(&borsh::to_vec(&test_curve()).unwrap())),
+        RpcRequest::GetLatestBlockhash => Ok(json!({
+            "context": { "slot": 1 },
+            "value": {
+                "blockhash": Hash::new_unique().to_string(),
+                "lastValidBlockHeight": 100,
+            }
+        })),
+        RpcRequest::SendTransaction => {
+            // the client checks the returned signature against the transaction's
+            let wire = base64::engine::general_purpose::STANDARD
+                .decode(params[0].as_str().unwrap_or_default())
+                .map_err(|e| e.to_string())?;
+            let transaction: Transaction =
+                bincode::deserialize(&wire).map_err(|e| e.to_string())?;
+            Ok(json!(transaction.signatures[0].to_string()))
+        }
+        other => Err(format!("unexpected {other}")),
+    }
+}
+
+/// `getAccountInfo` response body wrapping raw account data
+pub fn account_response(data: &[u8]) -> Value {
+    account_response_owned_by(data, PUMPFUN_PROGRAM_ID)
+}
+
+/// `getAccountInfo` result for an account owned by `owner`
+pub fn account_response_owned_by(data: &[u8], owner: &str) -> Value {
+    json!({
+        "context": { "slot": 1 },
+        "value": {
+            "data": [base64::engine::general_purpose::STANDARD.encode(data), "base64"],
+            "executable": false,
+            "lamports": 1_000_000,
+            "owner": owner,
+            "rentEpoch": 0,
+            "space": data.len(),
+        }
+    })
+}
+
+/// Executor backed by solana-client's mock sender
+pub fn mock_executor(config: Config, mocks: Mocks) -> TransactionExecutor {
+    TransactionExecutor::with_rpc_client(
+        Arc::new(RpcClient::new_mock_with_mocks(
+            "succeeds".to_string(),
+            mocks,
+        )),
+        config,
+    )
+}
+
+type Handler = dyn Fn(RpcRequest, &Value, usize) -> Result<Value, String> + Send + Sync;
+
+/// RPC sender answering from a closure of (request, call index), counting calls;
+/// an `Err(message)` from the closure surfaces as a failed RPC request
+pub struct ScriptedSender {
+    calls: Arc<AtomicUsize>,
+    delay: Duration,
+    handler: Box<Handler>,
+}
+
+impl ScriptedSender {
+    pub fn new(
+        handler: impl Fn(RpcRequest, usize) -> Result<Value, String> + Send + Sync + 'static,
+    ) -> Self {
+        Self::with_params(move |request, _, call| handler(request, call))
+    }
+
+    /// Like `new`, with the request params passed to the handler
+    pub fn with_params(
+        handler: impl Fn(RpcRequest, &Value, usize) -> Result<Value, String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            calls: Arc::new(AtomicUsize::new(0)),
+            delay: Duration::ZERO,
+            handler: Box::new(handler),
+        }
+    }
+
+    /// Hold every response for `delay`
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Shared call counter
+    pub fn calls(&self) -> Arc<AtomicUsize> {
+        self.calls.clone()
+    }
+}
+
+#[async_trait]
+impl RpcSender for ScriptedSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        // version probe issued by RpcClient itself, not part of the script
+        if request == RpcRequest::GetVersion {
+            return Ok(json!({ "solana-core": "1.18.26" }));
+        }
+
+        let call = self.calls.fetch_add(1, Ordering::SeqCst);
+        if !self.delay.is_zero() {
+            tokio::time::sleep(self.delay).await;
+        }
+        (self.handler)(request, &params, call)
+            .map_err(|message| ClientErrorKind::Custom(message).into())
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        "scripted".to_string()
+    }
+}
+
+/// Executor backed by a scripted sender
+pub fn scripted_client(sender: ScriptedSender) -> RpcClient {
+    RpcClient::new_sender(sender, RpcClientConfig::default())
+}
+
+pub fn scripted_executor(config: Config, sender: ScriptedSender) -> TransactionExecutor {
+    TransactionExecutor::with_rpc_client(Arc::new(scripted_client(sender)), config)
+}
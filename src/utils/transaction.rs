@@ -1,178 +1,1332 @@
 //! Transaction execution
 
 use crate::{
-    accounts::{BondingCurveAccount, GlobalAccount, TokenInfo},
-    common::Config,
+    accounts::{
+        amm_buy_quote, BondingCurveAccount, CurveFees, GlobalAccount, PumpSwapGlobalConfig,
+        PumpSwapPool, TokenInfo,
+    },
+    common::{Config, MAX_COMPUTE_UNIT_PRICE_MICRO_LAMPORTS},
     error::SniperError,
-    instructions::BuyInstruction,
-    utils::pda::derive_global_pda,
+    instructions::{BuyInstruction, PumpSwapBuyInstruction, SellInstruction},
+    utils::{
+        jito::{tip_account, tip_transaction, JitoExecutor},
+        pda::{derive_amm_global_config_pda, derive_global_pda, derive_pump_swap_pool_pda},
+        shared_http_client, RpcPool,
+    },
 };
 use anyhow::Result;
-use solana_client::rpc_client::RpcClient;
+use futures::future::{BoxFuture, FutureExt, Shared};
+use solana_client::{nonce_utils, rpc_client::RpcClient};
+use solana_program::program_pack::Pack;
 use solana_sdk::{
+    account::Account,
     commitment_config::CommitmentConfig,
     compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::Instruction,
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
     signature::{Keypair, Signature},
     signer::Signer,
-    transaction::Transaction,
+    system_instruction,
+    transaction::{uses_durable_nonce, Transaction},
 };
-use tracing::info;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+/// Max serialized size of a legacy transaction
+pub const MAX_TRANSACTION_SIZE: usize = PACKET_DATA_SIZE;
+
+/// Floor on the compute limit for AMM buys, which set up and close a WSOL account
+const AMM_MIN_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+
+/// How long a dynamic compute unit price is reused before fees are looked up again
+const PRIORITY_FEE_CACHE_TTL: Duration = Duration::from_secs(2);
+
+/// How often signature statuses are polled while confirming
+const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Base delay between resends of a durable buy, scaled by the resend count
+const DURABLE_RESEND_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Result of a sent buy; `confirmed` is false when confirmation is off or timed out
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuyOutcome {
+    pub signature: Signature,
+    pub confirmed: bool,
+    pub slot: Option<u64>,
+    /// Curve state and bounds the buy was built from; None for AMM buys
+    pub snapshot: Option<BuySnapshot>,
+}
+
+/// Bonding curve reserves and quote a buy was built from, kept for audit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BuySnapshot {
+    pub virtual_sol: u64,
+    pub virtual_token: u64,
+    pub real_sol: u64,
+    pub real_token: u64,
+    /// Tokens quoted for the SOL in, after fees
+    pub expected_tokens: u64,
+    /// Token amount on the buy instruction: the quote less slippage
+    pub min_tokens_out: u64,
+    /// SOL-in limit on the buy instruction
+    pub max_sol_cost: u64,
+    pub slippage_bps: u64,
+    /// Priority fee the compute budget pays at its limit, in lamports
+    pub priority_fee_lamports: u64,
+}
+
+impl BuySnapshot {
+    pub fn new(
+        curve: &BondingCurveAccount,
+        expected_tokens: u64,
+        min_tokens_out: u64,
+        max_sol_cost: u64,
+        slippage_bps: u64,
+        priority_fee_lamports: u64,
+    ) -> Self {
+        Self {
+            virtual_sol: curve.virtual_sol_reserves,
+            virtual_token: curve.virtual_token_reserves,
+            real_sol: curve.real_sol_reserves,
+            real_token: curve.real_token_reserves,
+            expected_tokens,
+            min_tokens_out,
+            max_sol_cost,
+            slippage_bps,
+            priority_fee_lamports,
+        }
+    }
+}
+
+/// Instructions making up a buy, kept separate so ATA creation can be split out
+#[derive(Debug, Clone)]
+pub struct BuyInstructions {
+    pub compute_budget: Vec<Instruction>,
+    pub create_ata: Instruction,
+    pub buy: Instruction,
+    pub snapshot: BuySnapshot,
+}
+
+impl BuyInstructions {
+    /// All instructions in a single transaction
+    pub fn combined(&self) -> Vec<Instruction> {
+        let mut instructions = self.compute_budget.clone();
+        instructions.push(self.create_ata.clone());
+        instructions.push(self.buy.clone());
+        instructions
+    }
+}
+
+/// Shared in-flight bonding curve fetch
+type CurveFetch = Shared<BoxFuture<'static, Result<BondingCurveAccount, SniperError>>>;
+
+/// Fetch an account, retrying after each delay in `delays_ms`
+async fn fetch_account_with_retry(
+    rpc: Arc<RpcPool>,
+    pubkey: Pubkey,
+    delays_ms: &[u64],
+) -> Result<Account, SniperError> {
+    let mut last_error = String::from("no attempts configured");
 
+    for &delay_ms in delays_ms {
+        if delay_ms > 0 {
+            tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
+        }
+
+        let rpc = rpc.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            rpc.call(|client| client.get_account(&pubkey).map_err(|e| e.to_string()))
+        })
+        .await
+        .map_err(|e| SniperError::RpcError(format!("Fetch task failed: {}", e)))?;
+
+        match result {
+            Ok(account) => return Ok(account),
+            Err(e) => last_error = e,
+        }
+    }
+
+    Err(SniperError::RpcError(format!(
+        "Account not found after {} attempts: {}",
+        delays_ms.len(),
+        last_error
+    )))
+}
+
+async fn fetch_bonding_curve_with_retry(
+    rpc: Arc<RpcPool>,
+    bonding_curve: Pubkey,
+    delays_ms: &[u64],
+) -> Result<BondingCurveAccount, SniperError> {
+    let account = fetch_account_with_retry(rpc, bonding_curve, delays_ms).await?;
+
+    BondingCurveAccount::from_account_data(&account.data)
+}
+
+/// Decode the mint and freeze authorities from SPL mint account data
+pub fn mint_authorities(data: &[u8]) -> Result<(Option<Pubkey>, Option<Pubkey>), SniperError> {
+    let mint = spl_token::state::Mint::unpack(data).map_err(|e| {
+        SniperError::SerializationError(format!("Failed to deserialize mint: {}", e))
+    })?;
+
+    Ok((mint.mint_authority.into(), mint.freeze_authority.into()))
+}
+
+/// Compute unit price (micro-lamports per CU) that spends `priority_fee_lamports` in
+/// total over `compute_unit_limit` units, clamped to `MAX_COMPUTE_UNIT_PRICE_MICRO_LAMPORTS`
+pub fn compute_unit_price_micro_lamports(
+    priority_fee_lamports: u64,
+    compute_unit_limit: u32,
+) -> u64 {
+    if compute_unit_limit == 0 {
+        return 0;
+    }
+    let price = priority_fee_lamports as u128 * 1_000_000 / compute_unit_limit as u128;
+    price.min(MAX_COMPUTE_UNIT_PRICE_MICRO_LAMPORTS as u128) as u64
+}
+
+/// Lamports the runtime charges for `compute_unit_limit` units at `compute_unit_price`
+/// micro-lamports each, rounded up
+pub fn priority_fee_lamports(compute_unit_price: u64, compute_unit_limit: u32) -> u64 {
+    (compute_unit_price as u128 * compute_unit_limit as u128).div_ceil(1_000_000) as u64
+}
+
+/// Compute unit price and limit instructions
+fn compute_budget_with_price(compute_unit_price: u64, compute_unit_limit: u32) -> Vec<Instruction> {
+    vec![
+        ComputeBudgetInstruction::set_compute_unit_price(compute_unit_price),
+        ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
+    ]
+}
+
+/// Nearest-rank `percentile` (0-100) of `fees`, None when empty
+pub fn fee_percentile(fees: &[u64], percentile: u8) -> Option<u64> {
+    if fees.is_empty() {
+        return None;
+    }
+    let mut sorted = fees.to_vec();
+    sorted.sort_unstable();
+    let rank = (percentile.min(100) as usize * sorted.len()).div_ceil(100);
+    Some(sorted[rank.saturating_sub(1)])
+}
+
+/// Most SOL the buy instruction may take from the payer: amount plus slippage tolerance
+pub fn max_sol_cost(sol_amount: u64, slippage_bps: u64) -> u64 {
+    sol_amount.saturating_add((sol_amount as u128 * slippage_bps as u128 / 10_000) as u64)
+}
+
+/// Worst-case lamports a buy takes out of the wallet: the program's SOL-in limit
+/// plus priority fee, base fee and any rent for accounts it creates
+pub fn total_cost_estimate(
+    sol_amount: u64,
+    slippage_bps: u64,
+    priority_fee_lamports: u64,
+    base_fee_lamports: u64,
+    rent_lamports: u64,
+) -> u64 {
+    max_sol_cost(sol_amount, slippage_bps)
+        .saturating_add(priority_fee_lamports)
+        .saturating_add(base_fee_lamports)
+        .saturating_add(rent_lamports)
+}
+
+/// Lamports to buy with when spending `pct` percent of `balance`, shrunk so the worst-case
+/// cost with slippage still leaves `reserve_lamports` for fees and rent. None when the
+/// balance doesn't cover the reserve
+pub fn buy_amount_from_balance(
+    balance: u64,
+    pct: f64,
+    slippage_bps: u64,
+    reserve_lamports: u64,
+) -> Option<u64> {
+    let spendable = balance.checked_sub(reserve_lamports)?;
+    let affordable = (spendable as u128 * 10_000 / (10_000 + slippage_bps as u128)) as u64;
+    let amount = ((balance as f64 * pct / 100.0) as u64).min(affordable);
+    Some(amount).filter(|amount| *amount > 0)
+}
+
+/// Share of `total_supply` held by `amount`, in percent; 0 for an empty supply
+pub fn holding_pct(amount: u64, total_supply: u64) -> f64 {
+    if total_supply == 0 {
+        return 0.0;
+    }
+    amount as f64 / total_supply as f64 * 100.0
+}
+
+/// Creator whose vault the program pays: the curve's, which a set-creator can move away
+/// from the create instruction's. Curves that predate the field fall back to the latter
+pub fn vault_creator(token_info: &TokenInfo, bonding_curve_data: &BondingCurveAccount) -> Pubkey {
+    let curve_creator = bonding_curve_data.creator;
+    if curve_creator == Pubkey::default() {
+        return token_info.creator;
+    }
+    if curve_creator != token_info.creator {
+        warn!(
+            "CREATOR MISMATCH: {} created by {} but its curve pays {}",
+            token_info.symbol, token_info.creator, curve_creator
+        );
+    }
+    curve_creator
+}
+
+/// Jito bundle sender for a config with a block engine URL
+fn jito_executor(config: &Config) -> Option<JitoExecutor> {
+    config
+        .jito_block_engine_url
+        .as_deref()
+        .map(|url| JitoExecutor::new(shared_http_client(), url))
+}
+
+/// Fewest tokens a buy asks for: the quote less slippage tolerance, so a reserve move
+/// between quote and execution shrinks the fill instead of reverting it
+pub fn min_token_output(expected_tokens: u64, slippage_bps: u64) -> u64 {
+    let slippage_bps = slippage_bps.min(10_000) as u128;
+    ((expected_tokens as u128) * (10_000 - slippage_bps) / 10_000) as u64
+}
+
+/// Lowest acceptable SOL out for a sell after applying slippage tolerance
+pub fn min_sol_output(expected_sol: u64, slippage_bps: u64) -> u64 {
+    let slippage_bps = slippage_bps.min(10_000) as u128;
+    ((expected_sol as u128) * (10_000 - slippage_bps) / 10_000) as u64
+}
+
+/// Serialized size of a transaction on the wire
+pub fn transaction_size(transaction: &Transaction) -> usize {
+    bincode::serialized_size(transaction)
+        .map(|size| size as usize)
+        .unwrap_or(usize::MAX)
+}
+
+/// Sign the buy as one transaction, or as ATA setup + buy when it exceeds `max_size`.
+/// Setup carries no compute budget of its own; errors if the buy alone is still too big
+pub fn pack_buy_transactions(
+    payer: &Keypair,
+    instructions: &BuyInstructions,
+    recent_blockhash: Hash,
+    max_size: usize,
+) -> Result<Vec<Transaction>, SniperError> {
+    let combined = Transaction::new_signed_with_payer(
+        &instructions.combined(),
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+
+    let size = transaction_size(&combined);
+    if size <= max_size {
+        return Ok(vec![combined]);
+    }
+
+    warn!(
+        "Buy transaction is {} bytes (limit {}), splitting ATA creation",
+        size, max_size
+    );
+
+    let setup = Transaction::new_signed_with_payer(
+        std::slice::from_ref(&instructions.create_ata),
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    let mut buy = instructions.compute_budget.clone();
+    buy.push(instructions.buy.clone());
+    let buy =
+        Transaction::new_signed_with_payer(&buy, Some(&payer.pubkey()), &[payer], recent_blockhash);
+
+    let buy_size = transaction_size(&buy);
+    if buy_size > max_size {
+        return Err(SniperError::TransactionFailed(format!(
+            "Buy transaction is {} bytes without ATA creation (limit {})",
+            buy_size, max_size
+        )));
+    }
+    Ok(vec![setup, buy])
+}
+
+/// Sign the buy against a durable nonce, advancing it first as the runtime requires
+fn durable_buy_transaction(
+    payer: &Keypair,
+    instructions: &BuyInstructions,
+    nonce: Hash,
+    advance_nonce: Instruction,
+) -> Transaction {
+    let mut durable = vec![advance_nonce];
+    durable.extend(instructions.combined());
+    Transaction::new_signed_with_payer(&durable, Some(&payer.pubkey()), &[payer], nonce)
+}
+
+/// Builds and sends trades. Clones share the RPC pool, in-flight fetches, rotation
+/// counters and fees, so a clone can run a buy on another task
+#[derive(Clone)]
 pub struct TransactionExecutor {
-    rpc_client: RpcClient,
+    rpc: Arc<RpcPool>,
     config: Config,
+    inflight_curves: Arc<Mutex<HashMap<Pubkey, CurveFetch>>>,
+    /// Bundle sender when a Jito block engine is configured
+    jito: Option<JitoExecutor>,
+    /// Bundles sent, to rotate tip accounts
+    bundles_sent: Arc<AtomicUsize>,
+    /// Trades built, to rotate fee recipients
+    fee_recipient_turns: Arc<AtomicUsize>,
+    /// Fees from the last global account read
+    curve_fees: Arc<Mutex<CurveFees>>,
+    /// Last dynamic compute unit price and when it was looked up
+    recent_unit_price: Arc<Mutex<Option<(Instant, u64)>>>,
 }
 
 impl TransactionExecutor {
     pub fn new(config: Config) -> Self {
-        let rpc_client = RpcClient::new_with_commitment(
-            config.rpc_endpoint.clone(),
-            CommitmentConfig::confirmed(),
+        let rpc = RpcPool::from_endpoints(&config.rpc_endpoint_list(), config.commitment_config());
+
+        Self::with_rpc_pool(rpc, config)
+    }
+
+    /// Executor calling through `rpc_client`, which the caller may share, in place of the
+    /// primary endpoint. Fallback `rpc_endpoints` still get clients of their own
+    pub fn with_rpc_client(rpc_client: Arc<RpcClient>, config: Config) -> Self {
+        let mut clients = vec![rpc_client.clone()];
+        for endpoint in &config.rpc_endpoints {
+            if *endpoint != rpc_client.url() && !endpoint.is_empty() {
+                clients.push(Arc::new(RpcClient::new_with_commitment(
+                    endpoint.clone(),
+                    config.commitment_config(),
+                )));
+            }
+        }
+        Self::with_rpc_pool(RpcPool::from_shared(clients), config)
+    }
+
+    /// Client for the primary RPC endpoint
+    pub fn rpc_client(&self) -> Arc<RpcClient> {
+        self.rpc.primary()
+    }
+
+    pub(crate) fn with_rpc_pool(rpc: RpcPool, config: Config) -> Self {
+        Self {
+            rpc: Arc::new(rpc),
+            jito: jito_executor(&config),
+            config,
+            inflight_curves: Arc::new(Mutex::new(HashMap::new())),
+            bundles_sent: Arc::new(AtomicUsize::new(0)),
+            fee_recipient_turns: Arc::new(AtomicUsize::new(0)),
+            curve_fees: Arc::new(Mutex::new(CurveFees::default())),
+            recent_unit_price: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Replace the config used for subsequent fetches and buys
+    pub fn set_config(&mut self, config: Config) {
+        self.jito = jito_executor(&config);
+        self.config = config;
+    }
+
+    /// Run a blocking RPC call on the blocking pool, keeping the async workers free
+    async fn call_blocking<T: Send + 'static>(
+        &self,
+        op: impl Fn(&RpcClient) -> Result<T, String> + Send + 'static,
+    ) -> Result<T, String> {
+        let rpc = self.rpc.clone();
+        tokio::task::spawn_blocking(move || rpc.call(op))
+            .await
+            .map_err(|e| format!("RPC task failed: {}", e))?
+    }
+
+    pub async fn get_slot(&self) -> Result<u64, SniperError> {
+        self.call_blocking(|client| client.get_slot().map_err(|e| e.to_string()))
+            .await
+            .map_err(|e| SniperError::RpcError(format!("Failed to fetch slot: {}", e)))
+    }
+
+    /// SOL balance of `owner` in lamports
+    pub async fn fetch_sol_balance(&self, owner: &Pubkey) -> Result<u64, SniperError> {
+        let owner = *owner;
+        self.call_blocking(move |client| client.get_balance(&owner).map_err(|e| e.to_string()))
+            .await
+            .map_err(|e| SniperError::RpcError(format!("Failed to fetch balance: {}", e)))
+    }
+
+    /// Token balance of `owner`'s ATA for `mint`, or None if the account doesn't exist
+    pub async fn fetch_token_balance(
+        &self,
+        owner: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<Option<u64>, SniperError> {
+        let ata = spl_associated_token_account::get_associated_token_address(owner, mint);
+
+        let account = self
+            .call_blocking(move |client| {
+                client
+                    .get_account_with_commitment(&ata, client.commitment())
+                    .map_err(|e| e.to_string())
+            })
+            .await
+            .map_err(|e| SniperError::RpcError(format!("Failed to fetch token account: {}", e)))?
+            .value;
+
+        match account {
+            Some(account) => spl_token::state::Account::unpack(&account.data)
+                .map(|token_account| Some(token_account.amount))
+                .map_err(|e| {
+                    SniperError::SerializationError(format!(
+                        "Failed to deserialize token account: {}",
+                        e
+                    ))
+                }),
+            None => Ok(None),
+        }
+    }
+
+    /// Tokens of `mint` in the creator's associated token account, 0 if it doesn't exist yet
+    pub async fn fetch_creator_holdings(
+        &self,
+        creator: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<u64, SniperError> {
+        Ok(self
+            .fetch_token_balance(creator, mint)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// Mint and freeze authorities of `mint`
+    pub async fn fetch_mint_authorities(
+        &self,
+        mint: &Pubkey,
+    ) -> Result<(Option<Pubkey>, Option<Pubkey>), SniperError> {
+        let mint = *mint;
+        let account = self
+            .call_blocking(move |client| client.get_account(&mint).map_err(|e| e.to_string()))
+            .await
+            .map_err(|e| SniperError::RpcError(format!("Failed to fetch mint account: {}", e)))?;
+
+        mint_authorities(&account.data)
+    }
+
+    pub async fn fetch_global_account(&self) -> Result<GlobalAccount, SniperError> {
+        let global_pda = derive_global_pda()?;
+
+        let account = fetch_account_with_retry(
+            self.rpc.clone(),
+            global_pda,
+            &self.config.fetch_retry_delays_ms,
+        )
+        .await
+        .map_err(|e| SniperError::RpcError(format!("Failed to fetch global account: {}", e)))?;
+
+        let global = GlobalAccount::from_account_data(&account.data)?;
+        *self.curve_fees.lock().unwrap() = global.fees();
+        Ok(global)
+    }
+
+    /// Fees from the most recent global account fetch, or the defaults before the first
+    pub fn curve_fees(&self) -> CurveFees {
+        *self.curve_fees.lock().unwrap()
+    }
+
+    pub async fn fetch_bonding_curve_data(
+        &self,
+        bonding_curve: &Pubkey,
+    ) -> Result<BondingCurveAccount, SniperError> {
+        if !self.config.coalesce_curve_fetches {
+            return fetch_bonding_curve_with_retry(
+                self.rpc.clone(),
+                *bonding_curve,
+                &self.config.fetch_retry_delays_ms,
+            )
+            .await;
+        }
+
+        // concurrent callers for the same curve share one request
+        let fetch = {
+            let mut inflight = self.inflight_curves.lock().unwrap();
+            inflight
+                .entry(*bonding_curve)
+                .or_insert_with(|| {
+                    let rpc = self.rpc.clone();
+                    let inflight_curves = self.inflight_curves.clone();
+                    let key = *bonding_curve;
+                    let delays_ms = self.config.fetch_retry_delays_ms.clone();
+                    async move {
+                        let result = fetch_bonding_curve_with_retry(rpc, key, &delays_ms).await;
+                        inflight_curves.lock().unwrap().remove(&key);
+                        result
+                    }
+                    .boxed()
+                    .shared()
+                })
+                .clone()
+        };
+
+        fetch.await
+    }
+
+    /// Assemble the compute budget, ATA creation and buy instructions
+    pub fn build_buy_instructions(
+        &self,
+        payer: &Keypair,
+        token_info: &TokenInfo,
+        bonding_curve_data: &BondingCurveAccount,
+        sol_amount: u64,
+        global_account: &GlobalAccount,
+    ) -> Result<BuyInstructions, SniperError> {
+        let fee_recipient = &self.select_fee_recipient(global_account)?;
+        let fees = global_account.fees();
+        let expected_tokens = bonding_curve_data.get_buy_price_with_fees(
+            sol_amount,
+            fees.fee_basis_points,
+            fees.creator_fee_basis_points,
+        )?;
+
+        // The program sells exactly `amount` tokens at the price on execution and reverts
+        // if that costs more than `max_sol_cost`. Slippage widens both sides: we ask for the
+        // quote less slippage and allow the SOL in plus slippage.
+        let buy_instruction_data = BuyInstruction {
+            amount: min_token_output(expected_tokens, self.config.max_slippage_bps),
+            max_sol_cost: max_sol_cost(sol_amount, self.config.max_slippage_bps),
+        };
+        // the floor applies to what the instruction guarantees, not the raw quote
+        if buy_instruction_data.amount < self.config.min_expected_tokens {
+            return Err(SniperError::InsufficientLiquidity(format!(
+                "{} lamports buys {} tokens after slippage, below the {} minimum",
+                sol_amount, buy_instruction_data.amount, self.config.min_expected_tokens
+            )));
+        }
+        let compute_unit_price = self.compute_unit_price(
+            self.config.compute_unit_limit,
+            &[token_info.bonding_curve, token_info.mint],
+        );
+        let snapshot = BuySnapshot::new(
+            bonding_curve_data,
+            expected_tokens,
+            buy_instruction_data.amount,
+            buy_instruction_data.max_sol_cost,
+            self.config.max_slippage_bps,
+            priority_fee_lamports(compute_unit_price, self.config.compute_unit_limit),
+        );
+
+        let buy = buy_instruction_data.create_instruction(
+            payer,
+            &token_info.mint,
+            fee_recipient,
+            &vault_creator(token_info, bonding_curve_data),
+        )?;
+
+        let compute_budget =
+            compute_budget_with_price(compute_unit_price, self.config.compute_unit_limit);
+
+        // idempotent so re-buys and retries don't revert on an existing ATA
+        let create_ata =
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &payer.pubkey(),
+                &payer.pubkey(),
+                &token_info.mint,
+                &spl_token::id(),
+            );
+
+        Ok(BuyInstructions {
+            compute_budget,
+            create_ata,
+            buy,
+            snapshot,
+        })
+    }
+
+    /// Per-CU price: a percentile of recent prioritization fees on `accounts` within the
+    /// configured bounds when dynamic, else `priority_fee_sol` spread over the limit.
+    /// A dynamic price is reused for `PRIORITY_FEE_CACHE_TTL`: recent fees are mostly set
+    /// by the program accounts every trade shares, so the lookup stays off most buys
+    fn compute_unit_price(&self, compute_unit_limit: u32, accounts: &[Pubkey]) -> u64 {
+        let static_price =
+            compute_unit_price_micro_lamports(self.config.priority_fee_sol, compute_unit_limit);
+        if !self.config.dynamic_priority_fee {
+            return static_price;
+        }
+        let now = Instant::now();
+        if let Some((looked_up_at, price)) = *self.recent_unit_price.lock().unwrap() {
+            if now.saturating_duration_since(looked_up_at) < PRIORITY_FEE_CACHE_TTL {
+                return price;
+            }
+        }
+
+        let recent = self.rpc.call(|client| {
+            client
+                .get_recent_prioritization_fees(accounts)
+                .map_err(|e| e.to_string())
+        });
+        let fees: Vec<u64> = match recent {
+            Ok(recent) => recent.iter().map(|fee| fee.prioritization_fee).collect(),
+            Err(e) => {
+                warn!("Prioritization fee lookup failed, using static fee: {}", e);
+                return static_price;
+            }
+        };
+
+        match fee_percentile(&fees, self.config.priority_fee_percentile) {
+            Some(fee) => {
+                let price = fee.clamp(self.config.min_priority_fee, self.config.max_priority_fee);
+                *self.recent_unit_price.lock().unwrap() = Some((now, price));
+                price
+            }
+            None => static_price,
+        }
+    }
+
+    /// Fee recipient from the global account, checked against the known set if configured
+    pub fn fee_recipient(&self, global_account: &GlobalAccount) -> Result<Pubkey, SniperError> {
+        let recipient = global_account.fee_recipient;
+        if self.config.validate_fee_recipient && !global_account.is_known_fee_recipient(&recipient)
+        {
+            return Err(SniperError::TransactionFailed(format!(
+                "Fee recipient {} is not a known pump fee recipient",
+                recipient
+            )));
+        }
+        Ok(recipient)
+    }
+
+    /// Fee recipient for the next trade. Pump accepts any of the `fee_recipients` entries
+    /// and rotates which it expects, so each trade takes the next populated entry in turn,
+    /// spreading trades across them. Falls back to the singular `fee_recipient` when the
+    /// array is empty
+    pub fn select_fee_recipient(
+        &self,
+        global_account: &GlobalAccount,
+    ) -> Result<Pubkey, SniperError> {
+        let turn = self.fee_recipient_turns.fetch_add(1, Ordering::Relaxed);
+        match global_account.rotated_fee_recipient(turn) {
+            Some(recipient) => Ok(recipient),
+            None => self.fee_recipient(global_account),
+        }
+    }
+
+    /// Compute unit price and limit; the price comes from recent fees on `accounts` in
+    /// dynamic mode
+    fn compute_budget_instructions(
+        &self,
+        compute_unit_limit: u32,
+        accounts: &[Pubkey],
+    ) -> Vec<Instruction> {
+        compute_budget_with_price(
+            self.compute_unit_price(compute_unit_limit, accounts),
+            compute_unit_limit,
+        )
+    }
+
+    /// Wrap SOL, buy on the PumpSwap pool and unwrap what's left
+    #[allow(clippy::too_many_arguments)]
+    pub fn build_amm_buy_instructions(
+        &self,
+        payer: &Keypair,
+        pool_address: &Pubkey,
+        pool: &PumpSwapPool,
+        global_config: &PumpSwapGlobalConfig,
+        base_reserve: u64,
+        quote_reserve: u64,
+        sol_amount: u64,
+    ) -> Result<Vec<Instruction>, SniperError> {
+        let base_amount_out = amm_buy_quote(
+            sol_amount,
+            base_reserve,
+            quote_reserve,
+            global_config.total_fee_basis_points(),
+        );
+        // the swap delivers exactly `base_amount_out`, so that is what the floor applies to
+        if base_amount_out < self.config.min_expected_tokens {
+            return Err(SniperError::InsufficientLiquidity(format!(
+                "PumpSwap pool {} returns {} tokens for {} lamports, below the {} minimum",
+                pool_address, base_amount_out, sol_amount, self.config.min_expected_tokens
+            )));
+        }
+
+        let protocol_fee_recipient = global_config
+            .protocol_fee_recipients
+            .iter()
+            .find(|recipient| **recipient != Pubkey::default())
+            .ok_or_else(|| {
+                SniperError::InvalidConfig("PumpSwap has no protocol fee recipient".to_string())
+            })?;
+
+        let max_quote_amount_in = max_sol_cost(sol_amount, self.config.max_slippage_bps);
+        let owner = payer.pubkey();
+        let wsol_account =
+            spl_associated_token_account::get_associated_token_address(&owner, &pool.quote_mint);
+
+        let mut instructions = self.compute_budget_instructions(
+            self.config
+                .compute_unit_limit
+                .max(AMM_MIN_COMPUTE_UNIT_LIMIT),
+            &[*pool_address, pool.base_mint],
+        );
+        for mint in [&pool.base_mint, &pool.quote_mint] {
+            instructions.push(
+                spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                    &owner,
+                    &owner,
+                    mint,
+                    &spl_token::id(),
+                ),
+            );
+        }
+        instructions.push(solana_sdk::system_instruction::transfer(
+            &owner,
+            &wsol_account,
+            max_quote_amount_in,
+        ));
+        instructions.push(
+            spl_token::instruction::sync_native(&spl_token::id(), &wsol_account)
+                .map_err(|e| SniperError::TransactionFailed(e.to_string()))?,
+        );
+        instructions.push(
+            PumpSwapBuyInstruction {
+                base_amount_out,
+                max_quote_amount_in,
+            }
+            .create_instruction(payer, pool_address, pool, protocol_fee_recipient)?,
+        );
+        instructions.push(
+            spl_token::instruction::close_account(
+                &spl_token::id(),
+                &wsol_account,
+                &owner,
+                &owner,
+                &[],
+            )
+            .map_err(|e| SniperError::TransactionFailed(e.to_string()))?,
+        );
+
+        Ok(instructions)
+    }
+
+    pub fn build_buy_transaction(
+        &self,
+        payer: &Keypair,
+        token_info: &TokenInfo,
+        bonding_curve_data: &BondingCurveAccount,
+        sol_amount: u64,
+        global_account: &GlobalAccount,
+    ) -> Result<(Transaction, BuySnapshot), SniperError> {
+        let instructions = self.build_buy_instructions(
+            payer,
+            token_info,
+            bonding_curve_data,
+            sol_amount,
+            global_account,
+        )?;
+
+        let transaction = match self.fetch_advance_nonce(&payer.pubkey())? {
+            Some((nonce, advance_nonce)) => {
+                durable_buy_transaction(payer, &instructions, nonce, advance_nonce)
+            }
+            None => Transaction::new_signed_with_payer(
+                &instructions.combined(),
+                Some(&payer.pubkey()),
+                &[payer],
+                self.latest_blockhash()?,
+            ),
+        };
+
+        Ok((transaction, instructions.snapshot))
+    }
+
+    /// Build the buy, splitting ATA creation into a preceding transaction if oversized
+    pub fn build_buy_transactions(
+        &self,
+        payer: &Keypair,
+        token_info: &TokenInfo,
+        bonding_curve_data: &BondingCurveAccount,
+        sol_amount: u64,
+        global_account: &GlobalAccount,
+    ) -> Result<(Vec<Transaction>, BuySnapshot), SniperError> {
+        let instructions = self.build_buy_instructions(
+            payer,
+            token_info,
+            bonding_curve_data,
+            sol_amount,
+            global_account,
+        )?;
+
+        // a nonce is spent by the first transaction that advances it, so a durable buy
+        // stays whole
+        if let Some((nonce, advance_nonce)) = self.fetch_advance_nonce(&payer.pubkey())? {
+            let transaction = durable_buy_transaction(payer, &instructions, nonce, advance_nonce);
+            return Ok((vec![transaction], instructions.snapshot));
+        }
+        let recent_blockhash = self.latest_blockhash()?;
+
+        let max_size = if self.config.split_oversized_buy {
+            MAX_TRANSACTION_SIZE
+        } else {
+            usize::MAX
+        };
+
+        let transactions = pack_buy_transactions(payer, &instructions, recent_blockhash, max_size)?;
+        Ok((transactions, instructions.snapshot))
+    }
+
+    fn latest_blockhash(&self) -> Result<Hash, SniperError> {
+        self.rpc
+            .call(|client| client.get_latest_blockhash().map_err(|e| e.to_string()))
+            .map_err(SniperError::RpcError)
+    }
+
+    /// The durable nonce to sign a buy against and the instruction advancing it, when
+    /// `nonce_account` is set. The payer has to be the nonce authority, since it is the
+    /// only signer
+    fn fetch_advance_nonce(
+        &self,
+        payer: &Pubkey,
+    ) -> Result<Option<(Hash, Instruction)>, SniperError> {
+        let Some(nonce_account) = self.config.nonce_account else {
+            return Ok(None);
+        };
+        let authority = self.config.nonce_authority.unwrap_or(*payer);
+        if authority != *payer {
+            return Err(SniperError::InvalidConfig(format!(
+                "Nonce authority {} must be the payer {}",
+                authority, payer
+            )));
+        }
+
+        let account = self
+            .rpc
+            .call(|client| {
+                client
+                    .get_account(&nonce_account)
+                    .map_err(|e| e.to_string())
+            })
+            .map_err(|e| SniperError::RpcError(format!("Failed to fetch nonce account: {}", e)))?;
+        let data = nonce_utils::data_from_account(&account).map_err(|e| {
+            SniperError::SerializationError(format!("Failed to read nonce account: {}", e))
+        })?;
+        if data.authority != authority {
+            return Err(SniperError::InvalidConfig(format!(
+                "Nonce account {} is controlled by {}, not {}",
+                nonce_account, data.authority, authority
+            )));
+        }
+
+        Ok(Some((
+            data.blockhash(),
+            system_instruction::advance_nonce_account(&nonce_account, &authority),
+        )))
+    }
+
+    pub async fn execute_buy(
+        &self,
+        payer: &Keypair,
+        token_info: &TokenInfo,
+        sol_amount: u64,
+    ) -> Result<BuyOutcome, SniperError> {
+        info!(
+            "FAST BUY: {} - {} SOL",
+            token_info.symbol,
+            sol_amount as f64 / 1e9
+        );
+
+        // fetch parallel
+        let (global_result, bonding_result) = tokio::join!(
+            self.fetch_global_account(),
+            self.fetch_bonding_curve_data(&token_info.bonding_curve)
+        );
+
+        let global_account = global_result?;
+        let bonding_curve_data = bonding_result?;
+
+        if bonding_curve_data.complete {
+            if !self.config.trade_migrated_on_amm {
+                return Err(SniperError::BondingCurveComplete);
+            }
+            return self.execute_amm_buy(payer, token_info, sol_amount).await;
+        }
+
+        let (mut transactions, snapshot) = self.build_buy_transactions(
+            payer,
+            token_info,
+            &bonding_curve_data,
+            sol_amount,
+            &global_account,
+        )?;
+        let transaction = transactions.pop().ok_or_else(|| {
+            SniperError::TransactionFailed("No buy transaction built".to_string())
+        })?;
+
+        // split ATA creation has to land before the buy
+        for setup in transactions {
+            let rpc = self.rpc.clone();
+            let signature = tokio::task::spawn_blocking(move || {
+                rpc.call(|client| {
+                    client
+                        .send_and_confirm_transaction(&setup)
+                        .map_err(|e| e.to_string())
+                })
+            })
+            .await
+            .map_err(|e| SniperError::TransactionFailed(format!("Setup task failed: {}", e)))?
+            .map_err(SniperError::TransactionFailed)?;
+            info!(
+                "ATA setup confirmed for {} - TX: {}",
+                token_info.display_name(),
+                signature
+            );
+        }
+
+        if let Some(jito) = &self.jito {
+            match self.send_jito_bundle(jito, payer, &transaction).await {
+                Ok(bundle_id) => {
+                    let signature = transaction.signatures[0];
+                    info!(
+                        "Buy bundle sent for {} - bundle {} - TX: {}",
+                        token_info.display_name(),
+                        bundle_id,
+                        signature
+                    );
+                    return self.confirm_buy(payer, &transaction, Some(snapshot)).await;
+                }
+                Err(e) => warn!(
+                    "Jito bundle failed for {}, sending over RPC: {}",
+                    token_info.display_name(),
+                    e
+                ),
+            }
+        }
+
+        self.send_buy(payer, &transaction, token_info, Some(snapshot))
+            .await
+    }
+
+    /// Bundle `transaction` with a tip transfer sharing its blockhash
+    async fn send_jito_bundle(
+        &self,
+        jito: &JitoExecutor,
+        payer: &Keypair,
+        transaction: &Transaction,
+    ) -> Result<String> {
+        let tip_account = tip_account(self.bundles_sent.fetch_add(1, Ordering::Relaxed));
+        // a durable buy's hash is its nonce, which the tip can't use without advancing it
+        let tip_blockhash = if self.config.nonce_account.is_some() {
+            self.latest_blockhash()?
+        } else {
+            transaction.message.recent_blockhash
+        };
+        let tip = tip_transaction(
+            payer,
+            &tip_account,
+            self.config.jito_tip_lamports,
+            tip_blockhash,
+        );
+        jito.send_bundle(&[transaction.clone(), tip]).await
+    }
+
+    /// Buy a migrated token on its canonical PumpSwap pool
+    pub async fn execute_amm_buy(
+        &self,
+        payer: &Keypair,
+        token_info: &TokenInfo,
+        sol_amount: u64,
+    ) -> Result<BuyOutcome, SniperError> {
+        let pool_address = derive_pump_swap_pool_pda(&token_info.mint)?;
+        let (pool_result, config_result) = tokio::join!(
+            self.fetch_borsh_account::<PumpSwapPool>(pool_address, "PumpSwap pool"),
+            self.fetch_borsh_account::<PumpSwapGlobalConfig>(
+                derive_amm_global_config_pda()?,
+                "PumpSwap global config"
+            )
+        );
+        let pool = pool_result?;
+        let global_config = config_result?;
+
+        let base_reserve = self.fetch_token_account_amount(&pool.pool_base_token_account)?;
+        let quote_reserve = self.fetch_token_account_amount(&pool.pool_quote_token_account)?;
+
+        let instructions = self.build_amm_buy_instructions(
+            payer,
+            &pool_address,
+            &pool,
+            &global_config,
+            base_reserve,
+            quote_reserve,
+            sol_amount,
+        )?;
+
+        let recent_blockhash = self
+            .rpc
+            .call(|client| client.get_latest_blockhash().map_err(|e| e.to_string()))
+            .map_err(SniperError::RpcError)?;
+        let transaction = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[payer],
+            recent_blockhash,
+        );
+
+        info!(
+            "AMM BUY: {} migrated, buying on pool {}",
+            token_info.display_name(),
+            pool_address
+        );
+        self.send_buy(payer, &transaction, token_info, None).await
+    }
+
+    async fn fetch_borsh_account<T: borsh::BorshDeserialize>(
+        &self,
+        pubkey: Pubkey,
+        name: &str,
+    ) -> Result<T, SniperError> {
+        let account =
+            fetch_account_with_retry(self.rpc.clone(), pubkey, &self.config.fetch_retry_delays_ms)
+                .await
+                .map_err(|e| SniperError::RpcError(format!("Failed to fetch {}: {}", name, e)))?;
+
+        solana_sdk::borsh1::try_from_slice_unchecked::<T>(&account.data).map_err(|e| {
+            SniperError::SerializationError(format!("Failed to deserialize {}: {}", name, e))
+        })
+    }
+
+    fn fetch_token_account_amount(&self, token_account: &Pubkey) -> Result<u64, SniperError> {
+        let balance = self
+            .rpc
+            .call(|client| {
+                client
+                    .get_token_account_balance(token_account)
+                    .map_err(|e| e.to_string())
+            })
+            .map_err(|e| SniperError::RpcError(format!("Failed to fetch reserves: {}", e)))?;
+
+        balance
+            .amount
+            .parse()
+            .map_err(|_| SniperError::SerializationError(format!("Bad amount {}", balance.amount)))
+    }
+
+    /// Send a signed buy without preflight, confirming it if configured. A durable buy
+    /// can only land once, so failed sends resend the same signed transaction up to
+    /// `max_buy_retries` times rather than leaving it to be rebuilt and signed again
+    async fn send_buy(
+        &self,
+        payer: &Keypair,
+        transaction: &Transaction,
+        token_info: &TokenInfo,
+        snapshot: Option<BuySnapshot>,
+    ) -> Result<BuyOutcome, SniperError> {
+        use solana_client::rpc_config::RpcSendTransactionConfig;
+
+        let send_config = RpcSendTransactionConfig {
+            skip_preflight: true,
+            preflight_commitment: Some(self.config.stream_commitment()),
+            encoding: None,
+            max_retries: Some(0),
+            min_context_slot: None,
+        };
+
+        let durable = uses_durable_nonce(transaction).is_some();
+        let mut resends = 0;
+        let signature = loop {
+            let result = self.rpc.call(|client| {
+                client
+                    .send_transaction_with_config(transaction, send_config)
+                    .map_err(|e| e.to_string())
+            });
+            match result {
+                Ok(signature) => break signature,
+                Err(e) if durable && resends < self.config.max_buy_retries => {
+                    resends += 1;
+                    warn!(
+                        "Durable buy send {} for {} failed, resending: {}",
+                        resends,
+                        token_info.display_name(),
+                        e
+                    );
+                    tokio::time::sleep(DURABLE_RESEND_BACKOFF * resends).await;
+                }
+                // signing again could land a second buy if an earlier send made it through
+                Err(e) if durable => return Err(SniperError::TransactionFailed(e)),
+                Err(e) => return Err(SniperError::from_send_error(e)),
+            }
+        };
+
+        info!(
+            "Buy transaction sent for {} - TX: {}",
+            token_info.display_name(),
+            signature
         );
 
-        Self { rpc_client, config }
+        self.confirm_buy(payer, transaction, snapshot).await
     }
 
-    pub async fn fetch_global_account(&self) -> Result<GlobalAccount, SniperError> {
-        let global_pda = derive_global_pda()?;
+    /// Wait for a sent buy per `confirm_timeout_secs`. A durable buy that times out is
+    /// cancelled by advancing its nonce, so it can't land after being counted as unfilled
+    async fn confirm_buy(
+        &self,
+        payer: &Keypair,
+        transaction: &Transaction,
+        snapshot: Option<BuySnapshot>,
+    ) -> Result<BuyOutcome, SniperError> {
+        let signature = transaction.signatures[0];
+        let mut slot = match self.config.confirm_timeout_secs {
+            0 => None,
+            secs => {
+                self.confirm_signature(&signature, Duration::from_secs(secs))
+                    .await?
+            }
+        };
 
-        match self.rpc_client.get_account(&global_pda) {
-            Ok(account) => {
-                match solana_sdk::borsh1::try_from_slice_unchecked::<GlobalAccount>(&account.data) {
-                    Ok(global_data) => Ok(global_data),
-                    Err(e) => Err(SniperError::SerializationError(format!(
-                        "Failed to deserialize global account: {}",
-                        e
-                    ))),
+        if slot.is_none() && self.config.confirm_timeout_secs > 0 {
+            if let Some(nonce_account) = self
+                .config
+                .nonce_account
+                .filter(|_| uses_durable_nonce(transaction).is_some())
+            {
+                match self.advance_nonce(payer, &nonce_account).await {
+                    // the buy either landed before the advance or never will
+                    Ok(_) => slot = self.confirm_signature(&signature, Duration::ZERO).await?,
+                    Err(e) => warn!(
+                        "Failed to advance nonce {} after unconfirmed buy {}: {}",
+                        nonce_account, signature, e
+                    ),
                 }
             }
-            Err(e) => Err(SniperError::RpcError(format!(
-                "Failed to fetch global account: {}",
-                e
-            ))),
         }
+
+        Ok(BuyOutcome {
+            signature,
+            confirmed: slot.is_some(),
+            slot,
+            snapshot,
+        })
     }
 
-    pub async fn fetch_bonding_curve_data(
+    /// Advance `nonce_account` on its own, invalidating anything signed against its nonce
+    async fn advance_nonce(
         &self,
-        bonding_curve: &solana_sdk::pubkey::Pubkey,
-    ) -> Result<BondingCurveAccount, SniperError> {
-        // progressive retry: 0ms, 100ms, 200ms
-        let delays = [0, 100, 200];
+        payer: &Keypair,
+        nonce_account: &Pubkey,
+    ) -> Result<Signature, SniperError> {
+        let transaction = Transaction::new_signed_with_payer(
+            &[system_instruction::advance_nonce_account(
+                nonce_account,
+                &payer.pubkey(),
+            )],
+            Some(&payer.pubkey()),
+            &[payer],
+            self.latest_blockhash()?,
+        );
+        let rpc = self.rpc.clone();
+        tokio::task::spawn_blocking(move || {
+            rpc.call(|client| {
+                client
+                    .send_and_confirm_transaction(&transaction)
+                    .map_err(|e| e.to_string())
+            })
+        })
+        .await
+        .map_err(|e| SniperError::TransactionFailed(format!("Nonce advance task failed: {}", e)))?
+        .map_err(SniperError::TransactionFailed)
+    }
 
-        for (attempt, &delay_ms) in delays.iter().enumerate() {
-            if delay_ms > 0 {
-                tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
-            }
+    /// Poll until `signature` reaches confirmed commitment, returning its slot, or None on timeout.
+    /// Errors if the transaction landed but failed.
+    pub async fn confirm_signature(
+        &self,
+        signature: &Signature,
+        timeout: Duration,
+    ) -> Result<Option<u64>, SniperError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let status = self
+                .rpc
+                .call(|client| {
+                    client
+                        .get_signature_statuses(&[*signature])
+                        .map_err(|e| e.to_string())
+                })
+                .map(|response| response.value.into_iter().next().flatten());
 
-            match self.rpc_client.get_account(bonding_curve) {
-                Ok(account) => {
-                    match solana_sdk::borsh1::try_from_slice_unchecked::<BondingCurveAccount>(
-                        &account.data,
-                    ) {
-                        Ok(bonding_curve_data) => return Ok(bonding_curve_data),
-                        Err(e) => {
-                            return Err(SniperError::SerializationError(format!(
-                                "Failed to deserialize bonding curve: {}",
-                                e
-                            )))
-                        }
+            match status {
+                Ok(Some(status)) => {
+                    if let Some(err) = status.err {
+                        return Err(SniperError::TransactionFailed(format!(
+                            "{} failed on-chain: {}",
+                            signature, err
+                        )));
+                    }
+                    if status.satisfies_commitment(CommitmentConfig::confirmed()) {
+                        return Ok(Some(status.slot));
                     }
                 }
-                Err(e) if attempt == delays.len() - 1 => {
-                    return Err(SniperError::RpcError(format!(
-                        "Account not found after {} attempts: {}",
-                        delays.len(),
-                        e
-                    )));
-                }
-                Err(_) => {
-                    continue;
-                }
+                Ok(None) => {}
+                Err(e) => warn!("Signature status check failed for {}: {}", signature, e),
             }
-        }
 
-        Err(SniperError::RpcError("Unexpected error".to_string()))
+            if Instant::now() + CONFIRM_POLL_INTERVAL > deadline {
+                return Ok(None);
+            }
+            tokio::time::sleep(CONFIRM_POLL_INTERVAL).await;
+        }
     }
 
-    pub fn build_buy_transaction(
+    pub fn build_sell_transaction(
         &self,
         payer: &Keypair,
         token_info: &TokenInfo,
         bonding_curve_data: &BondingCurveAccount,
-        sol_amount: u64,
-        fee_recipient: &solana_sdk::pubkey::Pubkey,
+        token_amount: u64,
+        global_account: &GlobalAccount,
     ) -> Result<Transaction, SniperError> {
-        let expected_tokens = bonding_curve_data.get_buy_price(sol_amount)?;
-
-        // slippage protection
-        let max_sol_cost = sol_amount + (sol_amount * self.config.max_slippage_bps / 10000);
+        if bonding_curve_data.complete {
+            return Err(SniperError::BondingCurveComplete);
+        }
 
-        let buy_instruction_data = BuyInstruction {
-            amount: expected_tokens,
-            max_sol_cost,
-        };
+        let expected_sol = bonding_curve_data
+            .get_sell_price(token_amount, global_account.fees().total_basis_points())?;
 
-        let buy_instruction = buy_instruction_data.create_instruction(
+        let sell_instruction = SellInstruction {
+            amount: token_amount,
+            min_sol_output: min_sol_output(expected_sol, self.config.max_slippage_bps),
+        }
+        .create_instruction(
             payer,
             &token_info.mint,
-            fee_recipient,
-            &token_info.creator,
+            &self.select_fee_recipient(global_account)?,
+            &vault_creator(token_info, bonding_curve_data),
         )?;
 
-        let mut instructions = Vec::with_capacity(4);
-
-        // priority fee
-        let priority_fee_microlamports =
-            (self.config.priority_fee_sol * 1_000_000) / self.config.compute_unit_limit as u64;
-        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
-            priority_fee_microlamports,
-        ));
-
-        // compute limit
-        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
+        let mut instructions = self.compute_budget_instructions(
             self.config.compute_unit_limit,
-        ));
-
-        instructions.push(
-            spl_associated_token_account::instruction::create_associated_token_account(
-                &payer.pubkey(),
-                &payer.pubkey(),
-                &token_info.mint,
-                &spl_token::id(),
-            ),
+            &[token_info.bonding_curve, token_info.mint],
         );
-
-        instructions.push(buy_instruction);
+        instructions.push(sell_instruction);
 
         let recent_blockhash = self
-            .rpc_client
-            .get_latest_blockhash()
-            .map_err(|e| SniperError::RpcError(e.to_string()))?;
+            .rpc
+            .call(|client| client.get_latest_blockhash().map_err(|e| e.to_string()))
+            .map_err(SniperError::RpcError)?;
 
-        let transaction = Transaction::new_signed_with_payer(
+        Ok(Transaction::new_signed_with_payer(
             &instructions,
             Some(&payer.pubkey()),
             &[payer],
             recent_blockhash,
-        );
-
-        Ok(transaction)
+        ))
     }
 
-    pub async fn execute_buy(
+    pub async fn execute_sell(
         &self,
         payer: &Keypair,
         token_info: &TokenInfo,
-        sol_amount: u64,
+        token_amount: u64,
     ) -> Result<Signature, SniperError> {
-        info!(
-            "FAST BUY: {} - {} SOL",
-            token_info.symbol,
-            sol_amount as f64 / 1e9
-        );
+        info!("SELL: {} - {} tokens", token_info.symbol, token_amount);
 
-        // fetch parallel
         let (global_result, bonding_result) = tokio::join!(
             self.fetch_global_account(),
             self.fetch_bonding_curve_data(&token_info.bonding_curve)
@@ -181,31 +1335,39 @@ impl TransactionExecutor {
         let global_account = global_result?;
         let bonding_curve_data = bonding_result?;
 
-        let transaction = self.build_buy_transaction(
+        if bonding_curve_data.complete {
+            return Err(SniperError::BondingCurveComplete);
+        }
+
+        let transaction = self.build_sell_transaction(
             payer,
             token_info,
             &bonding_curve_data,
-            sol_amount,
-            &global_account.fee_recipient,
+            token_amount,
+            &global_account,
         )?;
 
         use solana_client::rpc_config::RpcSendTransactionConfig;
 
         let send_config = RpcSendTransactionConfig {
             skip_preflight: true,
-            preflight_commitment: Some(solana_sdk::commitment_config::CommitmentLevel::Processed),
+            preflight_commitment: Some(self.config.stream_commitment()),
             encoding: None,
             max_retries: Some(0),
             min_context_slot: None,
         };
 
         let signature = self
-            .rpc_client
-            .send_transaction_with_config(&transaction, send_config)
-            .map_err(|e| SniperError::TransactionFailed(e.to_string()))?;
+            .rpc
+            .call(|client| {
+                client
+                    .send_transaction_with_config(&transaction, send_config)
+                    .map_err(|e| e.to_string())
+            })
+            .map_err(SniperError::TransactionFailed)?;
 
         info!(
-            "Buy transaction sent for {} - TX: {}",
+            "Sell transaction sent for {} - TX: {}",
             token_info.display_name(),
             signature
         );
@@ -220,26 +1382,33 @@ impl TransactionExecutor {
         sol_amount: u64,
     ) -> Result<(u64, u64), SniperError> {
         let global_account = self.fetch_global_account().await?;
-        let fee_recipient = global_account.fee_recipient;
 
         let bonding_curve_data = self
             .fetch_bonding_curve_data(&token_info.bonding_curve)
             .await?;
 
-        let expected_tokens = bonding_curve_data.get_buy_price(sol_amount)?;
+        let expected_tokens = bonding_curve_data.get_buy_price_with_fees(
+            sol_amount,
+            global_account.fee_basis_points,
+            global_account.creator_fee_basis_points,
+        )?;
 
-        let transaction = self.build_buy_transaction(
+        let (transaction, _) = self.build_buy_transaction(
             payer,
             token_info,
             &bonding_curve_data,
             sol_amount,
-            &fee_recipient,
+            &global_account,
         )?;
 
         let simulation_result = self
-            .rpc_client
-            .simulate_transaction(&transaction)
-            .map_err(|e| SniperError::TransactionFailed(e.to_string()))?;
+            .rpc
+            .call(|client| {
+                client
+                    .simulate_transaction(&transaction)
+                    .map_err(|e| e.to_string())
+            })
+            .map_err(SniperError::TransactionFailed)?;
 
         if let Some(err) = simulation_result.value.err {
             return Err(SniperError::TransactionFailed(format!(
@@ -253,3 +1422,982 @@ impl TransactionExecutor {
         Ok((expected_tokens, compute_units))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::BUY_DISCRIMINATOR;
+    use crate::utils::pda::derive_creator_vault_pda;
+    use crate::utils::test_utils::{self, test_curve, test_token, ScriptedSender};
+    use serde_json::{json, Value};
+    use solana_client::rpc_request::RpcRequest;
+    use std::sync::atomic::Ordering;
+
+    fn test_instructions(payer: &Keypair) -> BuyInstructions {
+        let executor = TransactionExecutor::new(Config::default());
+        executor
+            .build_buy_instructions(
+                payer,
+                &test_token(),
+                &test_curve(),
+                50_000_000,
+                &test_utils::test_global(),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_durable_nonce_buy() {
+        use solana_sdk::nonce::state::{Data, DurableNonce, State, Versions};
+        use solana_sdk::system_program;
+
+        let payer = Keypair::new();
+        let nonce_account = Pubkey::new_unique();
+        let nonce = DurableNonce::from_blockhash(&Hash::new_unique());
+        let state = Versions::new(State::Initialized(Data::new(payer.pubkey(), nonce, 5_000)));
+        let nonce_data = bincode::serialize(&state).unwrap();
+        let nonce_key = nonce_account.to_string();
+        let sender = ScriptedSender::with_params(move |request, params, _| {
+            if request == RpcRequest::GetAccountInfo && params[0] == nonce_key.as_str() {
+                return Ok(test_utils::account_response_owned_by(
+                    &nonce_data,
+                    &system_program::id().to_string(),
+                ));
+            }
+            test_utils::buy_response(request, params)
+        });
+        let config = Config {
+            nonce_account: Some(nonce_account),
+            ..Config::default()
+        };
+        let executor = test_utils::scripted_executor(config, sender);
+        let curve = BondingCurveAccount {
+            real_sol_reserves: 2_000_000_000,
+            ..test_curve()
+        };
+        let global = test_utils::test_global();
+
+        let (transaction, _) = executor
+            .build_buy_transaction(&payer, &test_token(), &curve, 50_000_000, &global)
+            .unwrap();
+        assert!(transaction.verify().is_ok());
+        assert_eq!(transaction.message.recent_blockhash, *nonce.as_hash());
+        let message = &transaction.message;
+        let first = &message.instructions[0];
+        assert_eq!(
+            message.account_keys[first.program_id_index as usize],
+            system_program::id()
+        );
+        let advance = system_instruction::advance_nonce_account(&nonce_account, &payer.pubkey());
+        assert_eq!(first.data, advance.data);
+        assert_eq!(
+            message.account_keys[first.accounts[0] as usize],
+            nonce_account
+        );
+
+        let (transactions, _) = executor
+            .build_buy_transactions(&payer, &test_token(), &curve, 50_000_000, &global)
+            .unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].message.recent_blockhash, *nonce.as_hash());
+
+        // only the payer signs, so it has to hold the nonce authority
+        let other = Keypair::new();
+        let err = executor
+            .build_buy_transaction(&other, &test_token(), &curve, 50_000_000, &global)
+            .unwrap_err();
+        assert!(err.to_string().contains("controlled by"), "{}", err);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_durable_buy_resent_unchanged() {
+        use solana_sdk::nonce::state::{Data, DurableNonce, State, Versions};
+        use solana_sdk::system_program;
+
+        let payer = Keypair::new();
+        let nonce_account = Pubkey::new_unique();
+        let nonce = DurableNonce::from_blockhash(&Hash::new_unique());
+        let state = Versions::new(State::Initialized(Data::new(payer.pubkey(), nonce, 5_000)));
+        let nonce_data = bincode::serialize(&state).unwrap();
+        let nonce_key = nonce_account.to_string();
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let sender = {
+            let sent = sent.clone();
+            ScriptedSender::with_params(move |request, params, _| match request {
+                RpcRequest::GetAccountInfo if params[0] == nonce_key.as_str() => {
+                    Ok(test_utils::account_response_owned_by(
+                        &nonce_data,
+                        &system_program::id().to_string(),
+                    ))
+                }
+                RpcRequest::SendTransaction => {
+                    let mut sent = sent.lock().unwrap();
+                    sent.push(params[0].clone());
+                    if sent.len() == 1 {
+                        return Err("connection reset by peer".to_string());
+                    }
+                    test_utils::buy_response(request, params)
+                }
+                _ => test_utils::buy_response(request, params),
+            })
+        };
+        let config = Config {
+            nonce_account: Some(nonce_account),
+            confirm_timeout_secs: 0,
+            fetch_retry_delays_ms: vec![0],
+            ..Config::default()
+        };
+        let executor = test_utils::scripted_executor(config, sender);
+
+        let outcome = executor
+            .execute_buy(&payer, &test_token(), 50_000_000)
+            .await
+            .unwrap();
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        // the resend is the transaction already signed, not a new one against the same nonce
+        assert_eq!(sent[0], sent[1]);
+        assert!(!outcome.confirmed);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_curve_fetches_coalesce() {
+        let data = borsh::to_vec(&test_curve()).unwrap();
+        let sender = ScriptedSender::new(move |_, _| Ok(test_utils::account_response(&data)))
+            .with_delay(Duration::from_millis(100));
+        let calls = sender.calls();
+        let executor = test_utils::scripted_executor(Config::default(), sender);
+        let bonding_curve = Pubkey::new_unique();
+
+        let (first, second) = tokio::join!(
+            executor.fetch_bonding_curve_data(&bonding_curve),
+            executor.fetch_bonding_curve_data(&bonding_curve)
+        );
+
+        first.unwrap();
+        second.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // a later fetch goes back to the backend
+        executor
+            .fetch_bonding_curve_data(&bonding_curve)
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_min_sol_output_slippage() {
+        assert_eq!(min_sol_output(1_000_000_000, 0), 1_000_000_000);
+        assert_eq!(min_sol_output(1_000_000_000, 100), 990_000_000);
+        assert_eq!(min_sol_output(1_000_000_000, 500), 950_000_000);
+        assert_eq!(min_sol_output(1_000_000_000, 10_000), 0);
+        // out-of-range slippage clamps to 100%
+        assert_eq!(min_sol_output(1_000_000_000, 20_000), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_buy_snapshot_matches_curve() {
+        let config = Config {
+            max_slippage_bps: 300,
+            ..Config::default()
+        };
+        let executor = test_utils::scripted_executor(config.clone(), test_utils::buy_sender());
+        let payer = Keypair::new();
+        let global = test_utils::test_global();
+        let curve = BondingCurveAccount {
+            real_sol_reserves: 2_000_000_000,
+            ..test_curve()
+        };
+
+        let (transaction, snapshot) = executor
+            .build_buy_transaction(&payer, &test_token(), &curve, 50_000_000, &global)
+            .unwrap();
+        assert!(transaction.verify().is_ok());
+        assert_eq!(
+            snapshot,
+            BuySnapshot {
+                virtual_sol: curve.virtual_sol_reserves,
+                virtual_token: curve.virtual_token_reserves,
+                real_sol: 2_000_000_000,
+                real_token: curve.real_token_reserves,
+                expected_tokens: curve
+                    .get_buy_price_with_fees(
+                        50_000_000,
+                        global.fee_basis_points,
+                        global.creator_fee_basis_points
+                    )
+                    .unwrap(),
+                min_tokens_out: min_token_output(snapshot.expected_tokens, 300),
+                max_sol_cost: 51_500_000,
+                slippage_bps: 300,
+                priority_fee_lamports: priority_fee_lamports(
+                    compute_unit_price_micro_lamports(
+                        config.priority_fee_sol,
+                        config.compute_unit_limit
+                    ),
+                    config.compute_unit_limit
+                ),
+            }
+        );
+
+        // the snapshot's bounds are the ones on the instruction
+        let buy_data = &transaction.message.instructions.last().unwrap().data;
+        assert_eq!(buy_data[8..16], snapshot.min_tokens_out.to_le_bytes());
+        assert_eq!(buy_data[16..24], snapshot.max_sol_cost.to_le_bytes());
+    }
+
+    #[test]
+    fn test_low_token_quote_refused() {
+        let payer = Keypair::new();
+        let build = |executor: &TransactionExecutor, sol_amount| {
+            executor.build_buy_instructions(
+                &payer,
+                &test_token(),
+                &test_curve(),
+                sol_amount,
+                &test_utils::test_global(),
+            )
+        };
+
+        // a lamport against 30 SOL of reserves rounds to nothing
+        let executor = TransactionExecutor::new(test_utils::test_config());
+        assert!(matches!(
+            build(&executor, 1),
+            Err(SniperError::InsufficientLiquidity(message))
+                if message.contains("buys 0 tokens after slippage")
+        ));
+        assert!(build(&executor, 10_000_000).is_ok());
+
+        let executor = TransactionExecutor::new(Config {
+            min_expected_tokens: 1_000_000,
+            ..test_utils::test_config()
+        });
+        assert!(matches!(
+            build(&executor, 10_000_000),
+            Err(SniperError::InsufficientLiquidity(_))
+        ));
+        assert!(build(&executor, 50_000_000).is_ok());
+
+        // a quote right at the floor falls below it once slippage is taken off
+        let fees = test_utils::test_global().fees();
+        let quote = test_curve()
+            .get_buy_price_with_fees(
+                50_000_000,
+                fees.fee_basis_points,
+                fees.creator_fee_basis_points,
+            )
+            .unwrap();
+        let executor = TransactionExecutor::new(Config {
+            min_expected_tokens: quote,
+            ..test_utils::test_config()
+        });
+        assert!(matches!(
+            build(&executor, 50_000_000),
+            Err(SniperError::InsufficientLiquidity(_))
+        ));
+    }
+
+    #[test]
+    fn test_vault_derived_from_curve_creator() {
+        let payer = Keypair::new();
+        let executor = TransactionExecutor::new(Config::default());
+        let token = test_token();
+        let mut curve = test_curve();
+        assert_ne!(curve.creator, token.creator);
+        let vault_of = |creator: &Pubkey| derive_creator_vault_pda(creator).unwrap();
+        // creator vault is the 10th buy account
+        let buy_vault = |curve: &BondingCurveAccount| {
+            executor
+                .build_buy_instructions(
+                    &payer,
+                    &token,
+                    curve,
+                    50_000_000,
+                    &test_utils::test_global(),
+                )
+                .unwrap()
+                .buy
+                .accounts[9]
+                .pubkey
+        };
+
+        assert_eq!(vault_creator(&token, &curve), curve.creator);
+        assert_eq!(buy_vault(&curve), vault_of(&curve.creator));
+        assert_ne!(buy_vault(&curve), vault_of(&token.creator));
+
+        // a curve without a creator keeps the instruction's
+        curve.creator = Pubkey::default();
+        assert_eq!(vault_creator(&token, &curve), token.creator);
+        assert_eq!(buy_vault(&curve), vault_of(&token.creator));
+    }
+
+    #[test]
+    fn test_buy_bounds_widen_with_slippage() {
+        let payer = Keypair::new();
+        let (token, curve, global) = (test_token(), test_curve(), test_utils::test_global());
+        let sol_amount = 50_000_000;
+        let quote = curve
+            .get_buy_price_with_fees(
+                sol_amount,
+                global.fee_basis_points,
+                global.creator_fee_basis_points,
+            )
+            .unwrap();
+
+        let bounds: Vec<(u64, u64)> = [0, 100, 500, 1_000]
+            .into_iter()
+            .map(|max_slippage_bps| {
+                let config = Config {
+                    max_slippage_bps,
+                    ..Config::default()
+                };
+                let buy = TransactionExecutor::new(config)
+                    .build_buy_instructions(&payer, &token, &curve, sol_amount, &global)
+                    .unwrap()
+                    .buy;
+                let amount = u64::from_le_bytes(buy.data[8..16].try_into().unwrap());
+                let max_cost = u64::from_le_bytes(buy.data[16..24].try_into().unwrap());
+                (amount, max_cost)
+            })
+            .collect();
+
+        // no tolerance: exactly the quote for at most the SOL in
+        assert_eq!(bounds[0], (quote, sol_amount));
+        assert_eq!(bounds[2], (quote * 95 / 100, sol_amount * 105 / 100));
+        for pair in bounds.windows(2) {
+            assert!(pair[1].0 < pair[0].0, "token floor drops");
+            assert!(pair[1].1 > pair[0].1, "SOL cap rises");
+        }
+
+        assert_eq!(min_token_output(1_000, 10_000), 0);
+        assert_eq!(min_token_output(1_000, 20_000), 0);
+    }
+
+    #[test]
+    fn test_fee_percentile() {
+        let fees = [500, 0, 100, 2_000, 300, 10_000, 700, 1_000];
+        assert_eq!(fee_percentile(&fees, 0), Some(0));
+        assert_eq!(fee_percentile(&fees, 50), Some(500));
+        assert_eq!(fee_percentile(&fees, 75), Some(1_000));
+        assert_eq!(fee_percentile(&fees, 90), Some(10_000));
+        assert_eq!(fee_percentile(&fees, 100), Some(10_000));
+        assert_eq!(fee_percentile(&[42], 75), Some(42));
+        assert_eq!(fee_percentile(&[], 75), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dynamic_priority_fee_from_recent_fees() {
+        let config = Config {
+            dynamic_priority_fee: true,
+            priority_fee_percentile: 75,
+            min_priority_fee: 200,
+            max_priority_fee: 5_000,
+            ..Config::default()
+        };
+        let accounts = [Pubkey::new_unique()];
+        let static_price =
+            compute_unit_price_micro_lamports(config.priority_fee_sol, config.compute_unit_limit);
+        let with_fees = |fees: Vec<u64>| {
+            let sender = ScriptedSender::new(move |request, _| match request {
+                RpcRequest::GetRecentPrioritizationFees => Ok(json!(fees
+                    .iter()
+                    .enumerate()
+                    .map(|(slot, fee)| json!({"slot": slot, "prioritizationFee": fee}))
+                    .collect::<Vec<_>>())),
+                other => Err(format!("unexpected {other}")),
+            });
+            test_utils::scripted_executor(config.clone(), sender)
+        };
+
+        let executor = with_fees(vec![100, 400, 800, 1_200]);
+        assert_eq!(
+            executor.compute_unit_price(config.compute_unit_limit, &accounts),
+            800
+        );
+        // reused within the cache ttl, even for other accounts
+        *executor.recent_unit_price.lock().unwrap() = Some((Instant::now(), 1_000));
+        assert_eq!(
+            executor.compute_unit_price(config.compute_unit_limit, &[Pubkey::new_unique()]),
+            1_000
+        );
+        *executor.recent_unit_price.lock().unwrap() =
+            Some((Instant::now() - PRIORITY_FEE_CACHE_TTL, 1_000));
+        assert_eq!(
+            executor.compute_unit_price(config.compute_unit_limit, &accounts),
+            800
+        );
+        // clamped to the bounds
+        let executor = with_fees(vec![0, 0, 0, 10]);
+        assert_eq!(
+            executor.compute_unit_price(config.compute_unit_limit, &accounts),
+            200
+        );
+        let executor = with_fees(vec![9_000; 4]);
+        assert_eq!(
+            executor.compute_unit_price(config.compute_unit_limit, &accounts),
+            5_000
+        );
+        // nothing recent falls back to the static fee
+        let executor = with_fees(Vec::new());
+        assert_eq!(
+            executor.compute_unit_price(config.compute_unit_limit, &accounts),
+            static_price
+        );
+
+        // a failed lookup falls back to the static fee
+        let failing = test_utils::scripted_executor(
+            config.clone(),
+            ScriptedSender::new(|_, _| Err("rpc down".to_string())),
+        );
+        assert_eq!(
+            failing.compute_unit_price(config.compute_unit_limit, &accounts),
+            static_price
+        );
+    }
+
+    #[test]
+    fn test_priority_fee_spends_configured_budget() {
+        // total fee the runtime charges for a unit price, rounded up
+        let total_fee =
+            |price: u64, limit: u32| (price as u128 * limit as u128).div_ceil(1_000_000);
+
+        for (budget, limit) in [(5_000_000, 200_000), (100_000, 75_000), (500_000, 70_000)] {
+            let price = compute_unit_price_micro_lamports(budget, limit);
+            assert_eq!(
+                total_fee(price, limit),
+                budget as u128,
+                "{budget} over {limit}"
+            );
+        }
+
+        assert_eq!(
+            compute_unit_price_micro_lamports(5_000_000, 200_000),
+            25_000_000
+        );
+        assert_eq!(
+            compute_unit_price_micro_lamports(u64::MAX, 1),
+            MAX_COMPUTE_UNIT_PRICE_MICRO_LAMPORTS
+        );
+        assert_eq!(compute_unit_price_micro_lamports(1_000, 0), 0);
+
+        let payer = Keypair::new();
+        let instructions = test_instructions(&payer);
+        assert_eq!(
+            instructions.compute_budget[0],
+            ComputeBudgetInstruction::set_compute_unit_price(25_000_000)
+        );
+    }
+
+    #[test]
+    fn test_total_cost_estimate() {
+        // no slippage, fees or rent: just the amount
+        assert_eq!(total_cost_estimate(50_000_000, 0, 0, 0, 0), 50_000_000);
+        // default config buy: 5% slippage, 0.005 SOL priority, one signature, new ATA
+        assert_eq!(
+            total_cost_estimate(50_000_000, 500, 5_000_000, 5_000, 2_039_280),
+            50_000_000 + 2_500_000 + 5_000_000 + 5_000 + 2_039_280
+        );
+        // accounting for an existing ATA skips rent
+        assert_eq!(
+            total_cost_estimate(1_000_000_000, 1_000, 100_000, 5_000, 0),
+            1_100_105_000
+        );
+        assert_eq!(total_cost_estimate(u64::MAX, 500, 1, 1, 1), u64::MAX);
+        assert_eq!(max_sol_cost(1_000_000_000, 250), 1_025_000_000);
+    }
+
+    #[test]
+    fn test_buy_amount_from_balance() {
+        let reserve = 5_000_000 + 5_000 + 2_039_280;
+        // 10% of 10 SOL, well clear of the reserve
+        assert_eq!(
+            buy_amount_from_balance(10_000_000_000, 10.0, 500, reserve),
+            Some(1_000_000_000)
+        );
+        // all of it: what's left after the reserve, less room for 5% slippage
+        let all = buy_amount_from_balance(1_000_000_000, 100.0, 500, reserve).unwrap();
+        assert_eq!(all, (1_000_000_000 - reserve) * 10_000 / 10_500);
+        assert!(total_cost_estimate(all, 500, 5_000_000, 5_000, 2_039_280) <= 1_000_000_000);
+        // nothing to spend once the reserve eats the balance
+        assert_eq!(buy_amount_from_balance(reserve, 50.0, 500, reserve), None);
+        assert_eq!(buy_amount_from_balance(1_000, 50.0, 0, reserve), None);
+    }
+
+    #[test]
+    fn test_sell_rejects_complete_curve() {
+        let payer = Keypair::new();
+        let executor = TransactionExecutor::new(Config::default());
+        let mut curve = test_curve();
+        curve.complete = true;
+        let global: GlobalAccount = solana_sdk::borsh1::try_from_slice_unchecked(&[0u8; 512])
+            .expect("zeroed global account");
+
+        let result = executor.build_sell_transaction(&payer, &test_token(), &curve, 1_000, &global);
+        assert!(matches!(result, Err(SniperError::BondingCurveComplete)));
+    }
+
+    #[test]
+    fn test_buy_fits_in_one_transaction() {
+        let payer = Keypair::new();
+        let instructions = test_instructions(&payer);
+
+        let transactions =
+            pack_buy_transactions(&payer, &instructions, Hash::default(), MAX_TRANSACTION_SIZE)
+                .unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].message.instructions.len(), 4);
+    }
+
+    #[test]
+    fn test_oversized_buy_splits_ata_creation() {
+        let payer = Keypair::new();
+        let instructions = test_instructions(&payer);
+        let combined =
+            pack_buy_transactions(&payer, &instructions, Hash::default(), usize::MAX).unwrap();
+        let limit = transaction_size(&combined[0]) - 1;
+
+        let transactions =
+            pack_buy_transactions(&payer, &instructions, Hash::default(), limit).unwrap();
+        assert_eq!(transactions.len(), 2);
+
+        let program_of = |tx: &Transaction, index: usize| {
+            let ix = &tx.message.instructions[index];
+            tx.message.account_keys[ix.program_id_index as usize]
+        };
+        // setup is the ATA creation alone, the compute budget goes with the buy
+        assert_eq!(transactions[0].message.instructions.len(), 1);
+        assert_eq!(
+            program_of(&transactions[0], 0),
+            spl_associated_token_account::id()
+        );
+        assert_eq!(
+            program_of(&transactions[1], 2),
+            crate::constants::accounts::pumpfun_program_id()
+        );
+        assert!(transactions.iter().all(|tx| transaction_size(tx) <= limit));
+
+        // a buy too big even on its own is refused rather than sent oversized
+        let buy_size = transaction_size(&transactions[1]);
+        assert!(matches!(
+            pack_buy_transactions(&payer, &instructions, Hash::default(), buy_size - 1),
+            Err(SniperError::TransactionFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_ata_creation_is_idempotent() {
+        let payer = Keypair::new();
+        let token = test_token();
+        let executor = TransactionExecutor::new(Config::default());
+        let expected =
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &payer.pubkey(),
+                &payer.pubkey(),
+                &token.mint,
+                &spl_token::id(),
+            );
+
+        // same mint twice, as on a re-buy
+        for _ in 0..2 {
+            let instructions = executor
+                .build_buy_instructions(
+                    &payer,
+                    &token,
+                    &test_curve(),
+                    50_000_000,
+                    &test_utils::test_global(),
+                )
+                .unwrap();
+            assert_eq!(instructions.create_ata, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_global_account_fetch_retries() {
+        let data = vec![0u8; 512];
+        let sender = ScriptedSender::new(move |request, call| match (request, call) {
+            (RpcRequest::GetAccountInfo, 0) => Err("connection reset".to_string()),
+            (RpcRequest::GetAccountInfo, _) => Ok(test_utils::account_response(&data)),
+            (other, _) => Err(format!("unexpected {other}")),
+        });
+        let calls = sender.calls();
+        let config = Config {
+            fetch_retry_delays_ms: vec![0, 10, 10],
+            ..Config::default()
+        };
+        let executor = test_utils::scripted_executor(config, sender);
+
+        executor.fetch_global_account().await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_short_accounts_rejected() {
+        let sender = ScriptedSender::new(|_, _| Ok(test_utils::account_response(&[0u8; 40])));
+        let executor = test_utils::scripted_executor(Config::default(), sender);
+
+        let err = executor.fetch_global_account().await.unwrap_err();
+        assert!(matches!(err, SniperError::SerializationError(_)));
+        assert!(
+            err.to_string().contains("Global account is 40 bytes"),
+            "{}",
+            err
+        );
+
+        let err = executor
+            .fetch_bonding_curve_data(&Pubkey::new_unique())
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Bonding curve account is 40 bytes, expected at least 81"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_global_account_fetch_gives_up_after_delays() {
+        let sender = ScriptedSender::new(|_, _| Err("connection reset".to_string()));
+        let calls = sender.calls();
+        let config = Config {
+            fetch_retry_delays_ms: vec![0, 10],
+            ..Config::default()
+        };
+        let executor = test_utils::scripted_executor(config, sender);
+
+        let result = executor.fetch_global_account().await;
+        assert!(matches!(result, Err(SniperError::RpcError(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_mint_authorities_decode() {
+        let pack = |mint_authority: Option<Pubkey>, freeze_authority: Option<Pubkey>| {
+            let mint = spl_token::state::Mint {
+                mint_authority: mint_authority.into(),
+                supply: 1_000_000_000,
+                decimals: 6,
+                is_initialized: true,
+                freeze_authority: freeze_authority.into(),
+            };
+            let mut data = vec![0u8; spl_token::state::Mint::LEN];
+            spl_token::state::Mint::pack(mint, &mut data).unwrap();
+            data
+        };
+
+        assert_eq!(mint_authorities(&pack(None, None)).unwrap(), (None, None));
+
+        let authority = Pubkey::new_unique();
+        assert_eq!(
+            mint_authorities(&pack(Some(authority), None)).unwrap(),
+            (Some(authority), None)
+        );
+        assert_eq!(
+            mint_authorities(&pack(None, Some(authority))).unwrap(),
+            (None, Some(authority))
+        );
+
+        assert!(mint_authorities(&[0u8; 10]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failover_to_second_endpoint() {
+        let global = vec![0u8; 512];
+        let down = ScriptedSender::new(|_, _| Err("endpoint down".to_string()));
+        let up = ScriptedSender::new(move |_, _| Ok(test_utils::account_response(&global)));
+        let (down_calls, up_calls) = (down.calls(), up.calls());
+        let executor = TransactionExecutor::with_rpc_pool(
+            RpcPool::new(vec![
+                test_utils::scripted_client(down),
+                test_utils::scripted_client(up),
+            ]),
+            Config::default(),
+        );
+
+        executor.fetch_global_account().await.unwrap();
+        executor.fetch_global_account().await.unwrap();
+
+        // first call fails over, second starts at the next endpoint round-robin
+        assert_eq!(down_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(up_calls.load(Ordering::SeqCst), 2);
+    }
+
+    fn signature_status(status: Value) -> Value {
+        json!({ "context": { "slot": 1 }, "value": [status] })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_confirm_signature_outcomes() {
+        let signature = Signature::default();
+
+        // pending, then confirmed
+        let sender = ScriptedSender::new(|_, call| {
+            Ok(signature_status(if call == 0 {
+                Value::Null
+            } else {
+                json!({
+                    "slot": 42,
+                    "confirmations": 0,
+                    "status": { "Ok": null },
+                    "err": null,
+                    "confirmationStatus": "confirmed",
+                })
+            }))
+        });
+        let executor = test_utils::scripted_executor(Config::default(), sender);
+        let slot = executor
+            .confirm_signature(&signature, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(slot, Some(42));
+
+        // landed but reverted
+        let sender = ScriptedSender::new(|_, _| {
+            Ok(signature_status(json!({
+                "slot": 42,
+                "confirmations": 0,
+                "status": { "Err": "AccountInUse" },
+                "err": "AccountInUse",
+                "confirmationStatus": "confirmed",
+            })))
+        });
+        let executor = test_utils::scripted_executor(Config::default(), sender);
+        let result = executor
+            .confirm_signature(&signature, Duration::from_secs(5))
+            .await;
+        assert!(matches!(result, Err(SniperError::TransactionFailed(_))));
+
+        // never seen
+        let sender = ScriptedSender::new(|_, _| Ok(signature_status(Value::Null)));
+        let executor = test_utils::scripted_executor(Config::default(), sender);
+        let slot = executor
+            .confirm_signature(&signature, Duration::from_millis(600))
+            .await
+            .unwrap();
+        assert_eq!(slot, None);
+    }
+
+    #[test]
+    fn test_amm_buy_instructions() {
+        let executor = TransactionExecutor::new(Config::default());
+        let payer = Keypair::new();
+        let mint = Pubkey::new_unique();
+        let wsol = crate::constants::accounts::wsol_mint();
+        let pool_address = derive_pump_swap_pool_pda(&mint).unwrap();
+        let pool = PumpSwapPool {
+            discriminator: 0,
+            pool_bump: 255,
+            index: 0,
+            creator: Pubkey::new_unique(),
+            base_mint: mint,
+            quote_mint: wsol,
+            lp_mint: Pubkey::new_unique(),
+            pool_base_token_account: Pubkey::new_unique(),
+            pool_quote_token_account: Pubkey::new_unique(),
+            lp_supply: 0,
+            coin_creator: Pubkey::new_unique(),
+        };
+        let mut global_config = PumpSwapGlobalConfig {
+            discriminator: 0,
+            admin: Pubkey::new_unique(),
+            lp_fee_basis_points: 20,
+            protocol_fee_basis_points: 5,
+            disable_flags: 0,
+            protocol_fee_recipients: [Pubkey::default(); 8],
+            coin_creator_fee_basis_points: 5,
+        };
+        global_config.protocol_fee_recipients[1] = Pubkey::new_unique();
+
+        // 1 SOL into 200M tokens / 80 SOL, 0.3% fee
+        let sol_amount = 1_000_000_000;
+        let (base_reserve, quote_reserve) = (200_000_000_000_000, 80_000_000_000);
+        let expected_out = amm_buy_quote(sol_amount, base_reserve, quote_reserve, 30);
+        assert_eq!(expected_out, 2_461_841_457_213);
+
+        let instructions = executor
+            .build_amm_buy_instructions(
+                &payer,
+                &pool_address,
+                &pool,
+                &global_config,
+                base_reserve,
+                quote_reserve,
+                sol_amount,
+            )
+            .unwrap();
+        // budget x2, base ATA, WSOL ATA, wrap, sync, swap, unwrap
+        assert_eq!(instructions.len(), 8);
+
+        let swap = &instructions[6];
+        assert_eq!(
+            swap.program_id,
+            crate::constants::accounts::pumpswap_program_id()
+        );
+        let max_quote = max_sol_cost(sol_amount, Config::default().max_slippage_bps);
+        let mut data = BUY_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&expected_out.to_le_bytes());
+        data.extend_from_slice(&max_quote.to_le_bytes());
+        assert_eq!(swap.data, data);
+
+        assert_eq!(swap.accounts.len(), 19);
+        assert_eq!(swap.accounts[0].pubkey, pool_address);
+        assert!(swap.accounts[1].pubkey == payer.pubkey() && swap.accounts[1].is_signer);
+        assert_eq!(swap.accounts[3].pubkey, mint);
+        assert_eq!(swap.accounts[4].pubkey, wsol);
+        assert_eq!(
+            swap.accounts[6].pubkey,
+            spl_associated_token_account::get_associated_token_address(&payer.pubkey(), &wsol)
+        );
+        assert_eq!(swap.accounts[7].pubkey, pool.pool_base_token_account);
+        assert_eq!(
+            swap.accounts[9].pubkey,
+            global_config.protocol_fee_recipients[1]
+        );
+
+        // empty pool can't fill
+        let result = executor.build_amm_buy_instructions(
+            &payer,
+            &pool_address,
+            &pool,
+            &global_config,
+            0,
+            quote_reserve,
+            sol_amount,
+        );
+        assert!(matches!(result, Err(SniperError::InsufficientLiquidity(_))));
+
+        // nor can one short of the configured floor
+        let floored = TransactionExecutor::new(Config {
+            min_expected_tokens: expected_out + 1,
+            ..Config::default()
+        });
+        let result = floored.build_amm_buy_instructions(
+            &payer,
+            &pool_address,
+            &pool,
+            &global_config,
+            base_reserve,
+            quote_reserve,
+            sol_amount,
+        );
+        assert!(matches!(result, Err(SniperError::InsufficientLiquidity(_))));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_creator_holding_pct() {
+        // 1B token supply with 6 decimals
+        let supply = 1_000_000_000_000_000;
+        assert_eq!(holding_pct(0, supply), 0.0);
+        assert!((holding_pct(35_000_000_000_000, supply) - 3.5).abs() < 1e-9);
+        assert_eq!(holding_pct(supply, supply), 100.0);
+        assert_eq!(holding_pct(1, 0), 0.0);
+
+        // no ATA yet reads as nothing held
+        let sender =
+            ScriptedSender::new(|_, _| Ok(json!({ "context": { "slot": 1 }, "value": null })));
+        let executor = test_utils::scripted_executor(Config::default(), sender);
+        let token = test_token();
+        assert_eq!(
+            executor
+                .fetch_creator_holdings(&token.creator, &token.mint)
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_shared_rpc_client() {
+        let client = Arc::new(test_utils::scripted_client(ScriptedSender::new(|_, _| {
+            Ok(serde_json::json!(7))
+        })));
+        let first = TransactionExecutor::with_rpc_client(client.clone(), Config::default());
+        let second = TransactionExecutor::with_rpc_client(client.clone(), Config::default());
+        assert!(Arc::ptr_eq(&first.rpc_client(), &client));
+        assert!(Arc::ptr_eq(&second.rpc_client(), &client));
+        assert_eq!(first.rpc.len(), 1);
+
+        // fallbacks are added behind the shared client
+        let config = Config {
+            rpc_endpoints: vec![client.url(), "http://127.0.0.1:8899".to_string()],
+            ..Config::default()
+        };
+        let with_fallback = TransactionExecutor::with_rpc_client(client.clone(), config);
+        assert_eq!(with_fallback.rpc.len(), 2);
+        assert!(Arc::ptr_eq(&with_fallback.rpc_client(), &client));
+    }
+
+    #[test]
+    fn test_fee_recipient_rotates_through_array() {
+        let executor = TransactionExecutor::new(Config::default());
+        let mut global = test_utils::test_global();
+        global.fee_recipients = [Pubkey::default(); 7];
+        let alternates = [Pubkey::new_unique(), Pubkey::new_unique()];
+        global.fee_recipients[2] = alternates[0];
+        global.fee_recipients[5] = alternates[1];
+
+        let picks: Vec<Pubkey> = (0..4)
+            .map(|_| executor.select_fee_recipient(&global).unwrap())
+            .collect();
+        assert_eq!(
+            picks,
+            [alternates[0], alternates[1], alternates[0], alternates[1]]
+        );
+        assert!(!picks.contains(&global.fee_recipient));
+
+        // the buy instruction carries the pick
+        let payer = Keypair::new();
+        let token = test_utils::test_token();
+        let instructions = executor
+            .build_buy_instructions(
+                &payer,
+                &token,
+                &test_utils::test_curve(),
+                1_000_000,
+                &global,
+            )
+            .unwrap();
+        assert_eq!(instructions.buy.accounts[1].pubkey, alternates[0]);
+
+        // empty array falls back to the singular recipient
+        global.fee_recipients = [Pubkey::default(); 7];
+        assert_eq!(
+            executor.select_fee_recipient(&global).unwrap(),
+            global.fee_recipient
+        );
+    }
+
+    #[test]
+    fn test_fee_recipient_validation() {
+        let config = Config {
+            validate_fee_recipient: true,
+            ..Config::default()
+        };
+        let executor = TransactionExecutor::new(config);
+        let mut global = test_utils::test_global();
+
+        // not the primary nor among the alternates
+        assert!(matches!(
+            executor.fee_recipient(&global),
+            Err(SniperError::TransactionFailed(_))
+        ));
+
+        global.fee_recipient = global.fee_recipients[3];
+        assert_eq!(
+            executor.fee_recipient(&global).unwrap(),
+            global.fee_recipients[3]
+        );
+        global.fee_recipient = crate::constants::accounts::fee_recipient();
+        assert!(executor.fee_recipient(&global).is_ok());
+
+        // unchecked when disabled
+        let unchecked = TransactionExecutor::new(Config::default());
+        global.fee_recipient = Pubkey::new_unique();
+        assert_eq!(
+            unchecked.fee_recipient(&global).unwrap(),
+            global.fee_recipient
+        );
+    }
+}
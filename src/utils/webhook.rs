@@ -0,0 +1,249 @@
+//! Webhook alerts for critical events
+
+use crate::common::SniperEvent;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+/// Longest a webhook POST may take before it's abandoned
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// POSTs Discord/Slack-style `{"content": "..."}` messages for critical events and
+/// stream connection changes. Each POST runs on its own task, so a slow or failing
+/// webhook never holds up the sniper
+#[derive(Clone)]
+pub struct WebhookNotifier {
+    client: Arc<reqwest::Client>,
+    url: String,
+    timeout: Duration,
+}
+
+impl WebhookNotifier {
+    pub fn new(client: Arc<reqwest::Client>, url: impl Into<String>) -> Self {
+        Self {
+            client,
+            url: url.into(),
+            timeout: WEBHOOK_TIMEOUT,
+        }
+    }
+
+    /// Whether `event` is posted: the critical events, plus stream connection changes
+    pub fn alerts(event: &SniperEvent) -> bool {
+        event.is_critical() || matches!(event, SniperEvent::ConnectionStatusChanged { .. })
+    }
+
+    /// Message posted for `event`, None for events that don't alert
+    pub fn message(event: &SniperEvent) -> Option<String> {
+        if !Self::alerts(event) {
+            return None;
+        }
+        let sol = |lamports: u64| lamports as f64 / 1e9;
+        let message = match event {
+            SniperEvent::BuyTriggered {
+                token_info,
+                market_cap,
+                buy_amount,
+                trigger_source,
+                ..
+            } => format!(
+                "Buy triggered: {} ({}) at {:.2} SOL market cap, buying {:.4} SOL ({})",
+                token_info.symbol,
+                token_info.mint,
+                sol(*market_cap),
+                sol(*buy_amount),
+                trigger_source.as_str()
+            ),
+            SniperEvent::BuyExecuted {
+                token_info,
+                transaction_signature,
+                amount_spent,
+                tokens_received,
+                ..
+            } => format!(
+                "Bought {} ({}): {:.4} SOL for {} tokens, tx {}",
+                token_info.symbol,
+                token_info.mint,
+                sol(*amount_spent),
+                tokens_received,
+                transaction_signature
+            ),
+            SniperEvent::SellTriggered {
+                token_info,
+                token_amount,
+                expected_sol_output,
+                reason,
+            } => format!(
+                "Selling {} ({}) on {}: {} tokens for ~{:.4} SOL",
+                token_info.symbol,
+                token_info.mint,
+                reason.as_str(),
+                token_amount,
+                sol(*expected_sol_output)
+            ),
+            SniperEvent::BuyFailed {
+                token_info,
+                error,
+                retry_count,
+            } => format!(
+                "Buy failed for {} ({}) after {} retries: {}",
+                token_info.symbol, token_info.mint, retry_count, error
+            ),
+            SniperEvent::SpendLimitReached {
+                token_info,
+                spent,
+                limit,
+            } => format!(
+                "Spend limit reached: skipped {} ({}), {:.4} of {:.4} SOL spent",
+                token_info.symbol,
+                token_info.mint,
+                sol(*spent),
+                sol(*limit)
+            ),
+            // the endpoint can carry an API key, so it stays out of the message
+            SniperEvent::ConnectionStatusChanged { connected, .. } => if *connected {
+                "Stream connected"
+            } else {
+                "Stream disconnected"
+            }
+            .to_string(),
+            // a critical event without its own wording still alerts
+            _ => format!("Sniper event: {}", event.event_type()),
+        };
+        Some(message)
+    }
+
+    /// POST `event` in the background if it alerts
+    pub fn notify(&self, event: &SniperEvent) {
+        let Some(content) = Self::message(event) else {
+            return;
+        };
+        let notifier = self.clone();
+        let event_type = event.event_type();
+        tokio::spawn(async move {
+            if let Err(e) = notifier.post(&content).await {
+                warn!("Webhook for {} failed: {}", event_type, e);
+            }
+        });
+    }
+
+    async fn post(&self, content: &str) -> Result<(), reqwest::Error> {
+        self.client
+            .post(&self.url)
+            .timeout(self.timeout)
+            .json(&json!({ "content": content }))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            // the URL is a secret, keep it out of logs
+            .map_err(|e| e.without_url())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    /// Answer every request with `status`, passing each request body on
+    async fn serve(status: &'static str) -> (String, mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (bodies, received) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 4096];
+                // read until the headers and the whole body are in
+                let body = loop {
+                    let read = stream.read(&mut buffer).await.unwrap_or(0);
+                    if read == 0 {
+                        break None;
+                    }
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    let Some((headers, body)) = text.split_once("\r\n\r\n") else {
+                        continue;
+                    };
+                    let length = headers
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        break Some(body.to_string());
+                    }
+                };
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+                if let Some(body) = body {
+                    let _ = bodies.send(body);
+                }
+            }
+        });
+        (format!("http://{}/hook", addr), received)
+    }
+
+    fn buy_failed() -> SniperEvent {
+        SniperEvent::BuyFailed {
+            token_info: test_utils::test_token(),
+            error: "blockhash not found".to_string(),
+            retry_count: 2,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_webhook_posts_content() {
+        let (url, mut received) = serve("204 No Content").await;
+        let notifier = WebhookNotifier::new(Arc::new(reqwest::Client::new()), url);
+
+        notifier.notify(&buy_failed());
+        let body: serde_json::Value =
+            serde_json::from_str(&received.recv().await.unwrap()).unwrap();
+        let content = body["content"].as_str().unwrap();
+        assert!(content.starts_with("Buy failed for"), "{}", content);
+        assert!(content.contains("blockhash not found"), "{}", content);
+
+        notifier.notify(&SniperEvent::ConnectionStatusChanged {
+            connected: false,
+            endpoint: "https://grpc.example/?x-token=secret".to_string(),
+        });
+        let body = received.recv().await.unwrap();
+        assert!(body.contains("Stream disconnected"), "{}", body);
+        assert!(!body.contains("secret"), "{}", body);
+
+        // routine events don't alert
+        let created = SniperEvent::TokenCreated(test_utils::test_token());
+        assert!(!created.is_critical() && WebhookNotifier::message(&created).is_none());
+        notifier.notify(&created);
+        notifier.notify(&buy_failed());
+        let body = received.recv().await.unwrap();
+        assert!(body.contains("Buy failed for"), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_failure_hides_url() {
+        let (url, _received) = serve("500 Internal Server Error").await;
+        let notifier = WebhookNotifier::new(Arc::new(reqwest::Client::new()), url.clone());
+
+        let error = notifier.post("hello").await.unwrap_err().to_string();
+        assert!(error.contains("500"), "{}", error);
+        assert!(!error.contains(&url), "{}", error);
+
+        // nothing listening
+        let closed =
+            WebhookNotifier::new(Arc::new(reqwest::Client::new()), "http://127.0.0.1:1/hook");
+        let error = closed.post("hello").await.unwrap_err().to_string();
+        assert!(!error.contains("127.0.0.1:1"), "{}", error);
+    }
+}